@@ -0,0 +1,2 @@
+void __tci_assert(int condition);
+#define assert(condition) __tci_assert(condition)
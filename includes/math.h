@@ -0,0 +1,12 @@
+#define M_PI 3.14159265358979323846
+#define M_E 2.7182818284590452354
+
+double sqrt(double x);
+double pow(double base, double exponent);
+double fabs(double x);
+double floor(double x);
+double ceil(double x);
+double sin(double x);
+double cos(double x);
+double log(double x);
+double exp(double x);
@@ -1 +1,26 @@
+typedef unsigned long size_t;
+
+typedef struct __tci_file {
+  int __fd;
+} FILE;
+
+#define EOF (-1)
+
+FILE *__tci_stream(int fd);
+#define stdin (__tci_stream(0))
+#define stdout (__tci_stream(1))
+#define stderr (__tci_stream(2))
+
 int printf(char* format, ...);
+int scanf(char* format, ...);
+int getchar();
+char *fgets(char *s, int size, FILE *stream);
+
+FILE *fopen(char *path, char *mode);
+int fclose(FILE *stream);
+int fgetc(FILE *stream);
+int fputc(int c, FILE *stream);
+int fprintf(FILE *stream, char *format, ...);
+int fscanf(FILE *stream, char *format, ...);
+size_t fread(void *ptr, size_t size, size_t count, FILE *stream);
+size_t fwrite(void *ptr, size_t size, size_t count, FILE *stream);
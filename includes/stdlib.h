@@ -1,5 +1,24 @@
 typedef unsigned long size_t;
 
+#define RAND_MAX 32767
+
 void *malloc(size_t size);
+void *calloc(size_t count, size_t size);
 void *realloc(void *buffer, size_t new_size);
 void free(void *value);
+void exit(int status);
+int atexit(void *func);
+
+int atoi(char *s);
+long atol(char *s);
+long strtol(char *s, char **end, int base);
+unsigned long strtoul(char *s, char **end, int base);
+
+int abs(int value);
+long labs(long value);
+
+int rand();
+void srand(int seed);
+
+void qsort(void *base, size_t count, size_t size, void *compare);
+void *bsearch(void *key, void *base, size_t count, size_t size, void *compare);
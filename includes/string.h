@@ -2,3 +2,18 @@
 typedef unsigned long size_t;
 
 void *memcpy(void *dest, void *src, size_t n);
+void *memmove(void *dest, void *src, size_t n);
+void *memset(void *dest, int c, size_t n);
+int memcmp(void *a, void *b, size_t n);
+
+size_t strlen(char *s);
+char *strcpy(char *dest, char *src);
+char *strncpy(char *dest, char *src, size_t n);
+char *strcat(char *dest, char *src);
+char *strncat(char *dest, char *src, size_t n);
+int strcmp(char *a, char *b);
+int strncmp(char *a, char *b, size_t n);
+char *strchr(char *s, int c);
+char *strrchr(char *s, int c);
+char *strstr(char *haystack, char *needle);
+char *strdup(char *s);
@@ -24,6 +24,66 @@ pub static LIB_FUNCS: LazyStatic<HashSet<u32>> = lazy_static!(lib_funcs, HashSet
     m.insert(INIT_SYMS.translate["free"]);
     m.insert(INIT_SYMS.translate["realloc"]);
     m.insert(INIT_SYMS.translate["memcpy"]);
+    m.insert(INIT_SYMS.translate["strlen"]);
+    m.insert(INIT_SYMS.translate["strcpy"]);
+    m.insert(INIT_SYMS.translate["strncpy"]);
+    m.insert(INIT_SYMS.translate["strcat"]);
+    m.insert(INIT_SYMS.translate["strncat"]);
+    m.insert(INIT_SYMS.translate["strcmp"]);
+    m.insert(INIT_SYMS.translate["strncmp"]);
+    m.insert(INIT_SYMS.translate["strchr"]);
+    m.insert(INIT_SYMS.translate["strrchr"]);
+    m.insert(INIT_SYMS.translate["strstr"]);
+    m.insert(INIT_SYMS.translate["strdup"]);
+    m.insert(INIT_SYMS.translate["memset"]);
+    m.insert(INIT_SYMS.translate["memmove"]);
+    m.insert(INIT_SYMS.translate["memcmp"]);
+    m.insert(INIT_SYMS.translate["__tci_stream"]);
+    m.insert(INIT_SYMS.translate["getchar"]);
+    m.insert(INIT_SYMS.translate["fgets"]);
+    m.insert(INIT_SYMS.translate["scanf"]);
+    m.insert(INIT_SYMS.translate["fopen"]);
+    m.insert(INIT_SYMS.translate["fclose"]);
+    m.insert(INIT_SYMS.translate["fgetc"]);
+    m.insert(INIT_SYMS.translate["fputc"]);
+    m.insert(INIT_SYMS.translate["fprintf"]);
+    m.insert(INIT_SYMS.translate["fscanf"]);
+    m.insert(INIT_SYMS.translate["fread"]);
+    m.insert(INIT_SYMS.translate["fwrite"]);
+    m.insert(INIT_SYMS.translate["calloc"]);
+    m.insert(INIT_SYMS.translate["atoi"]);
+    m.insert(INIT_SYMS.translate["atol"]);
+    m.insert(INIT_SYMS.translate["strtol"]);
+    m.insert(INIT_SYMS.translate["strtoul"]);
+    m.insert(INIT_SYMS.translate["abs"]);
+    m.insert(INIT_SYMS.translate["labs"]);
+    m.insert(INIT_SYMS.translate["rand"]);
+    m.insert(INIT_SYMS.translate["srand"]);
+    m.insert(INIT_SYMS.translate["atexit"]);
+    m.insert(INIT_SYMS.translate["qsort"]);
+    m.insert(INIT_SYMS.translate["bsearch"]);
+    m.insert(INIT_SYMS.translate["__tci_assert"]);
+    m.insert(INIT_SYMS.translate["isalnum"]);
+    m.insert(INIT_SYMS.translate["isalpha"]);
+    m.insert(INIT_SYMS.translate["isdigit"]);
+    m.insert(INIT_SYMS.translate["isxdigit"]);
+    m.insert(INIT_SYMS.translate["islower"]);
+    m.insert(INIT_SYMS.translate["isupper"]);
+    m.insert(INIT_SYMS.translate["isspace"]);
+    m.insert(INIT_SYMS.translate["ispunct"]);
+    m.insert(INIT_SYMS.translate["isprint"]);
+    m.insert(INIT_SYMS.translate["iscntrl"]);
+    m.insert(INIT_SYMS.translate["tolower"]);
+    m.insert(INIT_SYMS.translate["toupper"]);
+    m.insert(INIT_SYMS.translate["sqrt"]);
+    m.insert(INIT_SYMS.translate["pow"]);
+    m.insert(INIT_SYMS.translate["fabs"]);
+    m.insert(INIT_SYMS.translate["floor"]);
+    m.insert(INIT_SYMS.translate["ceil"]);
+    m.insert(INIT_SYMS.translate["sin"]);
+    m.insert(INIT_SYMS.translate["cos"]);
+    m.insert(INIT_SYMS.translate["log"]);
+    m.insert(INIT_SYMS.translate["exp"]);
     m
 });
 
@@ -43,6 +103,21 @@ pub fn init_main_no_args(main_sym: u32) -> Vec<Opcode> {
     ];
 }
 
+/// The kind of value that reading or writing something of type `t` moves
+fn access_of(t: &TCType) -> Access {
+    if t.pointer_count > 0 || t.array_kind != TCArrayKind::None {
+        return Access::Pointer;
+    }
+
+    match t.kind {
+        TCTypeKind::I8 | TCTypeKind::U8 => return Access::Char,
+        TCTypeKind::I32 | TCTypeKind::U32 => return Access::Int,
+        TCTypeKind::I64 | TCTypeKind::U64 => return Access::Long,
+        TCTypeKind::F64 => return Access::Double,
+        _ => return Access::Bytes { align: t.align() },
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ASMRuntimeStruct {
     pub members: Option<Vec<TCStructMember>>,
@@ -58,6 +133,7 @@ pub struct Assembler {
     pub types: HashMap<u32, ASMRuntimeStruct>,
     pub symbols: Vec<RuntimeVar>,
     pub struct_member_count: usize,
+    pub func_ptrs: HashMap<u32, (u32, CodeLoc)>, // function symbol -> binary var holding its address
 }
 
 impl Assembler {
@@ -70,6 +146,7 @@ impl Assembler {
             types: HashMap::new(),
             symbols: Vec::new(),
             struct_member_count: 0,
+            func_ptrs: HashMap::new(),
         }
     }
 
@@ -103,7 +180,8 @@ impl Assembler {
                             "first one found here",
                             struct_type.meta.loc,
                             "second one found here"
-                        ));
+                        )
+                        .with_code("TCI0025"));
                     }
                 } else {
                     self.struct_member_count += member_count;
@@ -173,8 +251,25 @@ impl Assembler {
         asm_func.func_header = Some((self.opcodes.len() as u32, defn.loc));
         let param_count = asm_func.func_type.params.len() as u32;
 
+        // Parameter names are only known here, not at the call site, so the
+        // runtime finds them through the function header instead
+        let params = self.symbols.len() as u32;
+        if !asm_func.func_type.varargs {
+            for param in &defn.params {
+                self.symbols.push(RuntimeVar {
+                    symbol: param.ident,
+                    decl_type: param.param_type,
+                    loc: param.loc,
+                });
+            }
+        }
+
         self.opcodes.push(TaggedOpcode {
-            op: Opcode::Func(ident),
+            op: Opcode::Func {
+                name: ident,
+                params,
+                param_count: self.symbols.len() as u32 - params,
+            },
             loc: defn.loc,
         });
 
@@ -382,6 +477,10 @@ impl Assembler {
                 tagged.op = Opcode::MakeTempU64(*val);
                 ops.push(tagged);
             }
+            TCExprKind::F64Literal(val) => {
+                tagged.op = Opcode::MakeTempF64(*val);
+                ops.push(tagged);
+            }
             TCExprKind::StringLiteral(val) => {
                 let var = self.data.add_var(val.len() as u32 + 1, META_NO_SYMBOL); // TODO overflow here
                 let slice = self.data.get_full_var_range_mut(var);
@@ -407,6 +506,17 @@ impl Assembler {
                 ops.push(tagged);
             }
 
+            TCExprKind::FuncPtr(func) => {
+                let data = &mut self.data;
+                let loc = expr.loc;
+                let (var, _) = *self
+                    .func_ptrs
+                    .entry(*func)
+                    .or_insert_with(|| (data.add_var(FUNC_PTR_SIZE, META_NO_SYMBOL), loc));
+                tagged.op = Opcode::MakeTempBinaryPtr { var, offset: 0 };
+                ops.push(tagged);
+            }
+
             TCExprKind::TypePun(array) => {
                 ops.append(&mut self.translate_expr(array));
             }
@@ -428,12 +538,24 @@ impl Assembler {
                 }
             }
 
+            TCExprKind::AddI32(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::AddI32;
+                ops.push(tagged);
+            }
             TCExprKind::AddU32(l, r) => {
                 ops.append(&mut self.translate_expr(l));
                 ops.append(&mut self.translate_expr(r));
                 tagged.op = Opcode::AddU32;
                 ops.push(tagged);
             }
+            TCExprKind::AddI64(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::AddI64;
+                ops.push(tagged);
+            }
             TCExprKind::AddU64(l, r) => {
                 ops.append(&mut self.translate_expr(l));
                 ops.append(&mut self.translate_expr(r));
@@ -448,6 +570,18 @@ impl Assembler {
                 tagged.op = Opcode::SubI32;
                 ops.push(tagged);
             }
+            TCExprKind::SubU32(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::SubU32;
+                ops.push(tagged);
+            }
+            TCExprKind::SubI64(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::SubI64;
+                ops.push(tagged);
+            }
             TCExprKind::SubU64(l, r) => {
                 ops.append(&mut self.translate_expr(l));
                 ops.append(&mut self.translate_expr(r));
@@ -461,6 +595,12 @@ impl Assembler {
                 tagged.op = Opcode::MulI32;
                 ops.push(tagged);
             }
+            TCExprKind::MulU32(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::MulU32;
+                ops.push(tagged);
+            }
             TCExprKind::MulI64(l, r) => {
                 ops.append(&mut self.translate_expr(l));
                 ops.append(&mut self.translate_expr(r));
@@ -480,6 +620,18 @@ impl Assembler {
                 tagged.op = Opcode::DivI32;
                 ops.push(tagged);
             }
+            TCExprKind::DivU32(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::DivU32;
+                ops.push(tagged);
+            }
+            TCExprKind::DivI64(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::DivI64;
+                ops.push(tagged);
+            }
             TCExprKind::DivU64(l, r) => {
                 ops.append(&mut self.translate_expr(l));
                 ops.append(&mut self.translate_expr(r));
@@ -487,6 +639,91 @@ impl Assembler {
                 ops.push(tagged);
             }
 
+            TCExprKind::AddPtr(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::AddPtr;
+                ops.push(tagged);
+            }
+            TCExprKind::SubPtr(l, r, elem_size) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::SubPtr {
+                    elem_size: *elem_size,
+                };
+                ops.push(tagged);
+            }
+            TCExprKind::LtPtr(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::CompLtPtr;
+                ops.push(tagged);
+            }
+            TCExprKind::GtPtr(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::Swap { top: 8, bottom: 8 };
+                ops.push(tagged);
+                tagged.op = Opcode::CompLtPtr;
+                ops.push(tagged);
+            }
+            TCExprKind::LeqPtr(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::CompLeqPtr;
+                ops.push(tagged);
+            }
+            TCExprKind::GeqPtr(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::Swap { top: 8, bottom: 8 };
+                ops.push(tagged);
+                tagged.op = Opcode::CompLeqPtr;
+                ops.push(tagged);
+            }
+            TCExprKind::EqPtr(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::CompEqPtr;
+                ops.push(tagged);
+            }
+
+            TCExprKind::ModI32(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::ModI32;
+                ops.push(tagged);
+            }
+            TCExprKind::ModU32(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::ModU32;
+                ops.push(tagged);
+            }
+            TCExprKind::ModI64(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::ModI64;
+                ops.push(tagged);
+            }
+            TCExprKind::ModU64(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::ModU64;
+                ops.push(tagged);
+            }
+
+            TCExprKind::NegI32(inner) => {
+                ops.append(&mut self.translate_expr(inner));
+                tagged.op = Opcode::NegI32;
+                ops.push(tagged);
+            }
+            TCExprKind::NegI64(inner) => {
+                ops.append(&mut self.translate_expr(inner));
+                tagged.op = Opcode::NegI64;
+                ops.push(tagged);
+            }
+
             TCExprKind::GtI32(l, r) => {
                 ops.append(&mut self.translate_expr(l));
                 ops.append(&mut self.translate_expr(r));
@@ -549,6 +786,59 @@ impl Assembler {
                 ops.push(tagged);
             }
 
+            TCExprKind::AddF64(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::AddF64;
+                ops.push(tagged);
+            }
+            TCExprKind::SubF64(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::SubF64;
+                ops.push(tagged);
+            }
+            TCExprKind::MulF64(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::MulF64;
+                ops.push(tagged);
+            }
+            TCExprKind::DivF64(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::DivF64;
+                ops.push(tagged);
+            }
+            TCExprKind::LtF64(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::CompLtF64;
+                ops.push(tagged);
+            }
+            TCExprKind::GtF64(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::Swap { top: 8, bottom: 8 };
+                ops.push(tagged);
+                tagged.op = Opcode::CompLtF64;
+                ops.push(tagged);
+            }
+            TCExprKind::GeqF64(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::Swap { top: 8, bottom: 8 };
+                ops.push(tagged);
+                tagged.op = Opcode::CompLeqF64;
+                ops.push(tagged);
+            }
+            TCExprKind::EqF64(l, r) => {
+                ops.append(&mut self.translate_expr(l));
+                ops.append(&mut self.translate_expr(r));
+                tagged.op = Opcode::CompEqF64;
+                ops.push(tagged);
+            }
+
             TCExprKind::SConv8To32(expr) => {
                 ops.append(&mut self.translate_expr(expr));
                 tagged.op = Opcode::SExtend8To32;
@@ -577,23 +867,59 @@ impl Assembler {
                 ops.push(tagged);
             }
 
-            TCExprKind::PostIncrU64(target) => {
+            TCExprKind::ConvI64ToF64(expr) => {
+                ops.append(&mut self.translate_expr(expr));
+                tagged.op = Opcode::I64ToF64;
+                ops.push(tagged);
+            }
+            TCExprKind::ConvU64ToF64(expr) => {
+                ops.append(&mut self.translate_expr(expr));
+                tagged.op = Opcode::U64ToF64;
+                ops.push(tagged);
+            }
+            TCExprKind::ConvF64ToI64(expr) => {
+                ops.append(&mut self.translate_expr(expr));
+                tagged.op = Opcode::F64ToI64;
+                ops.push(tagged);
+            }
+
+            TCExprKind::PostIncr(target) | TCExprKind::PostDecr(target) => {
+                // Signed types use the checked ops, so `LONG_MAX++` is an overflow
+                let (one, add, sub) = match target.target_type.to_shallow() {
+                    TCShallowType::I32 => (Opcode::MakeTempI32(1), Opcode::AddI32, Opcode::SubI32),
+                    TCShallowType::U32 => (Opcode::MakeTempI32(1), Opcode::AddU32, Opcode::SubU32),
+                    TCShallowType::I64 => (Opcode::MakeTempI64(1), Opcode::AddI64, Opcode::SubI64),
+                    _ => (Opcode::MakeTempU64(1), Opcode::AddU64, Opcode::SubU64),
+                };
+
                 ops.append(&mut self.translate_assign(target));
                 tagged.op = Opcode::PushDup { bytes: 8 };
                 ops.push(tagged);
-                let bytes = 8;
-                tagged.op = Opcode::Get { offset: 0, bytes };
+                let bytes = target.target_type.size();
+                let access = access_of(&target.target_type);
+                tagged.op = Opcode::Get {
+                    offset: 0,
+                    bytes,
+                    access,
+                };
                 ops.push(tagged);
                 tagged.op = Opcode::PushDup { bytes };
                 ops.push(tagged);
-                tagged.op = Opcode::MakeTempU64(1);
+                tagged.op = one;
                 ops.push(tagged);
-                tagged.op = Opcode::AddU64;
+                tagged.op = match expr.kind {
+                    TCExprKind::PostIncr(_) => add,
+                    _ => sub,
+                };
                 ops.push(tagged);
                 let top = bytes * 2;
                 tagged.op = Opcode::Swap { top, bottom: 8 };
                 ops.push(tagged);
-                tagged.op = Opcode::Set { offset: 0, bytes };
+                tagged.op = Opcode::Set {
+                    offset: 0,
+                    bytes,
+                    access,
+                };
                 ops.push(tagged);
             }
 
@@ -603,7 +929,11 @@ impl Assembler {
                 tagged.op = Opcode::PushDup { bytes };
                 ops.push(tagged);
                 ops.append(&mut self.translate_assign(target));
-                tagged.op = Opcode::Set { offset: 0, bytes };
+                tagged.op = Opcode::Set {
+                    offset: 0,
+                    bytes,
+                    access: access_of(&target.target_type),
+                };
                 ops.push(tagged);
             }
 
@@ -656,6 +986,7 @@ impl Assembler {
                 tagged.op = Opcode::Get {
                     offset: *offset,
                     bytes,
+                    access: access_of(&expr.expr_type),
                 };
                 ops.push(tagged);
             }
@@ -665,21 +996,25 @@ impl Assembler {
                 tagged.op = Opcode::Get {
                     offset: 0,
                     bytes: expr.expr_type.size(),
+                    access: access_of(&expr.expr_type),
+                };
+                ops.push(tagged);
+            }
+            TCExprKind::Ref(lvalue) => {
+                ops.append(&mut self.translate_assign(lvalue));
+            }
+            TCExprKind::MemberArray {
+                target,
+                struct_id,
+                member,
+            } => {
+                ops.append(&mut self.translate_assign(target));
+                tagged.op = Opcode::MemberArray {
+                    struct_id: *struct_id,
+                    member: *member,
                 };
                 ops.push(tagged);
             }
-            TCExprKind::Ref(lvalue) => match lvalue.kind {
-                TCAssignTargetKind::LocalIdent { var_offset } => {
-                    tagged.op = Opcode::MakeTempLocalStackPtr {
-                        var: var_offset,
-                        offset: 0,
-                    };
-                    ops.push(tagged);
-                }
-                TCAssignTargetKind::Ptr(expr) => {
-                    ops.append(&mut self.translate_expr(expr));
-                }
-            },
 
             TCExprKind::Call {
                 func,
@@ -696,10 +1031,17 @@ impl Assembler {
                 for param in *params {
                     let bytes = param.expr_type.size();
 
+                    // Arguments have no name of their own, but keeping track of where they
+                    // came from lets library functions point at them in errors
                     tagged.op = Opcode::StackAlloc {
                         bytes,
-                        symbol: META_NO_SYMBOL, // TODO this should be the parameter symbol
+                        symbol: self.symbols.len() as u32,
                     };
+                    self.symbols.push(RuntimeVar {
+                        symbol: META_NO_SYMBOL,
+                        decl_type: param.expr_type,
+                        loc: param.loc,
+                    });
                     ops.push(tagged);
                     ops.append(&mut self.translate_expr(param));
                     tagged.op = Opcode::PopIntoTopVar { offset: 0, bytes };
@@ -774,7 +1116,7 @@ impl Assembler {
     }
 
     pub fn assemble<'b>(mut self, env: &FileDb) -> Result<Program<'b>, Error> {
-        let no_main = || error!("missing main function definition");
+        let no_main = || error!("missing main function definition").with_code("TCI0026");
         let main_sym = INIT_SYMS.translate["main"];
         let main_func = self.functions.get(&main_sym).ok_or_else(no_main)?;
         let (main_idx, main_loc) = main_func.func_header.ok_or_else(no_main)?;
@@ -800,7 +1142,8 @@ impl Assembler {
                         return Err(error!(
                             "couldn't find definition for function",
                             op.loc, "called here", func_loc, "declared here"
-                        ));
+                        )
+                        .with_code("TCI0027"));
                     }
                 }
                 Opcode::Jump(target) => {
@@ -833,6 +1176,34 @@ impl Assembler {
                 _ => {}
             }
         }
+
+        // The address goes in now that it's known; the symbol lets the runtime check
+        // that the pointer still names a function before calling it
+        for (func, (var, loc)) in self.func_ptrs.iter() {
+            let function = self.functions.get(func).unwrap();
+            let fptr = match function.func_header {
+                Some((fptr, _loc)) => fptr + runtime_length,
+                None if LIB_FUNCS.contains(func) => {
+                    return Err(error!(
+                        "library functions can't be used as values",
+                        *loc, "used here"
+                    )
+                    .with_code("TCI0028"));
+                }
+                None => {
+                    let func_loc = function.func_type.loc;
+                    return Err(error!(
+                        "couldn't find definition for function",
+                        *loc, "used here", func_loc, "declared here"
+                    )
+                    .with_code("TCI0027"));
+                }
+            };
+
+            let ptr = VarPointer::new_binary(*var, 0);
+            self.data.set(ptr, [fptr.to_be(), func.to_be()]).unwrap();
+        }
+
         let file_size = align_usize(env.size(), align_of::<HashRefSlot<u32, RuntimeStruct>>());
 
         let type_struct_slots = self.types.len() * 2;
@@ -38,6 +38,10 @@ pub enum UnaryOp {
 #[derive(Debug, Clone, Copy)]
 pub enum ExprKind<'a> {
     IntLiteral(i32),
+    UIntLiteral(u32),
+    LongLiteral(i64),
+    ULongLiteral(u64),
+    DoubleLiteral(f64),
     CharLiteral(i8),
     StringLiteral(&'a str),
     SizeofType {
@@ -149,6 +153,7 @@ pub enum GlobalStmtKind<'a> {
         ident: u32,
         params: &'a [ParamDecl<'a>],
         body: &'a [Stmt<'a>],
+        body_end: CodeLoc,
     },
     FuncDecl {
         return_type: ASTType<'a>,
@@ -182,6 +187,7 @@ pub enum ASTTypeKind<'a> {
     Char,
     Unsigned,
     Void,
+    Double,
 
     LongInt,
     LongLongInt,
@@ -313,6 +319,7 @@ pub enum TCTypeKind {
     I64, // long
     I8,  // char
     U8,  // unsigned char
+    F64, // double
     Void,
     Struct { ident: u32, sa: SizeAlign },
     AnonStruct { loc: CodeLoc, sa: SizeAlign },
@@ -376,6 +383,7 @@ impl TCType {
             TCTypeKind::U64 => TCShallowType::U64,
             TCTypeKind::I8 => TCShallowType::I8,
             TCTypeKind::U8 => TCShallowType::U8,
+            TCTypeKind::F64 => TCShallowType::F64,
             TCTypeKind::Void => TCShallowType::Void,
             TCTypeKind::Struct { .. } => TCShallowType::Struct,
             TCTypeKind::AnonStruct { .. } => TCShallowType::Struct,
@@ -394,6 +402,29 @@ impl TCType {
         return self.pointer_count > 0;
     }
 
+    pub fn is_integer(&self) -> bool {
+        if self.is_pointer() {
+            return false;
+        }
+
+        match self.kind {
+            TCTypeKind::I32 | TCTypeKind::U32 | TCTypeKind::I64 | TCTypeKind::U64 => true,
+            TCTypeKind::I8 | TCTypeKind::U8 => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_signed(&self) -> bool {
+        if self.is_pointer() {
+            return false;
+        }
+
+        match self.kind {
+            TCTypeKind::I32 | TCTypeKind::I64 | TCTypeKind::I8 => true,
+            _ => false,
+        }
+    }
+
     pub fn rank(&self) -> u32 {
         match self.array_kind {
             TCArrayKind::None => {}
@@ -411,6 +442,7 @@ impl TCType {
             TCTypeKind::U32 => return 10,
             TCTypeKind::I64 => return 11,
             TCTypeKind::U64 => return 12,
+            TCTypeKind::F64 => return 13,
             _ => {}
         }
 
@@ -452,7 +484,7 @@ impl TCType {
 
         use TCTypeKind as TCTK;
         let element_size = match self.kind {
-            TCTK::U64 | TCTK::I64 => 8,
+            TCTK::U64 | TCTK::I64 | TCTK::F64 => 8,
             TCTK::I32 | TCTK::U32 => 4,
             TCTK::I8 | TCTK::U8 => 1,
             TCTK::Void => 0,
@@ -483,7 +515,7 @@ impl TCType {
 
         use TCTypeKind as TCTK;
         match self.kind {
-            TCTK::U64 | TCTK::I64 => 8,
+            TCTK::U64 | TCTK::I64 | TCTK::F64 => 8,
             TCTK::I32 | TCTK::U32 => 4,
             TCTK::I8 | TCTK::U8 => 1,
             TCTK::Void => 0,
@@ -514,6 +546,7 @@ impl TCType {
             TCTypeKind::I64 => write!(writer, "long"),
             TCTypeKind::I8 => write!(writer, "char"),
             TCTypeKind::U8 => write!(writer, "unsigned char"),
+            TCTypeKind::F64 => write!(writer, "double"),
             TCTypeKind::Void => write!(writer, "void"),
             TCTypeKind::Struct { ident, .. } => write!(writer, "struct {}", files.symbol_to_str(ident)),
             TCTypeKind::AnonStruct { .. } => write!(writer, "struct ?"),
@@ -556,6 +589,7 @@ pub enum TCShallowType {
     I64, // long
     I8,  // char
     U8,  // unsigned char
+    F64, // double
     Void,
     Struct,
     Pointer,
@@ -654,6 +688,7 @@ pub enum TCExprKind<'a> {
     I32Literal(i32),
     I64Literal(i64),
     U64Literal(u64),
+    F64Literal(f64),
     StringLiteral(&'a str),
     LocalIdent {
         var_offset: i16,
@@ -661,6 +696,7 @@ pub enum TCExprKind<'a> {
     LocalArrayIdent {
         var_offset: i16,
     },
+    FuncPtr(u32), // a function used as a value, e.g. a comparator passed to qsort
 
     TypePun(&'a TCExpr<'a>),
     Array(&'a [TCExpr<'a>]),
@@ -668,9 +704,12 @@ pub enum TCExprKind<'a> {
     BraceList(&'a [TCExpr<'a>]),
     ParenList(&'a [TCExpr<'a>]),
 
+    AddI32(&'a TCExpr<'a>, &'a TCExpr<'a>),
     SubI32(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    SubI64(&'a TCExpr<'a>, &'a TCExpr<'a>),
     MulI32(&'a TCExpr<'a>, &'a TCExpr<'a>),
     DivI32(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    NegI32(&'a TCExpr<'a>),
     LtI32(&'a TCExpr<'a>, &'a TCExpr<'a>),
     GtI32(&'a TCExpr<'a>, &'a TCExpr<'a>),
     LeqI32(&'a TCExpr<'a>, &'a TCExpr<'a>),
@@ -681,6 +720,9 @@ pub enum TCExprKind<'a> {
     Eq64(&'a TCExpr<'a>, &'a TCExpr<'a>),
 
     AddU32(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    SubU32(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    MulU32(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    DivU32(&'a TCExpr<'a>, &'a TCExpr<'a>),
 
     AddU64(&'a TCExpr<'a>, &'a TCExpr<'a>),
     SubU64(&'a TCExpr<'a>, &'a TCExpr<'a>),
@@ -688,8 +730,33 @@ pub enum TCExprKind<'a> {
     GeqU64(&'a TCExpr<'a>, &'a TCExpr<'a>),
     LtU64(&'a TCExpr<'a>, &'a TCExpr<'a>),
 
+    AddI64(&'a TCExpr<'a>, &'a TCExpr<'a>),
     MulI64(&'a TCExpr<'a>, &'a TCExpr<'a>),
     MulU64(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    DivI64(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    NegI64(&'a TCExpr<'a>),
+
+    AddPtr(&'a TCExpr<'a>, &'a TCExpr<'a>), // pointer and byte offset
+    SubPtr(&'a TCExpr<'a>, &'a TCExpr<'a>, u32), // pointers and element size
+    LtPtr(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    GtPtr(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    LeqPtr(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    GeqPtr(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    EqPtr(&'a TCExpr<'a>, &'a TCExpr<'a>),
+
+    ModI32(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    ModU32(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    ModI64(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    ModU64(&'a TCExpr<'a>, &'a TCExpr<'a>),
+
+    AddF64(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    SubF64(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    MulF64(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    DivF64(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    LtF64(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    GtF64(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    GeqF64(&'a TCExpr<'a>, &'a TCExpr<'a>),
+    EqF64(&'a TCExpr<'a>, &'a TCExpr<'a>),
 
     SConv8To32(&'a TCExpr<'a>),
     SConv32To64(&'a TCExpr<'a>),
@@ -699,7 +766,12 @@ pub enum TCExprKind<'a> {
 
     Conv64To32(&'a TCExpr<'a>),
 
-    PostIncrU64(TCAssignTarget<'a>),
+    ConvI64ToF64(&'a TCExpr<'a>),
+    ConvU64ToF64(&'a TCExpr<'a>),
+    ConvF64ToI64(&'a TCExpr<'a>),
+
+    PostIncr(TCAssignTarget<'a>),
+    PostDecr(TCAssignTarget<'a>),
 
     Assign {
         target: TCAssignTarget<'a>,
@@ -723,6 +795,12 @@ pub enum TCExprKind<'a> {
 
     Deref(&'a TCExpr<'a>),
     Ref(TCAssignTarget<'a>),
+    // Array members decay to pointers that have to stay inside the member
+    MemberArray {
+        target: TCAssignTarget<'a>,
+        struct_id: u32,
+        member: u32,
+    },
 
     Call {
         func: u32,
@@ -738,6 +816,21 @@ pub struct TCExpr<'a> {
     pub loc: CodeLoc,
 }
 
+impl<'a> TCExprKind<'a> {
+    pub fn int_literal(&self) -> Option<i128> {
+        match *self {
+            TCExprKind::I8Literal(value) => Some(value as i128),
+            TCExprKind::I32Literal(value) => Some(value as i128),
+            TCExprKind::I64Literal(value) => Some(value as i128),
+            TCExprKind::U64Literal(value) => Some(value as i128),
+            TCExprKind::NegI32(inner) | TCExprKind::NegI64(inner) => {
+                Some(-inner.kind.int_literal()?)
+            }
+            _ => None,
+        }
+    }
+}
+
 impl PartialEq for TCFuncType {
     fn eq(&self, other: &Self) -> bool {
         if self.return_type != other.return_type {
@@ -4,8 +4,26 @@ use crate::interpreter::*;
 use crate::runtime::*;
 use crate::*;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use strum_macros::IntoStaticStr;
 
+/// Programs run by the server share its threads, so they can't run forever
+pub const SERVER_LIMITS: Limits = Limits {
+    max_steps: Some(100_000_000),
+    max_time: Some(Duration::from_secs(10)),
+    max_depth: Limits::DEFAULT.max_depth,
+    max_stack_bytes: Limits::DEFAULT.max_stack_bytes,
+};
+
+/// Checks that are off by default, set with the `Options` command; they apply
+/// from the next `Compile`
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RunOptions {
+    pub math_warnings: bool,
+    pub strict: bool,
+}
+
 #[derive(Debug, Deserialize, Serialize, IntoStaticStr)]
 #[serde(tag = "command", content = "data")]
 pub enum Command {
@@ -13,6 +31,7 @@ pub enum Command {
         path: String,
         data: String,
     },
+    Options(RunOptions),
     Compile,
     RunUntilScopedPC(u32),
     RunOp,
@@ -25,6 +44,8 @@ pub enum Command {
     Snapshot,
     Back(u32),
     Forwards(u32),
+    Explain(String),
+    Stdin(String), // an empty string closes stdin
 }
 
 #[derive(Debug, Serialize)]
@@ -45,11 +66,32 @@ pub enum CommandResult {
         rendered: String,
         error: Vec<Error>,
     },
+    CompileWarnings {
+        rendered: String,
+        warnings: Vec<Error>,
+    },
     RuntimeError {
         rendered: String,
         error: IError,
     },
     Status(RuntimeDiagnostic),
+    Leaks {
+        rendered: String,
+        leaks: Vec<Leak>,
+    },
+    UnclosedFiles {
+        rendered: String,
+        files: Vec<UnclosedFile>,
+    },
+    DomainWarnings {
+        rendered: String,
+        warnings: Vec<DomainWarning>,
+    },
+    StrictWarnings {
+        rendered: String,
+        warnings: Vec<StrictWarning>,
+    },
+    Explanation(Option<&'static crate::explain::Explanation>),
     FileId {
         path: String,
         file_id: u32,
@@ -80,6 +122,7 @@ pub enum WSStateState {
 pub struct WSState {
     state: WSStateState,
     files: FileDbSlim,
+    options: RunOptions,
 }
 
 impl Drop for WSState {
@@ -98,6 +141,7 @@ impl Default for WSState {
         Self {
             state: WSStateState::NotRunning,
             files: FileDbSlim::new(),
+            options: RunOptions::default(),
         }
     }
 }
@@ -112,6 +156,15 @@ impl WSState {
             }};
         }
 
+        if let Command::Explain(code) = &command {
+            ret!(CommandResult::Explanation(crate::explain::lookup(code)));
+        }
+
+        if let Command::Options(options) = command {
+            self.options = options;
+            ret!(CommandResult::Confirm(command.into()));
+        }
+
         if let Command::AddFile { path, data } = &command {
             let file_id = self.files.add(path, data);
             messages.push(CommandResult::FileId {
@@ -122,7 +175,18 @@ impl WSState {
         } else if let Command::Compile = &command {
             let mut db = self.files.file_db();
             let program = match compile(&mut db) {
-                Ok(prog) => prog,
+                Ok((prog, warnings)) => {
+                    if warnings.len() != 0 {
+                        let mut writer = StringWriter::new();
+                        emit_err(&warnings, &mut db, &mut writer);
+                        messages.push(CommandResult::CompileWarnings {
+                            rendered: writer.into_string(),
+                            warnings,
+                        });
+                    }
+
+                    prog
+                }
                 Err(err) => {
                     let mut writer = StringWriter::new();
                     emit_err(&err, &mut db, &mut writer);
@@ -133,7 +197,15 @@ impl WSState {
                 }
             };
 
-            self.state = WSStateState::Running(Runtime::new(program, StringArray::new()));
+            let mut runtime = Runtime::new(program, StringArray::new());
+            runtime.limits = SERVER_LIMITS;
+            runtime.math_warnings = self.options.math_warnings;
+            runtime.strict = self.options.strict;
+            for file in &self.files.files {
+                runtime.memory.add_file(file._name, file._source.as_bytes());
+            }
+
+            self.state = WSStateState::Running(runtime);
             messages.push(CommandResult::Compiled(program));
             ret!(CommandResult::Confirm(command.into()));
         }
@@ -155,6 +227,7 @@ impl WSState {
                     }
 
                     if let Some(ret) = ret {
+                        messages.extend(exit_report(runtime));
                         ret!(CommandResult::StatusRet {
                             status: runtime.diagnostic(),
                             ret,
@@ -178,6 +251,7 @@ impl WSState {
                     }
 
                     if let Some(ret) = ret {
+                        messages.extend(exit_report(runtime));
                         ret!(CommandResult::StatusRet {
                             status: runtime.diagnostic(),
                             ret,
@@ -205,6 +279,7 @@ impl WSState {
                     }
 
                     if let Some(ret) = ret {
+                        messages.extend(exit_report(runtime));
                         ret!(CommandResult::StatusRet {
                             status: runtime.diagnostic(),
                             ret,
@@ -216,6 +291,10 @@ impl WSState {
                 Command::Snapshot => {
                     ret!(CommandResult::Snapshot(runtime.memory.snapshot()));
                 }
+                Command::Stdin(ref input) => {
+                    runtime.push_stdin(&input);
+                    ret!(CommandResult::Confirm(command.into()));
+                }
                 Command::Forwards(count) => {
                     for _ in 0..count {
                         let tag = runtime.memory.current_tag();
@@ -261,3 +340,41 @@ impl WSState {
         });
     }
 }
+
+/// Warnings collected while the program ran, and resources it never released,
+/// reported when it exits
+fn exit_report(runtime: &Runtime) -> Vec<CommandResult> {
+    let mut messages = Vec::new();
+
+    let warnings = &runtime.domain_warnings;
+    if warnings.len() != 0 {
+        let rendered = render_domain_warnings(warnings, &runtime.program);
+        messages.push(CommandResult::DomainWarnings {
+            rendered,
+            warnings: warnings.clone(),
+        });
+    }
+
+    let warnings = &runtime.strict_warnings;
+    if warnings.len() != 0 {
+        let rendered = render_strict_warnings(warnings, &runtime.program);
+        messages.push(CommandResult::StrictWarnings {
+            rendered,
+            warnings: warnings.clone(),
+        });
+    }
+
+    let leaks = runtime.leaks();
+    if leaks.len() != 0 {
+        let rendered = render_leaks(&leaks, &runtime.program);
+        messages.push(CommandResult::Leaks { rendered, leaks });
+    }
+
+    let files = runtime.unclosed_files();
+    if files.len() != 0 {
+        let rendered = render_unclosed_files(&files, &runtime.program);
+        messages.push(CommandResult::UnclosedFiles { rendered, files });
+    }
+
+    return messages;
+}
@@ -0,0 +1,1651 @@
+// Longer explanations for error codes, shown by `tci --explain TCI0042`. Each
+// entry has an example of code that causes the error and a corrected version.
+
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct Explanation {
+    pub code: &'static str,
+    pub title: &'static str,
+    pub explanation: &'static str,
+    pub wrong: &'static str,
+    pub fixed: &'static str,
+}
+
+/// Code for runtime errors that come from a bug in the interpreter rather than
+/// in the program being run
+pub const INTERNAL_ERROR: &str = "TCI0299";
+
+pub fn lookup(code: &str) -> Option<&'static Explanation> {
+    let code = code.to_ascii_uppercase();
+    return CATALOG.iter().find(|explanation| explanation.code == code);
+}
+
+/// The code for a runtime error, given its short name
+pub fn runtime_code(short_name: &str) -> &'static str {
+    match short_name {
+        "InvalidPointer" => return "TCI0201",
+        "MissingNullTerminator" => return "TCI0202",
+        "InvalidFormatString" => return "TCI0203",
+        "StackPopInvalidatesVariable" => return "TCI0204",
+        "UseAfterFree" => return "TCI0205",
+        "DoubleFree" => return "TCI0206",
+        "UninitializedMemory" => return "TCI0207",
+        "BufferOverflow" => return "TCI0208",
+        "OverlappingCopy" => return "TCI0209",
+        "InvalidStream" => return "TCI0210",
+        "InvalidFileMode" => return "TCI0211",
+        "InvalidFunctionPointer" => return "TCI0212",
+        "AssertionFailed" => return "TCI0213",
+        "MathDomainWarning" => return "TCI0214",
+        "LimitExceeded" => return "TCI0215",
+        "InfiniteLoop" => return "TCI0216",
+        "StackOverflow" => return "TCI0217",
+        "IntegerOverflow" => return "TCI0218",
+        "DivideByZero" => return "TCI0219",
+        "PointerOutOfBounds" => return "TCI0220",
+        "UnrelatedPointers" => return "TCI0221",
+        "MemberOutOfBounds" => return "TCI0222",
+        "NullPointer" => return "TCI0223",
+        "ReadFailed" | "WriteFailed" => return "TCI0224",
+        "IncompatibleRead" => return "TCI0225",
+        "MisalignedAccess" => return "TCI0226",
+        "MemoryLeak" => return "TCI0227",
+        "UnclosedFile" => return "TCI0228",
+        "WaitingForInput" => return "TCI0229",
+        // These only happen when tci itself goes wrong
+        "StackTooShort" | "StackIsEmpty" | "StackDupReadsVar" => return INTERNAL_ERROR,
+        "InvalidLibraryFunction" | "InvalidArgumentIndex" => return INTERNAL_ERROR,
+        "InvalidEnviromentCall" => return INTERNAL_ERROR,
+        _ => return INTERNAL_ERROR,
+    }
+}
+
+pub fn render(explanation: &Explanation) -> String {
+    let mut out = format!(
+        "{}: {}\n\n{}\n",
+        explanation.code, explanation.title, explanation.explanation
+    );
+
+    if explanation.wrong.len() != 0 {
+        out.push_str("\nExample of code with this error:\n\n");
+        out.push_str(explanation.wrong);
+        out.push_str("\nCorrected example:\n\n");
+        out.push_str(explanation.fixed);
+    }
+
+    return out;
+}
+
+pub static CATALOG: &[Explanation] = &[
+    Explanation {
+        code: "TCI0001",
+        title: "unexpected token",
+        explanation: "\
+The parser found a token where it didn't expect one. This usually means that
+something is missing just before the highlighted token, like a semicolon at the
+end of the previous line or a closing parenthesis.",
+        wrong: "\
+int main() {
+  int x = 1
+  return x;
+}
+",
+        fixed: "\
+int main() {
+  int x = 1;
+  return x;
+}
+",
+    },
+    Explanation {
+        code: "TCI0002",
+        title: "couldn't find name",
+        explanation: "\
+A variable was used that hasn't been declared in the current scope. Check the
+spelling, and make sure the variable is declared before it's used and inside a
+block that contains the use.",
+        wrong: "\
+int main() {
+  int count = 0;
+  return cuont;
+}
+",
+        fixed: "\
+int main() {
+  int count = 0;
+  return count;
+}
+",
+    },
+    Explanation {
+        code: "TCI0003",
+        title: "function doesn't exist",
+        explanation: "\
+A function was called that hasn't been declared. Library functions like
+`malloc` and `printf` are declared in headers, so the header needs to be
+included before they can be used.",
+        wrong: "\
+int main() {
+  int *data = malloc(4);
+  return 0;
+}
+",
+        fixed: "\
+#include <stdlib.h>
+
+int main() {
+  int *data = malloc(4);
+  return 0;
+}
+",
+    },
+    Explanation {
+        code: "TCI0004",
+        title: "couldn't find member in struct definition",
+        explanation: "\
+A struct member was accessed with `.` or `->`, but the struct doesn't have a
+member with that name. Check the spelling against the struct definition.",
+        wrong: "\
+struct Point {
+  int x;
+  int y;
+};
+
+int get_x(struct Point p) {
+  return p.z;
+}
+",
+        fixed: "\
+struct Point {
+  int x;
+  int y;
+};
+
+int get_x(struct Point p) {
+  return p.x;
+}
+",
+    },
+    Explanation {
+        code: "TCI0005",
+        title: "function hasn't been declared yet",
+        explanation: "\
+In C, a function has to be declared before the code that calls it. Either move
+the function above its first use, or add a declaration (the function's
+signature followed by a semicolon) near the top of the file.",
+        wrong: "\
+int main() {
+  return helper();
+}
+
+int helper() {
+  return 0;
+}
+",
+        fixed: "\
+int helper();
+
+int main() {
+  return helper();
+}
+
+int helper() {
+  return 0;
+}
+",
+    },
+    Explanation {
+        code: "TCI0006",
+        title: "function call has wrong number of parameters",
+        explanation: "\
+A function was called with more or fewer arguments than it was declared with.
+Every parameter needs a matching argument.",
+        wrong: "\
+int add(int a, int b) {
+  return a + b;
+}
+
+int main() {
+  return add(1);
+}
+",
+        fixed: "\
+int add(int a, int b) {
+  return a + b;
+}
+
+int main() {
+  return add(1, 2);
+}
+",
+    },
+    Explanation {
+        code: "TCI0007",
+        title: "value cannot be converted to target type",
+        explanation: "\
+A value was assigned, passed or returned where a different type was expected,
+and there's no implicit conversion between the two types. This often happens
+when mixing up a pointer and the value it points to.",
+        wrong: "\
+int main() {
+  int x = 1;
+  int *ptr = x;
+  return 0;
+}
+",
+        fixed: "\
+int main() {
+  int x = 1;
+  int *ptr = &x;
+  return 0;
+}
+",
+    },
+    Explanation {
+        code: "TCI0008",
+        title: "missing value in return statement",
+        explanation: "\
+A function whose return type isn't `void` has to give back a value every time it
+returns, including when it returns with a bare `return;`.",
+        wrong: "\
+int first(int *values) {
+  if (values == 0) {
+    return;
+  }
+
+  return values[0];
+}
+",
+        fixed: "\
+int first(int *values) {
+  if (values == 0) {
+    return 0;
+  }
+
+  return values[0];
+}
+",
+    },
+    Explanation {
+        code: "TCI0009",
+        title: "void function should not return a value",
+        explanation: "\
+A function declared to return `void` gives back nothing, so its return
+statements can't have a value. Either remove the value or change the return
+type.",
+        wrong: "\
+void set(int *ptr) {
+  *ptr = 1;
+  return 1;
+}
+",
+        fixed: "\
+void set(int *ptr) {
+  *ptr = 1;
+  return;
+}
+",
+    },
+    Explanation {
+        code: "TCI0010",
+        title: "function can end without returning a value",
+        explanation: "\
+Some path through the function reaches its closing brace without a return
+statement. If that happens at runtime, the caller gets a garbage value. Make
+sure every branch returns something.",
+        wrong: "\
+int sign(int x) {
+  if (x < 0) {
+    return 0 - 1;
+  } else if (x > 0) {
+    return 1;
+  }
+}
+",
+        fixed: "\
+int sign(int x) {
+  if (x < 0) {
+    return 0 - 1;
+  } else if (x > 0) {
+    return 1;
+  }
+
+  return 0;
+}
+",
+    },
+    Explanation {
+        code: "TCI0011",
+        title: "name redefined in scope",
+        explanation: "\
+Two variables with the same name were declared in the same block. Give one of
+them a different name, or assign to the existing variable instead of declaring a
+new one. The same goes for two members of a struct, two parameters of a
+function, and structs or functions that are defined more than once.",
+        wrong: "\
+int main() {
+  int total = 0;
+  int total = 1;
+  return total;
+}
+",
+        fixed: "\
+int main() {
+  int total = 0;
+  total = 1;
+  return total;
+}
+",
+    },
+    Explanation {
+        code: "TCI0012",
+        title: "invalid operands to binary expression",
+        explanation: "\
+An operator was used on values whose types it doesn't support, like adding two
+pointers or comparing a struct. The labels show the type of each side.",
+        wrong: "\
+struct Point {
+  int x;
+};
+
+int main() {
+  struct Point a;
+  struct Point b;
+  return a + b;
+}
+",
+        fixed: "\
+struct Point {
+  int x;
+};
+
+int main() {
+  struct Point a;
+  struct Point b;
+  a.x = 1;
+  b.x = 2;
+  return a.x + b.x;
+}
+",
+    },
+    Explanation {
+        code: "TCI0013",
+        title: "invalid signature for main",
+        explanation: "\
+The `main` function must return `int` (or `void`) and take either no parameters
+or `int argc, char **argv`.",
+        wrong: "\
+char *main(int argc) {
+  return 0;
+}
+",
+        fixed: "\
+int main(int argc, char **argv) {
+  return 0;
+}
+",
+    },
+    Explanation {
+        code: "TCI0014",
+        title: "invalid token",
+        explanation: "\
+The source contains a character that isn't part of any C token, like `@` or a
+backtick outside of a string. It's often a stray keystroke or text pasted from
+somewhere else.",
+        wrong: "\
+int main() {
+  int total = 3 @ 4;
+  return total;
+}
+",
+        fixed: "\
+int main() {
+  int total = 3 + 4;
+  return total;
+}
+",
+    },
+    Explanation {
+        code: "TCI0015",
+        title: "unexpected end of file",
+        explanation: "\
+The file ended in the middle of something, like a function body, a macro or a
+parameter list. Usually a closing brace or parenthesis is missing somewhere
+above the end of the file.",
+        wrong: "\
+int main() {
+  return 0;
+",
+        fixed: "\
+int main() {
+  return 0;
+}
+",
+    },
+    Explanation {
+        code: "TCI0016",
+        title: "malformed preprocessor directive",
+        explanation: "\
+A line starting with `#` isn't a directive tci understands, or is missing part
+of what the directive needs. `#define` needs a name and a function-like macro
+needs a list of parameter names in parentheses.",
+        wrong: "\
+#define SQUARE(x, ) ((x) * (x))
+",
+        fixed: "\
+#define SQUARE(x) ((x) * (x))
+",
+    },
+    Explanation {
+        code: "TCI0017",
+        title: "included file couldn't be read",
+        explanation: "\
+The file named in an `#include` couldn't be found or opened. Files in quotes
+are looked up next to the file including them, and files in angle brackets in
+the system headers that tci provides.",
+        wrong: "\
+#include \"helpers.h\"
+",
+        fixed: "\
+#include \"helper.h\"
+",
+    },
+    Explanation {
+        code: "TCI0018",
+        title: "include cycle",
+        explanation: "\
+A file includes itself, either directly or through other files it includes, so
+including it would never finish. Headers that need each other usually only need
+a declaration of the other's types or functions, which can be written out
+instead of including the whole header.",
+        wrong: "\
+// list.h
+#include \"list.h\"
+",
+        fixed: "\
+// list.h
+struct list;
+",
+    },
+    Explanation {
+        code: "TCI0019",
+        title: "malformed number literal",
+        explanation: "\
+A number couldn't be read, because its exponent has no digits after the `e`,
+or because it's too large to fit in an `unsigned long`.",
+        wrong: "\
+double scale = 1.5e;
+",
+        fixed: "\
+double scale = 1.5e3;
+",
+    },
+    Explanation {
+        code: "TCI0020",
+        title: "malformed character or string literal",
+        explanation: "\
+A character or string literal is empty, isn't closed, contains a character
+outside of ASCII, or uses an escape sequence that doesn't exist. Character
+literals hold exactly one character between single quotes; strings use double
+quotes.",
+        wrong: "\
+char newline = '\\q';
+",
+        fixed: "\
+char newline = '\\n';
+",
+    },
+    Explanation {
+        code: "TCI0021",
+        title: "macro used incorrectly",
+        explanation: "\
+A macro was used in a way that doesn't match its definition: a function-like
+macro was used without parentheses or with the wrong number of arguments, or a
+macro defined without a value was used in code.",
+        wrong: "\
+#define MAX(a, b) ((a) > (b) ? (a) : (b))
+
+int biggest = MAX(1, 2, 3);
+",
+        fixed: "\
+#define MAX(a, b) ((a) > (b) ? (a) : (b))
+
+int biggest = MAX(MAX(1, 2), 3);
+",
+    },
+    Explanation {
+        code: "TCI0022",
+        title: "invalid array declaration",
+        explanation: "\
+An array was declared with a size tci can't use. Sizes have to be integer
+literals of at least 1, arrays can have at most one dimension, and an array
+without a size needs an initializer list to take its size from.",
+        wrong: "\
+int values[0];
+char name[];
+",
+        fixed: "\
+int values[4];
+char name[] = {'t', 'c', 'i', 0};
+",
+    },
+    Explanation {
+        code: "TCI0023",
+        title: "declaration doesn't declare anything",
+        explanation: "\
+A type was written on its own without a name after it, so nothing is declared.
+Usually the variable name was left out or the line is left over from an edit.",
+        wrong: "\
+int main() {
+  int;
+  return 0;
+}
+",
+        fixed: "\
+int main() {
+  int count;
+  return 0;
+}
+",
+    },
+    Explanation {
+        code: "TCI0024",
+        title: "`float` isn't supported",
+        explanation: "\
+tci doesn't support the `float` type. Use `double` instead, which has more
+precision and works with all of the math functions.",
+        wrong: "\
+float average = 2.5;
+",
+        fixed: "\
+double average = 2.5;
+",
+    },
+    Explanation {
+        code: "TCI0025",
+        title: "conflicting definitions of a type",
+        explanation: "\
+Two files define a struct with the same name in different ways. A struct
+should be defined once, in a header that every file using it includes.",
+        wrong: "\
+// a.c
+struct point { int x; };
+
+// b.c
+struct point { double x; };
+",
+        fixed: "\
+// point.h
+struct point { int x; };
+
+// a.c and b.c
+#include \"point.h\"
+",
+    },
+    Explanation {
+        code: "TCI0026",
+        title: "missing main function",
+        explanation: "\
+None of the files define `main`, so there's nowhere for the program to start.
+Check that the file containing `main` was passed to tci.",
+        wrong: "\
+int add(int a, int b) {
+  return a + b;
+}
+",
+        fixed: "\
+int add(int a, int b) {
+  return a + b;
+}
+
+int main() {
+  return add(1, 2);
+}
+",
+    },
+    Explanation {
+        code: "TCI0027",
+        title: "function is declared but never defined",
+        explanation: "\
+A function was declared and then used, but none of the files give it a body.
+Either the definition is missing, the file that defines it wasn't passed to tci,
+or its name is spelled differently there.",
+        wrong: "\
+int square(int x);
+
+int main() {
+  return square(3);
+}
+",
+        fixed: "\
+int square(int x);
+
+int main() {
+  return square(3);
+}
+
+int square(int x) {
+  return x * x;
+}
+",
+    },
+    Explanation {
+        code: "TCI0028",
+        title: "library function used as a value",
+        explanation: "\
+Library functions like `strlen` can be called, but tci can't make a function
+pointer to them. Wrap the call in a function of your own and use a pointer to
+that instead.",
+        wrong: "\
+unsigned long (*length)(const char *) = strlen;
+",
+        fixed: "\
+unsigned long my_strlen(const char *s) {
+  return strlen(s);
+}
+
+unsigned long (*length)(const char *) = my_strlen;
+",
+    },
+    Explanation {
+        code: "TCI0029",
+        title: "invalid initializer",
+        explanation: "\
+An initializer list in braces was used somewhere it can't be: on something that
+isn't an array, outside of a declaration, or with a different number of
+elements than the array was declared with.",
+        wrong: "\
+int values[3] = {1, 2};
+",
+        fixed: "\
+int values[3] = {1, 2, 0};
+",
+    },
+    Explanation {
+        code: "TCI0030",
+        title: "type used before it's complete",
+        explanation: "\
+A struct or typedef was used in a way that needs its full definition, like
+declaring a variable of it or reading a member, before it was defined. Order
+matters in C: move the definition above the code using it, or use a pointer,
+which only needs the declaration. A struct also can't contain itself except
+through a pointer.",
+        wrong: "\
+struct node {
+  int value;
+  struct node next;
+};
+",
+        fixed: "\
+struct node {
+  int value;
+  struct node *next;
+};
+",
+    },
+    Explanation {
+        code: "TCI0031",
+        title: "type doesn't exist",
+        explanation: "\
+A struct or typedef name was used that hasn't been declared anywhere. Check the
+spelling, and that the header declaring it is included.",
+        wrong: "\
+struct point { int x; };
+
+struct pointt origin;
+",
+        fixed: "\
+struct point { int x; };
+
+struct point origin;
+",
+    },
+    Explanation {
+        code: "TCI0032",
+        title: "struct defined where it isn't allowed",
+        explanation: "\
+A struct was defined inside something that can only name a type, like a
+function's return type or a cast. Define the struct on its own first and refer
+to it by name.",
+        wrong: "\
+struct pair { int a; int b; } make_pair(int a, int b);
+",
+        fixed: "\
+struct pair { int a; int b; };
+
+struct pair make_pair(int a, int b);
+",
+    },
+    Explanation {
+        code: "TCI0033",
+        title: "dereferenced a value that isn't a pointer",
+        explanation: "\
+`*` or `[]` was used on a value that isn't a pointer or an array. The label
+shows the value's type.",
+        wrong: "\
+int count = 3;
+int first = *count;
+",
+        fixed: "\
+int count = 3;
+int *ptr = &count;
+int first = *ptr;
+",
+    },
+    Explanation {
+        code: "TCI0034",
+        title: "member access on a value that isn't a struct",
+        explanation: "\
+`.` was used on something that isn't a struct, or on a pointer to a struct,
+which needs `->` instead.",
+        wrong: "\
+struct point { int x; };
+
+int get_x(struct point *p) {
+  return p.x;
+}
+",
+        fixed: "\
+struct point { int x; };
+
+int get_x(struct point *p) {
+  return p->x;
+}
+",
+    },
+    Explanation {
+        code: "TCI0035",
+        title: "invalid parameter list",
+        explanation: "\
+A function's parameters can't be used like this. The `...` for variable
+arguments has to come last, and every parameter needs a name when the function
+is defined, even though declarations can leave them out.",
+        wrong: "\
+int sum(int count, ..., int last);
+",
+        fixed: "\
+int sum(int count, ...);
+",
+    },
+    Explanation {
+        code: "TCI0036",
+        title: "void used as a type with a size",
+        explanation: "\
+`void` means \"no value\", so it has no size. A variable can't have type `void`
+and `sizeof(void)` doesn't mean anything; use a pointer to `void` or a real
+type instead.",
+        wrong: "\
+void value;
+",
+        fixed: "\
+void *value;
+",
+    },
+    Explanation {
+        code: "TCI0037",
+        title: "invalid operand to unary expression",
+        explanation: "\
+A unary operator like `-`, `!`, `~` or `++` was used on a value whose type it
+doesn't support, like a struct. The label shows the operand's type.",
+        wrong: "\
+struct point { int x; };
+
+struct point p;
+p++;
+",
+        fixed: "\
+struct point { int x; };
+
+struct point p;
+p.x++;
+",
+    },
+    Explanation {
+        code: "TCI0038",
+        title: "called something that isn't a function",
+        explanation: "\
+An expression was called like a function, but it's a regular value. Often a
+local variable has the same name as the function it was meant to call.",
+        wrong: "\
+int max(int a, int b);
+
+int main() {
+  int max = 0;
+  return max(1, 2);
+}
+",
+        fixed: "\
+int max(int a, int b);
+
+int main() {
+  int largest = 0;
+  return max(1, 2);
+}
+",
+    },
+    Explanation {
+        code: "TCI0039",
+        title: "expression is not assignable",
+        explanation: "\
+The left side of an assignment, or the operand of `++`, `--` or `&`, has to be
+something that names memory, like a variable, an array element or a struct
+member. The result of a calculation or a function call isn't.",
+        wrong: "\
+int a = 1;
+int b = 2;
+a + b = 3;
+",
+        fixed: "\
+int a = 1;
+int b = 2;
+a = 3 - b;
+",
+    },
+    Explanation {
+        code: "TCI0040",
+        title: "struct used as a condition",
+        explanation: "\
+A struct was used where a true or false value is needed, like in an `if` or a
+loop condition. Only numbers and pointers can be tested; test one of the
+struct's members instead.",
+        wrong: "\
+struct result { int ok; };
+
+struct result r;
+if (r) {
+}
+",
+        fixed: "\
+struct result { int ok; };
+
+struct result r;
+if (r.ok) {
+}
+",
+    },
+    Explanation {
+        code: "TCI0041",
+        title: "function declarations don't match",
+        explanation: "\
+A function was declared twice with different return or parameter types. Every
+declaration of a function, including the one in its definition, has to agree.",
+        wrong: "\
+int area(int w, int h);
+
+long area(int w, int h) {
+  return w * h;
+}
+",
+        fixed: "\
+int area(int w, int h);
+
+int area(int w, int h) {
+  return w * h;
+}
+",
+    },
+    Explanation {
+        code: "TCI0101",
+        title: "unused variable",
+        explanation: "\
+A local variable is declared but never used. This is often a sign of a typo, or
+of code that was meant to use the variable but doesn't.",
+        wrong: "\
+int main() {
+  int result = 0;
+  return 0;
+}
+",
+        fixed: "\
+int main() {
+  int result = 0;
+  return result;
+}
+",
+    },
+    Explanation {
+        code: "TCI0102",
+        title: "unused parameter",
+        explanation: "\
+A function parameter is never used in the function's body. Callers pass a value
+that's ignored, which is usually a mistake.",
+        wrong: "\
+int square(int x) {
+  return 4;
+}
+",
+        fixed: "\
+int square(int x) {
+  return x * x;
+}
+",
+    },
+    Explanation {
+        code: "TCI0103",
+        title: "assignment used as a condition",
+        explanation: "\
+`=` assigns a value, while `==` compares two values. Using `=` in a condition
+overwrites the variable, and the condition is then true whenever the assigned
+value is non-zero.",
+        wrong: "\
+if (x = 4) {
+  printf(\"x is four\\n\");
+}
+",
+        fixed: "\
+if (x == 4) {
+  printf(\"x is four\\n\");
+}
+",
+    },
+    Explanation {
+        code: "TCI0104",
+        title: "comparison between signed and unsigned integers",
+        explanation: "\
+When a signed and an unsigned integer are compared, the signed one is converted
+to unsigned first. Negative numbers become very large, so `-1 < len` can be
+false.",
+        wrong: "\
+int i = 0 - 1;
+unsigned long len = 4;
+if (i < len) {
+  printf(\"smaller\\n\");
+}
+",
+        fixed: "\
+int i = 0 - 1;
+unsigned long len = 4;
+if (i < 0 || (unsigned long)i < len) {
+  printf(\"smaller\\n\");
+}
+",
+    },
+    Explanation {
+        code: "TCI0105",
+        title: "implicit conversion may change value",
+        explanation: "\
+A value was converted to a smaller integer type without a cast. If it doesn't
+fit in the smaller type, its upper bits are silently thrown away. Add a cast to
+show that this is intended, or use a bigger type.",
+        wrong: "\
+unsigned long len = sizeof(buffer);
+int n = len;
+",
+        fixed: "\
+unsigned long len = sizeof(buffer);
+unsigned long n = len;
+",
+    },
+    Explanation {
+        code: "TCI0106",
+        title: "if statement has an empty body",
+        explanation: "\
+A semicolon directly after the condition of an `if` statement ends the
+statement. The block that follows then runs no matter what the condition is.",
+        wrong: "\
+if (x == 2);
+{
+  printf(\"x is two\\n\");
+}
+",
+        fixed: "\
+if (x == 2) {
+  printf(\"x is two\\n\");
+}
+",
+    },
+    Explanation {
+        code: "TCI0107",
+        title: "variable may be used uninitialized",
+        explanation: "\
+A local variable was read before it was given a value, at least along some path
+through the function. Its value is whatever happened to be in memory. The
+labels show the branches that skip the assignment.",
+        wrong: "\
+int total;
+if (n > 0) {
+  total = n;
+}
+printf(\"%d\\n\", total);
+",
+        fixed: "\
+int total = 0;
+if (n > 0) {
+  total = n;
+}
+printf(\"%d\\n\", total);
+",
+    },
+    Explanation {
+        code: "TCI0108",
+        title: "unreachable statement",
+        explanation: "\
+A statement comes after a `return`, `break` or `continue` in the same block, so
+it can never run.",
+        wrong: "\
+for (int i = 0; i < 10; i = i + 1) {
+  break;
+  printf(\"%d\\n\", i);
+}
+",
+        fixed: "\
+for (int i = 0; i < 10; i = i + 1) {
+  printf(\"%d\\n\", i);
+  break;
+}
+",
+    },
+    Explanation {
+        code: "TCI0109",
+        title: "format string doesn't match arguments",
+        explanation: "\
+Each conversion in a `printf` format string like `%d` or `%s` takes one
+argument, which must have the matching type: `%d` takes an `int`, `%ld` a
+`long`, `%c` a `char` and `%s` a string. In `scanf` format strings, each
+conversion takes a pointer to where the value should be stored instead, like
+`&x` for `%d`.",
+        wrong: "\
+unsigned long len = 4;
+printf(\"%d\\n\", len);
+",
+        fixed: "\
+unsigned long len = 4;
+printf(\"%lu\\n\", len);
+",
+    },
+    Explanation {
+        code: "TCI0201",
+        title: "invalid pointer",
+        explanation: "\
+The program read or wrote memory through a pointer that doesn't point to a
+valid object. Common causes are using a pointer to a local variable after its
+function returned, and indexing past the end of an array.",
+        wrong: "\
+int *make() {
+  int x = 1;
+  return &x;
+}
+",
+        fixed: "\
+int *make() {
+  int *x = malloc(sizeof(int));
+  *x = 1;
+  return x;
+}
+",
+    },
+    Explanation {
+        code: "TCI0202",
+        title: "missing null terminator",
+        explanation: "\
+Strings in C end with a zero byte. A string function read to the end of an
+object without finding one, so it would have kept reading into other memory.",
+        wrong: "\
+char name[3];
+name[0] = 'a';
+name[1] = 'b';
+name[2] = 'c';
+printf(\"%s\\n\", name);
+",
+        fixed: "\
+char name[4];
+name[0] = 'a';
+name[1] = 'b';
+name[2] = 'c';
+name[3] = 0;
+printf(\"%s\\n\", name);
+",
+    },
+    Explanation {
+        code: "TCI0203",
+        title: "invalid format string",
+        explanation: "\
+A `printf` or `scanf` format string contained a `%` that isn't followed by a
+supported conversion. To print or match a percent sign, write `%%`. This also
+happens when `scanf` is given fewer pointers than its format string has
+conversions, or a value where it needs a pointer.",
+        wrong: "\
+printf(\"100%\\n\");
+",
+        fixed: "\
+printf(\"100%%\\n\");
+",
+    },
+    Explanation {
+        code: "TCI0204",
+        title: "stack pop invalidates variable",
+        explanation: "\
+A value on the stack was removed while a variable still lived there. This
+usually happens when memory belonging to a function that already returned is
+still in use.",
+        wrong: "\
+int *make() {
+  int x = 1;
+  return &x;
+}
+",
+        fixed: "\
+int make() {
+  int x = 1;
+  return x;
+}
+",
+    },
+    Explanation {
+        code: "TCI0205",
+        title: "use after free",
+        explanation: "\
+The program read or wrote heap memory after it was passed to `free`. Once
+memory is freed it no longer belongs to the program, even though the pointer
+still holds the same address. Even comparing the pointer to another one isn't
+allowed, since its value stops meaning anything. Stop using a pointer once it's
+been freed, or free it later.",
+        wrong: "\
+int *x = malloc(sizeof(int));
+free(x);
+*x = 1;
+",
+        fixed: "\
+int *x = malloc(sizeof(int));
+*x = 1;
+free(x);
+",
+    },
+    Explanation {
+        code: "TCI0206",
+        title: "double free",
+        explanation: "\
+The program called `free` on memory that had already been freed. Each
+allocation should be freed exactly once; setting a pointer to `NULL` after
+freeing it makes a second `free` harmless.",
+        wrong: "\
+int *x = malloc(sizeof(int));
+free(x);
+free(x);
+",
+        fixed: "\
+int *x = malloc(sizeof(int));
+free(x);
+x = NULL;
+free(x);
+",
+    },
+    Explanation {
+        code: "TCI0207",
+        title: "uninitialized memory",
+        explanation: "\
+A branch, a pointer dereference, or printed output depended on memory that was
+never given a value. Local variables and memory from `malloc` start out holding
+whatever happened to be there before, so reading them before writing to them
+gives unpredictable results. The error points at where the memory was created.",
+        wrong: "\
+int *counts = malloc(sizeof(int) * 2);
+counts[0] = 1;
+printf(\"%d\\n\", counts[0] + counts[1]);
+",
+        fixed: "\
+int *counts = malloc(sizeof(int) * 2);
+counts[0] = 1;
+counts[1] = 0;
+printf(\"%d\\n\", counts[0] + counts[1]);
+",
+    },
+    Explanation {
+        code: "TCI0208",
+        title: "buffer overflow",
+        explanation: "\
+A library function like `strcpy` or `memset` was asked to touch more bytes than
+the object its pointer argument points into can hold. Make sure the destination
+has room for everything being written, including the null terminator at the end
+of a string.",
+        wrong: "\
+char *name = malloc(5);
+strcpy(name, \"hello\");
+",
+        fixed: "\
+char *name = malloc(6);
+strcpy(name, \"hello\");
+",
+    },
+    Explanation {
+        code: "TCI0209",
+        title: "overlapping copy",
+        explanation: "\
+`memcpy` was called with a source and destination that share some bytes. The
+result of copying between overlapping ranges with `memcpy` is undefined; use
+`memmove`, which handles overlap correctly.",
+        wrong: "\
+memcpy(buffer + 1, buffer, 4);
+",
+        fixed: "\
+memmove(buffer + 1, buffer, 4);
+",
+    },
+    Explanation {
+        code: "TCI0210",
+        title: "invalid stream",
+        explanation: "\
+A stdio function was given a `FILE*` that it can't use. This happens when the
+file was already closed with `fclose`, when it was opened with a mode that
+doesn't allow the operation, like reading from a file opened with \"w\", or
+when the pointer didn't come from `fopen` at all.",
+        wrong: "\
+FILE *log = fopen(\"log.txt\", \"w\");
+fclose(log);
+fprintf(log, \"done\\n\");
+",
+        fixed: "\
+FILE *log = fopen(\"log.txt\", \"w\");
+fprintf(log, \"done\\n\");
+fclose(log);
+",
+    },
+    Explanation {
+        code: "TCI0211",
+        title: "invalid file mode",
+        explanation: "\
+The mode passed to `fopen` isn't one it understands. The mode is a string: \"r\"
+to read, \"w\" to write (emptying the file first), or \"a\" to append, each
+optionally followed by `+` to allow both reading and writing.",
+        wrong: "\
+FILE *out = fopen(\"out.txt\", \"write\");
+",
+        fixed: "\
+FILE *out = fopen(\"out.txt\", \"w\");
+",
+    },
+    Explanation {
+        code: "TCI0212",
+        title: "invalid function pointer",
+        explanation: "\
+A library function that calls back into your program, like `qsort`, `bsearch`
+or `atexit`, was given something that isn't a function. Pass the name of the
+function itself, without calling it.",
+        wrong: "\
+qsort(values, count, sizeof(int), compare_ints(a, b));
+",
+        fixed: "\
+qsort(values, count, sizeof(int), compare_ints);
+",
+    },
+    Explanation {
+        code: "TCI0213",
+        title: "assertion failed",
+        explanation: "\
+The condition passed to `assert` was false, so the program stopped. An assert
+states something that should always be true at that point in the program;
+when it fails, either the assumption or the code leading up to it is wrong.
+The error shows the value of each variable in the condition to help find out
+which.",
+        wrong: "\
+int average(int total, int count) {
+  assert(count > 0);
+  return total / count;
+}
+
+average(10, 0);
+",
+        fixed: "\
+int average(int total, int count) {
+  assert(count > 0);
+  return total / count;
+}
+
+average(10, 2);
+",
+    },
+    Explanation {
+        code: "TCI0214",
+        title: "math function called outside its domain",
+        explanation: "\
+A math.h function was given a value it isn't defined for, like the square root
+of a negative number or the logarithm of 0. Normally these return NaN or
+infinity, which then silently spread through every calculation that uses them.
+tci warns about these when it's run with `--math-warnings`, and lets the program
+carry on with the NaN or infinity it would have gotten anyway.",
+        wrong: "\
+double side = sqrt(area - 10);
+",
+        fixed: "\
+if (area >= 10) {
+  double side = sqrt(area - 10);
+}
+",
+    },
+    Explanation {
+        code: "TCI0215",
+        title: "program ran for too long",
+        explanation: "\
+The program ran for more steps or more time than it's allowed to, so it was
+stopped. This almost always means a loop whose condition never becomes false,
+or recursion that never reaches its base case. The error shows where the program
+was when it was stopped, which is usually inside the loop. The limits can be set
+with `--max-steps` and `--time-limit`.",
+        wrong: "\
+for (int i = 10; i > 0; i = i + 1) {
+  printf(\"%d\\n\", i);
+}
+",
+        fixed: "\
+for (int i = 10; i > 0; i = i - 1) {
+  printf(\"%d\\n\", i);
+}
+",
+    },
+    Explanation {
+        code: "TCI0216",
+        title: "loop can never terminate",
+        explanation: "\
+The program came back around to the start of a loop without changing anything
+since the last time it was there. Every later time around the loop will do
+exactly the same thing, so the loop will never end. Usually the variable that
+the condition checks is never updated in the loop.",
+        wrong: "\
+int i = 0;
+int sum = 0;
+while (i < 10) {
+  if (i > 5) {
+    sum = sum + i;
+  }
+}
+",
+        fixed: "\
+int i = 0;
+int sum = 0;
+while (i < 10) {
+  if (i > 5) {
+    sum = sum + i;
+  }
+  i = i + 1;
+}
+",
+    },
+    Explanation {
+        code: "TCI0217",
+        title: "stack overflow",
+        explanation: "\
+The program made too many function calls without returning from them, or its
+local variables took up too much space. Every call gets its own copy of the
+function's arguments and local variables, and they only go away once the call
+returns. This is almost always recursion that never reaches its base case; the
+error shows the arguments of the first and last calls so you can see which way
+they were heading. The limits can be set with `--max-depth` and `--max-stack`.",
+        wrong: "\
+int factorial(int n) {
+  return n * factorial(n - 1);
+}
+",
+        fixed: "\
+int factorial(int n) {
+  if (n < 2) {
+    return 1;
+  }
+  return n * factorial(n - 1);
+}
+",
+    },
+    Explanation {
+        code: "TCI0218",
+        title: "signed integer overflow",
+        explanation: "\
+A calculation on signed integers gave a result that's too big or too small to
+fit in its type. Unlike unsigned integers, which wrap around, overflowing a
+signed integer is undefined behavior in C, so the program can't rely on
+whatever value comes out. The error shows the values that went into the
+calculation. Use a bigger type like `long`, or check the values before doing
+the calculation.",
+        wrong: "\
+int total = 2147483647;
+total = total + 1;
+",
+        fixed: "\
+long total = 2147483647;
+total = total + 1;
+",
+    },
+    Explanation {
+        code: "TCI0219",
+        title: "division by zero",
+        explanation: "\
+An integer was divided by 0, or the remainder of dividing by 0 was taken with
+`%`. There's no sensible answer, so in C this is undefined behavior, and on most
+machines it crashes the program. The error highlights the divisor; check that it
+can't be 0 before dividing.",
+        wrong: "\
+int average(int total, int count) {
+  return total / count;
+}
+",
+        fixed: "\
+int average(int total, int count) {
+  if (count == 0) {
+    return 0;
+  }
+  return total / count;
+}
+",
+    },
+    Explanation {
+        code: "TCI0220",
+        title: "pointer arithmetic went out of bounds",
+        explanation: "\
+Pointer arithmetic moved a pointer outside of the object it points into. A
+pointer can point anywhere inside its object, or just one past its end so that
+loops have something to stop at, but even making a pointer any further away is
+undefined behavior in C, whether or not it's ever dereferenced. Usually a count
+passed along with the pointer is bigger than the object really is.",
+        wrong: "\
+int scores[4];
+int *end = scores + 5;
+",
+        fixed: "\
+int scores[4];
+int *end = scores + 4;
+",
+    },
+    Explanation {
+        code: "TCI0221",
+        title: "pointers to different objects were subtracted or compared",
+        explanation: "\
+Two pointers were subtracted, or compared with `<`, `>`, `<=` or `>=`, but they
+point into different objects, like two separate arrays or two blocks from
+malloc. C only defines these operations for pointers into the same object, since
+there's no telling where separate objects end up relative to each other. The
+error shows where both objects came from.",
+        wrong: "\
+char first[8];
+char second[8];
+long distance = second - first;
+",
+        fixed: "\
+char buffer[16];
+char *first = buffer;
+char *second = buffer + 8;
+long distance = second - first;
+",
+    },
+    Explanation {
+        code: "TCI0222",
+        title: "array inside a struct was indexed out of bounds",
+        explanation: "\
+An array that's a member of a struct was indexed past its end (or before its
+start), either directly or through a pointer made from it, or a library function
+like `strcpy` was given more bytes than fit in it. The rest of the struct comes
+right after the array in memory, so instead of crashing, the program quietly
+reads or overwrites whichever member comes next. The error says which member the
+access landed in and where the array is declared. Check the index against the array's length, and remember that the
+string in a `char` array also needs room for its terminating `'\\0'`.",
+        wrong: "\
+struct person {
+  char name[4];
+  int age;
+};
+
+struct person p;
+strcpy(p.name, \"Bob\");
+p.name[4] = '!';
+",
+        fixed: "\
+struct person {
+  char name[8];
+  int age;
+};
+
+struct person p;
+strcpy(p.name, \"Bob\");
+p.name[4] = '!';
+",
+    },
+    Explanation {
+        code: "TCI0223",
+        title: "a NULL pointer was dereferenced",
+        explanation: "\
+A pointer that was NULL was read or written through, with `*`, `->` or `[]`, or
+was passed to a library function that needed a real object. NULL doesn't point
+at anything, so on most machines this crashes the program with a segmentation
+fault. Functions like `malloc` and `fopen` return NULL when they fail, and
+searches and linked lists often use it to mean \"nothing here\". When the
+pointer came out of a variable, the error shows where that variable was last
+set; check for NULL before using it.",
+        wrong: "\
+int *find(int *values, int len, int target);
+
+int *match = find(values, len, 7);
+printf(\"%d\\n\", *match);
+",
+        fixed: "\
+int *find(int *values, int len, int target);
+
+int *match = find(values, len, 7);
+if (match != NULL) {
+  printf(\"%d\\n\", *match);
+}
+",
+    },
+    Explanation {
+        code: "TCI0224",
+        title: "couldn't read or write the program's input or output",
+        explanation: "\
+Reading the program's standard input or writing its output failed outside of
+the program, for example because the terminal or the pipe it writes to was
+closed. There's nothing wrong with the program itself; run it again with
+somewhere working to read from and write to.",
+        wrong: "",
+        fixed: "",
+    },
+    Explanation {
+        code: "TCI0225",
+        title: "memory was read as a different type than it was written",
+        explanation: "\
+With `--strict`, memory remembers the type it was last written as, and this
+read used a different one, like reading the bytes of a `double` through an
+`int *`. C's aliasing rules make that undefined, so optimizing compilers can
+assume it never happens and reorder or drop the accesses. Copy the bytes with
+`memcpy` into a variable of the new type, or read through a `char *`, which is
+allowed to look at anything.",
+        wrong: "\
+double value = 1.5;
+long bits = *(long *)&value;
+",
+        fixed: "\
+double value = 1.5;
+long bits;
+memcpy(&bits, &value, sizeof(bits));
+",
+    },
+    Explanation {
+        code: "TCI0226",
+        title: "misaligned memory access",
+        explanation: "\
+With `--strict`, reads and writes of values like `int` and `double` are checked
+against their alignment, and this one used an address that isn't a multiple of
+it. Some machines crash on misaligned accesses and on others they're slow, and
+either way they're undefined in C. This usually comes from casting a pointer
+into the middle of a `char` buffer; copy the bytes out with `memcpy` instead.",
+        wrong: "\
+char buffer[16];
+int *count = (int *)(buffer + 1);
+*count = 3;
+",
+        fixed: "\
+char buffer[16];
+int count = 3;
+memcpy(buffer + 1, &count, sizeof(count));
+",
+    },
+    Explanation {
+        code: "TCI0227",
+        title: "memory leak",
+        explanation: "\
+Memory allocated with `malloc`, `calloc` or `realloc` was still allocated when
+the program ended. Each label shows where the leaked blocks were allocated.
+Every allocation should be passed to `free` once the program is done with it;
+long-running programs that leak eventually run out of memory.",
+        wrong: "\
+int *values = malloc(10 * sizeof(int));
+values[0] = 1;
+return 0;
+",
+        fixed: "\
+int *values = malloc(10 * sizeof(int));
+values[0] = 1;
+free(values);
+return 0;
+",
+    },
+    Explanation {
+        code: "TCI0228",
+        title: "file was never closed",
+        explanation: "\
+A file opened with `fopen` was still open when the program ended. Output
+written to it may not be saved until it's closed, and programs can only have
+so many files open at once. Close every file with `fclose` once the program is
+done with it.",
+        wrong: "\
+FILE *out = fopen(\"out.txt\", \"w\");
+fprintf(out, \"done\\n\");
+return 0;
+",
+        fixed: "\
+FILE *out = fopen(\"out.txt\", \"w\");
+fprintf(out, \"done\\n\");
+fclose(out);
+return 0;
+",
+    },
+    Explanation {
+        code: "TCI0229",
+        title: "input was needed inside a library callback",
+        explanation: "\
+A function passed to a library function, like the comparator given to `qsort`,
+tried to read input that hadn't arrived yet. tci can't pause a library function
+halfway through to wait for it, so read the input before calling the library
+function and pass it along instead.",
+        wrong: "\
+int compare(const void *a, const void *b) {
+  int order;
+  scanf(\"%d\", &order);
+  return order * (*(int *)a - *(int *)b);
+}
+",
+        fixed: "\
+int order;
+
+int compare(const void *a, const void *b) {
+  return order * (*(int *)a - *(int *)b);
+}
+
+scanf(\"%d\", &order);
+qsort(values, len, sizeof(int), compare);
+",
+    },
+    Explanation {
+        code: "TCI0299",
+        title: "internal interpreter error",
+        explanation: "\
+The interpreter got into a state it doesn't know how to handle. This is a bug in
+tci rather than in your program; please report it along with the code that
+caused it.",
+        wrong: "",
+        fixed: "",
+    },
+];
@@ -184,12 +184,18 @@ pub struct FileDb {
     pub translate: HashMap<&'static str, u32>,
     pub names: Vec<CodeLoc>,
     pub fs_read_access: bool,
+    pub include_dirs: Vec<String>,
+    pub defines: Option<u32>,
 }
 
 pub struct InitSyms {
     pub names: Vec<&'static str>,
     pub translate: HashMap<&'static str, u32>,
     pub files: Vec<File<'static>>,
+
+    /// Functions declared by the bundled system headers, along with the name of
+    /// the header that declares them; tci's own `__tci_` builtins are left out
+    pub library_funcs: Vec<(&'static str, &'static str)>,
 }
 
 pub static INIT_SYMS: LazyStatic<InitSyms> = lazy_static!(init_syms_lazy_static, InitSyms, {
@@ -221,6 +227,10 @@ pub static INIT_SYMS: LazyStatic<InitSyms> = lazy_static!(init_syms_lazy_static,
     add_syslib_sym!("string.h");
     add_syslib_sym!("stddef.h");
     add_syslib_sym!("stdint.h");
+    add_syslib_sym!("limits.h");
+    add_syslib_sym!("ctype.h");
+    add_syslib_sym!("assert.h");
+    add_syslib_sym!("math.h");
 
     add_sym!("main");
     add_sym!("va_list");
@@ -230,14 +240,102 @@ pub static INIT_SYMS: LazyStatic<InitSyms> = lazy_static!(init_syms_lazy_static,
     add_sym!("free");
     add_sym!("realloc");
     add_sym!("memcpy");
-
+    add_sym!("strlen");
+    add_sym!("strcpy");
+    add_sym!("strncpy");
+    add_sym!("strcat");
+    add_sym!("strncat");
+    add_sym!("strcmp");
+    add_sym!("strncmp");
+    add_sym!("strchr");
+    add_sym!("strrchr");
+    add_sym!("strstr");
+    add_sym!("strdup");
+    add_sym!("memset");
+    add_sym!("memmove");
+    add_sym!("memcmp");
+    add_sym!("__tci_stream");
+    add_sym!("getchar");
+    add_sym!("fgets");
+    add_sym!("scanf");
+    add_sym!("fopen");
+    add_sym!("fclose");
+    add_sym!("fgetc");
+    add_sym!("fputc");
+    add_sym!("fprintf");
+    add_sym!("fscanf");
+    add_sym!("fread");
+    add_sym!("fwrite");
+    add_sym!("calloc");
+    add_sym!("atoi");
+    add_sym!("atol");
+    add_sym!("strtol");
+    add_sym!("strtoul");
+    add_sym!("abs");
+    add_sym!("labs");
+    add_sym!("rand");
+    add_sym!("srand");
+    add_sym!("atexit");
+    add_sym!("qsort");
+    add_sym!("bsearch");
+    add_sym!("__tci_assert");
+    add_sym!("isalnum");
+    add_sym!("isalpha");
+    add_sym!("isdigit");
+    add_sym!("isxdigit");
+    add_sym!("islower");
+    add_sym!("isupper");
+    add_sym!("isspace");
+    add_sym!("ispunct");
+    add_sym!("isprint");
+    add_sym!("iscntrl");
+    add_sym!("tolower");
+    add_sym!("toupper");
+    add_sym!("sqrt");
+    add_sym!("pow");
+    add_sym!("fabs");
+    add_sym!("floor");
+    add_sym!("ceil");
+    add_sym!("sin");
+    add_sym!("cos");
+    add_sym!("log");
+    add_sym!("exp");
+
+    let library_funcs = scan_library_funcs(&files);
     InitSyms {
         names,
         translate,
         files,
+        library_funcs,
     }
 });
 
+/// Finds every name followed by a `(` in the system headers
+fn scan_library_funcs(files: &[File<'static>]) -> Vec<(&'static str, &'static str)> {
+    let mut funcs = Vec::new();
+    for file in files {
+        let source = file._source.as_bytes();
+        let mut idx = 0;
+        while idx < source.len() {
+            let begin = idx;
+            while idx < source.len() && crate::lexer::is_ident_char(source[idx]) {
+                idx += 1;
+            }
+
+            if idx == begin {
+                idx += 1;
+            } else if idx < source.len() && source[idx] == b'(' {
+                let name = &file._source[begin..idx];
+                if !name.starts_with("__tci_") {
+                    funcs.push((name, file._name));
+                }
+            }
+        }
+    }
+
+    return funcs;
+}
+
 impl Drop for FileDb {
     fn drop(&mut self) {
         while let Some(b) = unsafe { self.buckets.dealloc() } {
@@ -278,6 +376,8 @@ impl FileDb {
             translate: HashMap::new(),
             names: Vec::new(),
             fs_read_access,
+            include_dirs: Vec::new(),
+            defines: None,
         };
 
         for symbol in symbols {
@@ -301,9 +401,24 @@ impl FileDb {
         let iter = self.files.iter();
         return (0..self.files.len() as u32)
             .skip(INIT_SYMS.files.len() + 1) // +1 here is for the init syms initial file
+            .filter(|&id| Some(id) != self.defines)
             .collect();
     }
 
+    /// Add a directory to search when resolving includes. Directories are
+    /// searched in the order they were added.
+    pub fn add_include_dir(&mut self, dir: &str) {
+        self.include_dirs.push(dir.to_string());
+    }
+
+    /// Add the macro definitions that every file is preprocessed with, e.g.
+    /// from `-D` flags. The source should only contain `#define` directives.
+    pub fn add_defines(&mut self, source: &str) -> Result<u32, io::Error> {
+        let id = self.add("<command-line>", source)?;
+        self.defines = Some(id);
+        return Ok(id);
+    }
+
     /// Add a file to the database, returning the handle that can be used to
     /// refer to it again. Errors if the file already exists in the database.
     pub fn add(&mut self, file_name: &str, source: &str) -> Result<u32, io::Error> {
@@ -361,20 +476,42 @@ impl FileDb {
         return unsafe { str::from_utf8_unchecked(&text.as_bytes()[range]) };
     }
 
-    pub fn add_from_symbols(&mut self, base_file: u32, symbol: u32) -> Result<u32, io::Error> {
+    /// Resolve an include. Quoted includes are looked up relative to the
+    /// including file first; both kinds then fall back to the include
+    /// directories.
+    pub fn add_from_symbols(
+        &mut self,
+        base_file: u32,
+        symbol: u32,
+        sys: bool,
+    ) -> Result<u32, io::Error> {
         let cloc = self.names[symbol as usize];
         let range: ops::Range<usize> = cloc.into();
         let text = self.files[cloc.file as usize]._source;
         let text = unsafe { str::from_utf8_unchecked(&text.as_bytes()[range]) };
 
-        if Path::new(text).is_relative() {
+        if !Path::new(text).is_relative() {
+            return self.add_from_fs(&text);
+        }
+
+        let mut result = Err(io::ErrorKind::NotFound.into());
+        if !sys {
             let base_path = parent_if_file(self.files[base_file as usize]._name);
             let real_path = Path::new(base_path).join(text);
-            let path_str = real_path.to_str().unwrap();
-            return self.add_from_fs(&path_str);
+            result = self.add_from_fs(real_path.to_str().unwrap());
+        }
+
+        for idx in 0..self.include_dirs.len() {
+            match &result {
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                _ => return result,
+            }
+
+            let real_path = Path::new(&self.include_dirs[idx]).join(text);
+            result = self.add_from_fs(real_path.to_str().unwrap());
         }
 
-        return self.add_from_fs(&text);
+        return result;
     }
 
     #[inline]
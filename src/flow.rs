@@ -0,0 +1,543 @@
+use crate::ast::*;
+use crate::filedb::*;
+use crate::util::*;
+use std::collections::HashMap;
+
+// Flow-sensitive checks that run over the body of a function after it has
+// been type checked.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InitState {
+    Init,
+    Uninit,
+    /// Uninitialized along some paths; each label marks a place where one of
+    /// those paths diverged from the ones that assign the variable
+    MaybeUninit(Vec<(CodeLoc, &'static str)>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalInit {
+    pub symbol: u32,
+    pub decl_loc: CodeLoc,
+    pub state: InitState,
+}
+
+/// Initialization state of every local, indexed by `var_offset`. `None` means
+/// the current point in the function is unreachable.
+type FlowState = Option<Vec<Option<LocalInit>>>;
+
+struct LoopEdges {
+    breaks: Vec<(FlowState, CodeLoc)>,
+    continues: Vec<FlowState>,
+}
+
+pub struct InitCheck<'a> {
+    files: &'a FileDb,
+    decl_idx: usize,
+    loops: Vec<LoopEdges>,
+    warnings: HashMap<CodeLoc, Error>,
+}
+
+/// Warns about locals that might be read before they're assigned a value
+pub fn check_init(files: &FileDb, stmts: &[TCStmt]) -> Vec<Error> {
+    let mut check = InitCheck {
+        files,
+        decl_idx: 0,
+        loops: Vec::new(),
+        warnings: HashMap::new(),
+    };
+
+    let mut state = Some(Vec::new());
+    check.stmts(&mut state, stmts);
+
+    let mut warnings: Vec<Error> = check.warnings.into_iter().map(|(_, w)| w).collect();
+    warnings.sort_by_key(|warning| warning.sections[0].location.start);
+    return warnings;
+}
+
+/// Merges the states flowing into a single point. The label of an edge describes
+/// the path it takes, and is attached to variables left uninitialized along it.
+fn join(edges: Vec<(FlowState, CodeLoc, &'static str)>) -> FlowState {
+    let edges: Vec<_> = edges
+        .into_iter()
+        .filter_map(|(state, loc, label)| Some((state?, loc, label)))
+        .collect();
+
+    let len = edges.iter().map(|(locals, _, _)| locals.len()).max()?;
+    let mut out = Vec::with_capacity(len);
+    for idx in 0..len {
+        let incoming = edges.iter().filter_map(|(locals, loc, label)| {
+            let local = locals.get(idx)?.as_ref()?;
+            return Some((local, *loc, *label));
+        });
+
+        let (first, _, _) = match incoming.clone().next() {
+            Some(first) => first,
+            None => {
+                out.push(None);
+                continue;
+            }
+        };
+
+        // Slots are reused by sibling scopes, so only merge the edges that
+        // agree on which variable is stored here
+        let incoming = incoming.filter(|(local, _, _)| local.decl_loc == first.decl_loc);
+        let (mut all_init, mut all_uninit) = (true, true);
+        let mut labels = Vec::new();
+        for (local, loc, label) in incoming {
+            match &local.state {
+                InitState::Init => all_uninit = false,
+                InitState::Uninit => {
+                    all_init = false;
+                    push_label(&mut labels, (loc, label));
+                }
+                InitState::MaybeUninit(prev) => {
+                    all_init = false;
+                    all_uninit = false;
+                    for &label in prev {
+                        push_label(&mut labels, label);
+                    }
+                }
+            }
+        }
+
+        let state = if all_init {
+            InitState::Init
+        } else if all_uninit {
+            InitState::Uninit
+        } else {
+            InitState::MaybeUninit(labels)
+        };
+
+        out.push(Some(LocalInit {
+            symbol: first.symbol,
+            decl_loc: first.decl_loc,
+            state,
+        }));
+    }
+
+    return Some(out);
+}
+
+fn push_label(labels: &mut Vec<(CodeLoc, &'static str)>, label: (CodeLoc, &'static str)) {
+    if !labels.contains(&label) {
+        labels.push(label);
+    }
+}
+
+impl<'a> InitCheck<'a> {
+    /// Checks the statements of a scope. Locals are numbered in declaration
+    /// order, the same way the type checker assigns their `var_offset`.
+    fn stmts(&mut self, state: &mut FlowState, stmts: &[TCStmt]) {
+        let decl_idx = self.decl_idx;
+        for stmt in stmts {
+            self.stmt(state, stmt);
+        }
+        self.decl_idx = decl_idx;
+    }
+
+    fn stmt(&mut self, state: &mut FlowState, stmt: &TCStmt) {
+        match &stmt.kind {
+            TCStmtKind::RetVal(expr) => {
+                self.expr(state, expr);
+                *state = None;
+            }
+            TCStmtKind::Ret => *state = None,
+            TCStmtKind::Expr(expr) => self.expr(state, expr),
+            TCStmtKind::Decl { symbol, init } => {
+                let init_state = match init.kind {
+                    TCExprKind::Uninit => InitState::Uninit,
+                    _ => {
+                        self.expr(state, init);
+                        InitState::Init
+                    }
+                };
+
+                let idx = self.decl_idx;
+                self.decl_idx += 1;
+                if let Some(locals) = state {
+                    if locals.len() <= idx {
+                        locals.resize(idx + 1, None);
+                    }
+
+                    locals[idx] = Some(LocalInit {
+                        symbol: *symbol,
+                        decl_loc: stmt.loc,
+                        state: init_state,
+                    });
+                }
+            }
+            TCStmtKind::Branch {
+                cond,
+                if_body,
+                else_body,
+            } => {
+                self.expr(state, cond);
+
+                let mut if_state = state.clone();
+                self.stmts(&mut if_state, if_body.stmts);
+                let mut else_state = state.take();
+                self.stmts(&mut else_state, else_body.stmts);
+
+                let if_edge = if if_body.stmts.len() == 0 {
+                    (
+                        if_state,
+                        cond.loc,
+                        "not assigned when this condition is true",
+                    )
+                } else {
+                    (
+                        if_state,
+                        if_body.loc,
+                        "not assigned when this branch is taken",
+                    )
+                };
+                let else_edge = if else_body.stmts.len() == 0 {
+                    (
+                        else_state,
+                        cond.loc,
+                        "not assigned when this condition is false",
+                    )
+                } else {
+                    (
+                        else_state,
+                        else_body.loc,
+                        "not assigned when this branch is taken",
+                    )
+                };
+
+                *state = join(vec![if_edge, else_edge]);
+            }
+            TCStmtKind::Block(block) => self.stmts(state, block.stmts),
+            TCStmtKind::Loop(block) => {
+                let entry = state.take();
+                let mut head = entry.clone();
+                loop {
+                    self.loops.push(LoopEdges {
+                        breaks: Vec::new(),
+                        continues: Vec::new(),
+                    });
+
+                    let mut body_state = head.clone();
+                    self.stmts(&mut body_state, block.stmts);
+                    let edges = self.loops.pop().unwrap();
+
+                    let mut back_edges = vec![(
+                        entry.clone(),
+                        stmt.loc,
+                        "not assigned before the first iteration",
+                    )];
+                    back_edges.push((
+                        body_state,
+                        block.loc,
+                        "not assigned by the previous iteration",
+                    ));
+                    for cont in edges.continues {
+                        back_edges.push((
+                            cont,
+                            block.loc,
+                            "not assigned by the previous iteration",
+                        ));
+                    }
+
+                    let next_head = join(back_edges);
+                    if next_head == head {
+                        let exits = edges.breaks.into_iter();
+                        let exits = exits.map(|(state, loc)| {
+                            (state, loc, "not assigned when the loop exits here")
+                        });
+                        *state = join(exits.collect());
+                        break;
+                    }
+
+                    head = next_head;
+                }
+            }
+            TCStmtKind::Break => {
+                let loop_edges = self.loops.last_mut().unwrap();
+                loop_edges.breaks.push((state.take(), stmt.loc));
+            }
+            TCStmtKind::Continue => {
+                let loop_edges = self.loops.last_mut().unwrap();
+                loop_edges.continues.push(state.take());
+            }
+        }
+    }
+
+    fn expr(&mut self, state: &mut FlowState, expr: &TCExpr) {
+        match &expr.kind {
+            TCExprKind::Uninit
+            | TCExprKind::I8Literal(_)
+            | TCExprKind::I32Literal(_)
+            | TCExprKind::I64Literal(_)
+            | TCExprKind::U64Literal(_)
+            | TCExprKind::F64Literal(_)
+            | TCExprKind::StringLiteral(_)
+            | TCExprKind::FuncPtr(_) => {}
+
+            TCExprKind::LocalIdent { var_offset } => self.read(state, *var_offset, expr.loc),
+
+            // The array's address escapes here, so we can't track it anymore
+            TCExprKind::LocalArrayIdent { var_offset } => self.write(state, *var_offset),
+
+            TCExprKind::TypePun(inner)
+            | TCExprKind::SConv8To32(inner)
+            | TCExprKind::SConv32To64(inner)
+            | TCExprKind::ZConv8To32(inner)
+            | TCExprKind::ZConv32To64(inner)
+            | TCExprKind::Conv64To32(inner)
+            | TCExprKind::ConvI64ToF64(inner)
+            | TCExprKind::ConvU64ToF64(inner)
+            | TCExprKind::ConvF64ToI64(inner)
+            | TCExprKind::NegI32(inner)
+            | TCExprKind::NegI64(inner)
+            | TCExprKind::Deref(inner)
+            | TCExprKind::Member { base: inner, .. }
+            | TCExprKind::PtrMember { base: inner, .. } => self.expr(state, inner),
+
+            TCExprKind::Array(exprs)
+            | TCExprKind::BraceList(exprs)
+            | TCExprKind::ParenList(exprs)
+            | TCExprKind::Call { params: exprs, .. } => {
+                for expr in *exprs {
+                    self.expr(state, expr);
+                }
+            }
+
+            TCExprKind::AddI32(l, r)
+            | TCExprKind::SubI32(l, r)
+            | TCExprKind::SubI64(l, r)
+            | TCExprKind::MulI32(l, r)
+            | TCExprKind::DivI32(l, r)
+            | TCExprKind::LtI32(l, r)
+            | TCExprKind::GtI32(l, r)
+            | TCExprKind::LeqI32(l, r)
+            | TCExprKind::GeqI32(l, r)
+            | TCExprKind::Eq32(l, r)
+            | TCExprKind::Neq32(l, r)
+            | TCExprKind::Eq64(l, r)
+            | TCExprKind::AddU32(l, r)
+            | TCExprKind::SubU32(l, r)
+            | TCExprKind::MulU32(l, r)
+            | TCExprKind::DivU32(l, r)
+            | TCExprKind::AddI64(l, r)
+            | TCExprKind::DivI64(l, r)
+            | TCExprKind::AddPtr(l, r)
+            | TCExprKind::SubPtr(l, r, _)
+            | TCExprKind::LtPtr(l, r)
+            | TCExprKind::GtPtr(l, r)
+            | TCExprKind::LeqPtr(l, r)
+            | TCExprKind::GeqPtr(l, r)
+            | TCExprKind::EqPtr(l, r)
+            | TCExprKind::ModI32(l, r)
+            | TCExprKind::ModU32(l, r)
+            | TCExprKind::ModI64(l, r)
+            | TCExprKind::ModU64(l, r)
+            | TCExprKind::AddU64(l, r)
+            | TCExprKind::SubU64(l, r)
+            | TCExprKind::DivU64(l, r)
+            | TCExprKind::GeqU64(l, r)
+            | TCExprKind::LtU64(l, r)
+            | TCExprKind::MulI64(l, r)
+            | TCExprKind::MulU64(l, r)
+            | TCExprKind::AddF64(l, r)
+            | TCExprKind::SubF64(l, r)
+            | TCExprKind::MulF64(l, r)
+            | TCExprKind::DivF64(l, r)
+            | TCExprKind::LtF64(l, r)
+            | TCExprKind::GtF64(l, r)
+            | TCExprKind::GeqF64(l, r)
+            | TCExprKind::EqF64(l, r) => {
+                self.expr(state, l);
+                self.expr(state, r);
+            }
+
+            TCExprKind::PostIncr(target) | TCExprKind::PostDecr(target) => {
+                if let TCAssignTargetKind::LocalIdent { var_offset } = target.kind {
+                    self.read(state, var_offset, target.target_loc);
+                }
+                self.target(state, target);
+            }
+            TCExprKind::Assign { target, value } => {
+                self.expr(state, value);
+                self.target(state, target);
+            }
+            TCExprKind::Ref(target) | TCExprKind::MemberArray { target, .. } => {
+                self.target(state, target)
+            }
+
+            TCExprKind::Ternary {
+                condition,
+                if_true,
+                if_false,
+            } => {
+                self.expr(state, condition);
+
+                let mut true_state = state.clone();
+                self.expr(&mut true_state, if_true);
+                let mut false_state = state.take();
+                self.expr(&mut false_state, if_false);
+
+                *state = join(vec![
+                    (
+                        true_state,
+                        if_true.loc,
+                        "not assigned when this branch is taken",
+                    ),
+                    (
+                        false_state,
+                        if_false.loc,
+                        "not assigned when this branch is taken",
+                    ),
+                ]);
+            }
+        }
+    }
+
+    /// Handles writes to an assignment target. Writing to any part of a local
+    /// counts as initializing all of it.
+    fn target(&mut self, state: &mut FlowState, target: &TCAssignTarget) {
+        match target.kind {
+            TCAssignTargetKind::LocalIdent { var_offset } => self.write(state, var_offset),
+            TCAssignTargetKind::Ptr(ptr) => self.expr(state, ptr),
+        }
+    }
+
+    fn write(&mut self, state: &mut FlowState, var_offset: i16) {
+        if let Some(local) = local_mut(state, var_offset) {
+            local.state = InitState::Init;
+        }
+    }
+
+    fn read(&mut self, state: &mut FlowState, var_offset: i16, loc: CodeLoc) {
+        let local = match local_mut(state, var_offset) {
+            Some(local) => local,
+            None => return,
+        };
+
+        let name = self.files.symbol_to_str(local.symbol);
+        let warning = match &local.state {
+            InitState::Init => return,
+            InitState::Uninit => warning!(
+                Lint::Uninitialized,
+                &format!("variable `{}` is used uninitialized", name),
+                loc,
+                "read here",
+                local.decl_loc,
+                "declared here without a value"
+            ),
+            InitState::MaybeUninit(labels) => {
+                let mut warning = warning!(
+                    Lint::Uninitialized,
+                    &format!("variable `{}` may be used uninitialized", name),
+                    loc,
+                    "read here",
+                    local.decl_loc,
+                    "declared here without a value"
+                );
+
+                for &(label_loc, label) in labels {
+                    warning = warning.with_secondary(label_loc, label);
+                }
+
+                warning
+            }
+        };
+
+        // Only report each variable once per path
+        local.state = InitState::Init;
+        self.warnings.insert(loc, warning);
+    }
+}
+
+fn local_mut(state: &mut FlowState, var_offset: i16) -> Option<&mut LocalInit> {
+    if var_offset < 0 {
+        return None;
+    }
+
+    let locals = state.as_mut()?;
+    return locals.get_mut(var_offset as usize)?.as_mut();
+}
+
+/// Finds the statement that prevents control from reaching the end of `stmts`,
+/// if there is one. Warns about statements that can never run.
+pub fn check_reachable(stmts: &[Stmt], warnings: &mut Vec<Error>) -> Option<CodeLoc> {
+    let mut terminator = None;
+    for stmt in stmts {
+        if let Some(term_loc) = terminator {
+            if let StmtKind::Nop = stmt.kind {
+                continue;
+            }
+
+            warnings.push(warning!(
+                Lint::UnreachableCode,
+                "unreachable statement",
+                stmt.loc,
+                "this statement will never run",
+                term_loc,
+                "because control never continues past this statement"
+            ));
+            return terminator;
+        }
+
+        terminator = match &stmt.kind {
+            StmtKind::Ret | StmtKind::RetVal(_) => Some(stmt.loc),
+            StmtKind::Break | StmtKind::Continue => Some(stmt.loc),
+            StmtKind::Branch {
+                if_body, else_body, ..
+            } => {
+                let if_term = check_reachable(if_body.stmts, warnings);
+                let else_term = check_reachable(else_body.stmts, warnings);
+                if_term.and(else_term).map(|_| stmt.loc)
+            }
+            StmtKind::Block(block) => check_reachable(block.stmts, warnings),
+
+            // Only a loop whose condition can never be false, like `while (1)`
+            // or `for (;;)`, and that has no `break`, keeps control from getting
+            // past it
+            StmtKind::For {
+                condition, body, ..
+            }
+            | StmtKind::ForDecl {
+                condition, body, ..
+            }
+            | StmtKind::While { condition, body } => {
+                check_reachable(body.stmts, warnings);
+                match always_true(condition) && !breaks_out(body.stmts) {
+                    true => Some(stmt.loc),
+                    false => None,
+                }
+            }
+
+            StmtKind::Decl { .. } | StmtKind::Expr(_) | StmtKind::Nop => None,
+        };
+    }
+
+    return terminator;
+}
+
+/// Whether `expr` is a constant that's never 0
+fn always_true(expr: &Expr) -> bool {
+    match expr.kind {
+        ExprKind::IntLiteral(value) => return value != 0,
+        ExprKind::UIntLiteral(value) => return value != 0,
+        ExprKind::LongLiteral(value) => return value != 0,
+        ExprKind::ULongLiteral(value) => return value != 0,
+        ExprKind::CharLiteral(value) => return value != 0,
+        ExprKind::ParenList(&[ref inner]) => return always_true(inner),
+        _ => return false,
+    }
+}
+
+/// Whether any `break` in `stmts` leaves the loop they're the body of, rather
+/// than a loop nested inside it
+fn breaks_out(stmts: &[Stmt]) -> bool {
+    return stmts.iter().any(|stmt| match &stmt.kind {
+        StmtKind::Break => true,
+        StmtKind::Branch {
+            if_body, else_body, ..
+        } => breaks_out(if_body.stmts) || breaks_out(else_body.stmts),
+        StmtKind::Block(block) => breaks_out(block.stmts),
+        _ => false,
+    });
+}
@@ -1,12 +1,15 @@
 use crate::ast::*;
 use crate::buckets::*;
+use crate::explain::runtime_code;
 use crate::filedb::*;
 use crate::runtime::*;
 use crate::util::*;
-use core::fmt;
+use core::ops::Range;
+use core::{fmt, mem};
 use serde::Serialize;
 use std::collections::HashMap;
-use std::io::Write;
+use std::io::{BufRead, Write};
+use std::time::{Duration, Instant};
 
 macro_rules! error {
     ($arg1:tt,$($arg:tt)*) => {
@@ -34,17 +37,160 @@ pub fn render_err(error: &IError, stack_trace: &Vec<CallFrame>, program: &Progra
         end_context_lines: 1,
     };
 
-    write!(out, "{}: {}\n", error.short_name, error.message).unwrap();
+    let (name, code) = (&error.short_name, error.code);
+    write!(out, "{}[{}]: {}\n", name, code, error.message).unwrap();
+
+    let frames: Vec<_> = stack_trace.iter().skip(1).collect();
+    let mut idx = 0;
+    while idx < frames.len() {
+        let frame = frames[idx];
+        let repeats = frames[idx..]
+            .iter()
+            .take_while(|next| next.loc == frame.loc);
+        let repeats = repeats.count();
 
-    for frame in stack_trace.iter().skip(1) {
         let diagnostic = Diagnostic::new(Severity::Void)
             .with_labels(vec![Label::primary(frame.loc.file, frame.loc)]);
         codespan_reporting::term::emit(&mut out, &config, &program.files, &diagnostic).unwrap();
+
+        // Recursion makes the same call over and over, so only its first and
+        // last frames are shown
+        if repeats > 2 {
+            let name = &program.files.symbols[frame.name as usize];
+            let times = with_commas(repeats as u64 - 2);
+            write!(out, "`{}` called itself {} more times\n", name, times).unwrap();
+            idx += repeats - 1;
+        } else {
+            idx += 1;
+        }
+    }
+
+    if error.labels.len() != 0 {
+        let labels = error.labels.iter();
+        let labels = labels.map(|(loc, msg)| Label::secondary(loc.file, *loc).with_message(msg));
+        let diagnostic = Diagnostic::new(Severity::Void).with_labels(labels.collect());
+        codespan_reporting::term::emit(&mut out, &config, &program.files, &diagnostic).unwrap();
+    }
+
+    write_explain_hint(&mut out, code);
+
+    return out.to_string();
+}
+
+pub fn render_leaks(leaks: &[Leak], program: &Program) -> String {
+    use codespan_reporting::diagnostic::*;
+    use codespan_reporting::term::*;
+
+    let mut out = StringWriter::new();
+    let config = Config::default();
+
+    let bytes: u64 = leaks.iter().map(|leak| leak.bytes).sum();
+    let blocks: u32 = leaks.iter().map(|leak| leak.blocks).sum();
+    let plural = if blocks == 1 { "" } else { "s" };
+    write!(
+        out,
+        "MemoryLeak[{}]: {} bytes in {} block{} were never freed\n",
+        runtime_code("MemoryLeak"),
+        bytes,
+        blocks,
+        plural
+    )
+    .unwrap();
+
+    for leak in leaks {
+        let plural = if leak.blocks == 1 { "" } else { "s" };
+        let message = format!("{} bytes in {} block{}", leak.bytes, leak.blocks, plural);
+        let label = Label::primary(leak.loc.file, leak.loc).with_message(message);
+        let diagnostic = Diagnostic::new(Severity::Void).with_labels(vec![label]);
+        codespan_reporting::term::emit(&mut out, &config, &program.files, &diagnostic).unwrap();
+    }
+
+    write_explain_hint(&mut out, runtime_code("MemoryLeak"));
+    return out.to_string();
+}
+
+pub fn render_strict_warnings(warnings: &[StrictWarning], program: &Program) -> String {
+    use codespan_reporting::diagnostic::*;
+    use codespan_reporting::term::*;
+
+    let mut out = StringWriter::new();
+    let config = Config::default();
+
+    for warning in warnings {
+        let code = runtime_code(warning.kind);
+        write!(out, "{}[{}]: {}\n", warning.kind, code, warning.message).unwrap();
+
+        let loc = warning.loc;
+        let mut labels = vec![Label::primary(loc.file, loc)];
+        if let Some((loc, written)) = warning.written {
+            let message = format!("last written as {} here", written.describe());
+            labels.push(Label::secondary(loc.file, loc).with_message(message));
+        }
+
+        let diagnostic = Diagnostic::new(Severity::Void).with_labels(labels);
+        codespan_reporting::term::emit(&mut out, &config, &program.files, &diagnostic).unwrap();
+        write_explain_hint(&mut out, code);
+    }
+
+    return out.to_string();
+}
+
+pub fn render_domain_warnings(warnings: &[DomainWarning], program: &Program) -> String {
+    use codespan_reporting::diagnostic::*;
+    use codespan_reporting::term::*;
+
+    let mut out = StringWriter::new();
+    let config = Config::default();
+    let code = runtime_code("MathDomainWarning");
+
+    for warning in warnings {
+        write!(out, "MathDomainWarning[{}]: {}\n", code, warning.message).unwrap();
+
+        let label = match warning.arg {
+            Some(loc) => Label::primary(loc.file, loc).with_message(warning.why),
+            None => Label::primary(warning.loc.file, warning.loc),
+        };
+
+        let diagnostic = Diagnostic::new(Severity::Void).with_labels(vec![label]);
+        codespan_reporting::term::emit(&mut out, &config, &program.files, &diagnostic).unwrap();
+        write_explain_hint(&mut out, code);
+    }
+
+    return out.to_string();
+}
+
+pub fn render_unclosed_files(files: &[UnclosedFile], program: &Program) -> String {
+    use codespan_reporting::diagnostic::*;
+    use codespan_reporting::term::*;
+
+    let mut out = StringWriter::new();
+    let config = Config::default();
+
+    let plural = if files.len() == 1 { " was" } else { "s were" };
+    write!(
+        out,
+        "UnclosedFile[{}]: {} file{} never closed\n",
+        runtime_code("UnclosedFile"),
+        files.len(),
+        plural
+    )
+    .unwrap();
+
+    for file in files {
+        let message = format!("`{}` opened here", file.path);
+        let label = Label::primary(file.loc.file, file.loc).with_message(message);
+        let diagnostic = Diagnostic::new(Severity::Void).with_labels(vec![label]);
+        codespan_reporting::term::emit(&mut out, &config, &program.files, &diagnostic).unwrap();
     }
 
+    write_explain_hint(&mut out, runtime_code("UnclosedFile"));
     return out.to_string();
 }
 
+fn write_explain_hint(out: &mut StringWriter, code: &str) {
+    write!(out, "for more information, run `tci --explain {}`\n", code).unwrap();
+}
+
 /// Exit the program with an error code
 pub const ECALL_EXIT: u32 = 0;
 
@@ -58,11 +204,65 @@ pub const ECALL_ARGV: u32 = 2;
 /// No symbol associated with this stack/binary var
 pub const META_NO_SYMBOL: u32 = u32::MAX;
 
+/// How many FILE objects a program can have, including stdin, stdout and stderr
+pub const FOPEN_MAX: u32 = 256;
+
+/// Size of the binary var a function pointer points to; it holds the address of
+/// the function's header followed by the function's name symbol
+pub const FUNC_PTR_SIZE: u32 = 8;
+
+/// Largest value rand returns
+pub const RAND_MAX: u32 = 32767;
+
+/// How many ops run between checks of the time limit
+pub const TIME_CHECK_INTERVAL: u64 = 1024;
+
+/// Limits on how much a program can run before it's stopped with a
+/// LimitExceeded error; `None` means there's no limit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    pub max_steps: Option<u64>,       // ops run over the life of the program
+    pub max_time: Option<Duration>,   // time spent in a single call to one of the `run` methods
+    pub max_depth: Option<u32>,       // function calls that haven't returned yet
+    pub max_stack_bytes: Option<u64>, // bytes of local variables and arguments
+}
+
+impl Limits {
+    pub const NONE: Limits = Limits {
+        max_steps: None,
+        max_time: None,
+        max_depth: None,
+        max_stack_bytes: None,
+    };
+
+    /// Programs can run for as long as they like, but runaway recursion is
+    /// stopped long before it uses up the host's memory
+    pub const DEFAULT: Limits = Limits {
+        max_steps: None,
+        max_time: None,
+        max_depth: Some(10_000),
+        max_stack_bytes: Some(8 * 1024 * 1024),
+    };
+}
+
+/// Most stack variables there can be at once; they're addressed with a u16
+pub const MAX_STACK_VARS: usize = u16::MAX as usize - 1;
+
+/// The state of the machine at the last backwards jump, used to notice loops
+/// that come back around without anything having changed
+#[derive(Debug, Clone, Copy)]
+struct LoopState {
+    pc: u32,
+    fp: u16,
+    history_index: usize,
+    stack_len: usize,
+}
+
 /// - GetLocal gets a value from the stack at a given stack and variable offset
 /// - SetLocal sets a value on the stack at a given stack and variable offset to the value at the top
 ///   of the stack
 /// - Set and Get are equivalent of GetLocal and SetLocal, but the location they access is
-///   determined by popping the top of the stack first
+///   determined by popping the top of the stack first; `access` is the kind of value they move
 /// - PopKeep pops keep-many bytes off the stack, then pops drop-many bytes off the stack and
 ///   repushes the first set of popped bytes back onto  the stack
 /// - CompLt compares pops t, the top of the stack, and compares it to n, the next item on the stack.
@@ -74,11 +274,22 @@ pub const META_NO_SYMBOL: u32 = u32::MAX;
 #[derive(Debug, Clone, Copy, Serialize)]
 #[serde(tag = "code", content = "data")]
 pub enum Opcode {
-    Func(u32), // Function header used for callstack manipulation
+    // Function header used for callstack manipulation; `params` is the index in
+    // `Program::symbols` of the first of the function's `param_count` parameters
+    Func {
+        name: u32,
+        params: u32,
+        param_count: u32,
+    },
 
-    StackAlloc { bytes: u32, symbol: u32 }, // Allocates space on the stack
-    StackAllocDyn { symbol: u32 },          // Allocates space on the stack based on a u32 pop
-    StackDealloc,                           // Pops a variable off of the stack
+    StackAlloc {
+        bytes: u32,
+        symbol: u32,
+    }, // Allocates space on the stack
+    StackAllocDyn {
+        symbol: u32,
+    }, // Allocates space on the stack based on a u32 pop
+    StackDealloc,   // Pops a variable off of the stack
     StackAddToTemp, // Pops a variable off the stack, adding it to the temporary storage below
 
     MakeTempI8(i8),
@@ -86,15 +297,36 @@ pub enum Opcode {
     MakeTempI64(i64),
     MakeTempU64(u64),
     MakeTempF64(f64),
-    MakeTempBinaryPtr { var: u32, offset: u32 },
-    MakeTempLocalStackPtr { var: i16, offset: u32 },
+    MakeTempBinaryPtr {
+        var: u32,
+        offset: u32,
+    },
+    MakeTempLocalStackPtr {
+        var: i16,
+        offset: u32,
+    },
 
-    Pop { bytes: u32 },
-    PopKeep { keep: u32, drop: u32 },
-    PushUndef { bytes: u32 },       // Push undefined bytes onto the stack
-    PushDup { bytes: u32 },         // Push bytes duplicated from the top of the stack
-    Swap { top: u32, bottom: u32 }, // Swap some number of top bytes with some number of bytes below
-    PopIntoTopVar { offset: u32, bytes: u32 },
+    Pop {
+        bytes: u32,
+    },
+    PopKeep {
+        keep: u32,
+        drop: u32,
+    },
+    PushUndef {
+        bytes: u32,
+    }, // Push undefined bytes onto the stack
+    PushDup {
+        bytes: u32,
+    }, // Push bytes duplicated from the top of the stack
+    Swap {
+        top: u32,
+        bottom: u32,
+    }, // Swap some number of top bytes with some number of bytes below
+    PopIntoTopVar {
+        offset: u32,
+        bytes: u32,
+    },
 
     SExtend8To16,
     SExtend8To32,
@@ -110,25 +342,58 @@ pub enum Opcode {
     ZExtend16To64,
     ZExtend32To64,
 
-    GetLocal { var: i16, offset: u32, bytes: u32 },
-    SetLocal { var: i16, offset: u32, bytes: u32 },
+    GetLocal {
+        var: i16,
+        offset: u32,
+        bytes: u32,
+    },
+    SetLocal {
+        var: i16,
+        offset: u32,
+        bytes: u32,
+    },
 
-    Get { offset: u32, bytes: u32 },
-    Set { offset: u32, bytes: u32 },
+    Get {
+        offset: u32,
+        bytes: u32,
+        access: Access,
+    },
+    Set {
+        offset: u32,
+        bytes: u32,
+        access: Access,
+    },
+    MemberArray {
+        struct_id: u32,
+        member: u32,
+    }, // Marks the pointer on top of the stack as pointing into the given array member
 
+    AddI32,
     AddU32,
+    AddI64,
     AddU64,
 
     SubI32,
+    SubU32,
     SubI64,
     SubU64,
 
     MulI32,
+    MulU32,
 
     DivI32,
+    DivU32,
     DivI64,
     DivU64,
 
+    ModI32,
+    ModU32,
+    ModI64,
+    ModU64,
+
+    NegI32,
+    NegI64,
+
     CompLtI32,
     CompLtU64,
     CompLeqI32,
@@ -138,9 +403,29 @@ pub enum Opcode {
     CompNeq32,
     CompEq64,
 
+    AddPtr,
+    SubPtr {
+        elem_size: u32,
+    },
+    CompLtPtr,
+    CompLeqPtr,
+    CompEqPtr,
+
     MulI64,
     MulU64,
-    ModI64,
+
+    AddF64,
+    SubF64,
+    MulF64,
+    DivF64,
+
+    CompLtF64,
+    CompLeqF64,
+    CompEqF64,
+
+    I64ToF64,
+    U64ToF64,
+    F64ToI64,
 
     Jump(u32),
 
@@ -161,6 +446,50 @@ pub enum Opcode {
     Ecall(u32),
 }
 
+/// The kind of value a Get or Set moves. Strict mode records it as the
+/// effective type of the heap memory being written, and checks reads against
+/// it; signed and unsigned versions of a type are allowed to alias each other,
+/// so they share a kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Access {
+    Char, // can read and write anything, so it's never recorded or checked
+    Int,
+    Long,
+    Double,
+    Pointer,
+    Bytes { align: u32 }, // structs, which are copied without looking inside
+}
+
+impl Access {
+    pub fn align(self) -> u32 {
+        match self {
+            Access::Char => return 1,
+            Access::Int => return 4,
+            Access::Long | Access::Double | Access::Pointer => return 8,
+            Access::Bytes { align } => return align,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Access::Char => return "char",
+            Access::Int => return "int",
+            Access::Long => return "long",
+            Access::Double => return "double",
+            Access::Pointer => return "pointer",
+            Access::Bytes { .. } => return "struct",
+        }
+    }
+
+    /// The name with an article in front, like "an `int`"
+    pub fn describe(self) -> String {
+        match self {
+            Access::Int => return "an `int`".to_string(),
+            access => return format!("a `{}`", access.name()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize)]
 pub struct TaggedOpcode {
     pub op: Opcode,
@@ -206,12 +535,48 @@ impl<'a> fmt::Debug for Program<'a> {
 
 type LibFunc = for<'a> fn(&'a mut Runtime) -> Result<Option<i32>, IError>;
 
+/// Heap memory that was still allocated when the program exited, grouped by
+/// the call that allocated it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Leak {
+    pub loc: CodeLoc,
+    pub blocks: u32,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnclosedFile {
+    pub loc: CodeLoc,
+    pub path: String,
+}
+
+/// Something strict mode noticed that real hardware or an optimizing compiler
+/// might not forgive, reported once per place in the code
+#[derive(Debug, Clone, Serialize)]
+pub struct StrictWarning {
+    pub kind: &'static str,
+    pub message: String,
+    pub loc: CodeLoc,
+    pub written: Option<(CodeLoc, Access)>, // the write that set the memory's effective type
+}
+
+/// A math.h function given a value outside its domain, reported once per call
+/// site when math warnings are on
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainWarning {
+    pub message: String,
+    pub loc: CodeLoc,
+    pub arg: Option<CodeLoc>,
+    pub why: &'static str, // why the argument is outside the domain
+}
+
 #[derive(Debug, Serialize)]
 pub struct RuntimeDiagnostic {
     pub callstack: u32,
     pub fp: u16,
     pub pc: u32,
     pub loc: CodeLoc,
+    pub waiting_for_input: bool,
 }
 
 pub struct Runtime {
@@ -219,6 +584,37 @@ pub struct Runtime {
     pub args: StringArray,
     pub lib_funcs: HashMap<u32, LibFunc>,
     pub program: Program<'static>,
+
+    pub streams: u32, // binary var holding every FILE; the one for fd `n` is at offset `4 * n`
+    argv: Vec<VarPointer>, // the strings in `args`, which live in binary memory
+    pub waiting_for_input: bool,
+    pub exiting: bool, // atexit handlers are running
+
+    /// Whether to warn when math.h functions are given values outside their
+    /// domain; either way they return NaN or infinity like in C
+    pub math_warnings: bool,
+    pub domain_warnings: Vec<DomainWarning>,
+
+    pub limits: Limits,
+    pub steps: u64,
+    started: Instant, // when the current call to one of the `run` methods began
+    last_loop: Option<LoopState>,
+
+    /// Where the last NULL pointer was loaded from, and the pc that loaded it,
+    /// so that dereferencing it can say which variable it came from
+    null_load: Option<(VarPointer, u32)>,
+
+    /// Whether to track the effective type of heap memory, and warn about
+    /// reads through an incompatible type or accesses through misaligned
+    /// pointers
+    pub strict: bool,
+    pub strict_warnings: Vec<StrictWarning>,
+}
+
+/// How a call from a library function back into the program ended
+pub enum Callback {
+    Returned(Vec<u8>),
+    Exited(i32),
 }
 
 impl Runtime {
@@ -230,46 +626,233 @@ impl Runtime {
         lib_funcs.insert(INIT_SYMS.translate["malloc"], malloc);
         lib_funcs.insert(INIT_SYMS.translate["realloc"], realloc);
         lib_funcs.insert(INIT_SYMS.translate["memcpy"], memcpy);
+        lib_funcs.insert(INIT_SYMS.translate["free"], free);
+        lib_funcs.insert(INIT_SYMS.translate["strlen"], strlen);
+        lib_funcs.insert(INIT_SYMS.translate["strcpy"], strcpy);
+        lib_funcs.insert(INIT_SYMS.translate["strncpy"], strncpy);
+        lib_funcs.insert(INIT_SYMS.translate["strcat"], strcat);
+        lib_funcs.insert(INIT_SYMS.translate["strncat"], strncat);
+        lib_funcs.insert(INIT_SYMS.translate["strcmp"], strcmp);
+        lib_funcs.insert(INIT_SYMS.translate["strncmp"], strncmp);
+        lib_funcs.insert(INIT_SYMS.translate["strchr"], strchr);
+        lib_funcs.insert(INIT_SYMS.translate["strrchr"], strrchr);
+        lib_funcs.insert(INIT_SYMS.translate["strstr"], strstr);
+        lib_funcs.insert(INIT_SYMS.translate["strdup"], strdup);
+        lib_funcs.insert(INIT_SYMS.translate["memset"], memset);
+        lib_funcs.insert(INIT_SYMS.translate["memmove"], memmove);
+        lib_funcs.insert(INIT_SYMS.translate["memcmp"], memcmp);
+        lib_funcs.insert(INIT_SYMS.translate["__tci_stream"], tci_stream);
+        lib_funcs.insert(INIT_SYMS.translate["getchar"], getchar);
+        lib_funcs.insert(INIT_SYMS.translate["fgets"], fgets);
+        lib_funcs.insert(INIT_SYMS.translate["scanf"], scanf);
+        lib_funcs.insert(INIT_SYMS.translate["fopen"], fopen);
+        lib_funcs.insert(INIT_SYMS.translate["fclose"], fclose);
+        lib_funcs.insert(INIT_SYMS.translate["fgetc"], fgetc);
+        lib_funcs.insert(INIT_SYMS.translate["fputc"], fputc);
+        lib_funcs.insert(INIT_SYMS.translate["fprintf"], fprintf);
+        lib_funcs.insert(INIT_SYMS.translate["fscanf"], fscanf);
+        lib_funcs.insert(INIT_SYMS.translate["fread"], fread);
+        lib_funcs.insert(INIT_SYMS.translate["fwrite"], fwrite);
+        lib_funcs.insert(INIT_SYMS.translate["calloc"], calloc);
+        lib_funcs.insert(INIT_SYMS.translate["atoi"], atoi);
+        lib_funcs.insert(INIT_SYMS.translate["atol"], atol);
+        lib_funcs.insert(INIT_SYMS.translate["strtol"], strtol);
+        lib_funcs.insert(INIT_SYMS.translate["strtoul"], strtoul);
+        lib_funcs.insert(INIT_SYMS.translate["abs"], abs);
+        lib_funcs.insert(INIT_SYMS.translate["labs"], labs);
+        lib_funcs.insert(INIT_SYMS.translate["rand"], rand);
+        lib_funcs.insert(INIT_SYMS.translate["srand"], srand);
+        lib_funcs.insert(INIT_SYMS.translate["atexit"], atexit);
+        lib_funcs.insert(INIT_SYMS.translate["qsort"], qsort);
+        lib_funcs.insert(INIT_SYMS.translate["bsearch"], bsearch);
+        lib_funcs.insert(INIT_SYMS.translate["__tci_assert"], tci_assert);
+        lib_funcs.insert(INIT_SYMS.translate["isalnum"], isalnum);
+        lib_funcs.insert(INIT_SYMS.translate["isalpha"], isalpha);
+        lib_funcs.insert(INIT_SYMS.translate["isdigit"], isdigit);
+        lib_funcs.insert(INIT_SYMS.translate["isxdigit"], isxdigit);
+        lib_funcs.insert(INIT_SYMS.translate["islower"], islower);
+        lib_funcs.insert(INIT_SYMS.translate["isupper"], isupper);
+        lib_funcs.insert(INIT_SYMS.translate["isspace"], isspace);
+        lib_funcs.insert(INIT_SYMS.translate["ispunct"], ispunct);
+        lib_funcs.insert(INIT_SYMS.translate["isprint"], isprint);
+        lib_funcs.insert(INIT_SYMS.translate["iscntrl"], iscntrl);
+        lib_funcs.insert(INIT_SYMS.translate["tolower"], tolower);
+        lib_funcs.insert(INIT_SYMS.translate["toupper"], toupper);
+        lib_funcs.insert(INIT_SYMS.translate["sqrt"], sqrt);
+        lib_funcs.insert(INIT_SYMS.translate["pow"], pow);
+        lib_funcs.insert(INIT_SYMS.translate["fabs"], fabs);
+        lib_funcs.insert(INIT_SYMS.translate["floor"], floor);
+        lib_funcs.insert(INIT_SYMS.translate["ceil"], ceil);
+        lib_funcs.insert(INIT_SYMS.translate["sin"], sin);
+        lib_funcs.insert(INIT_SYMS.translate["cos"], cos);
+        lib_funcs.insert(INIT_SYMS.translate["log"], log);
+        lib_funcs.insert(INIT_SYMS.translate["exp"], exp);
+
+        let mut memory = Memory::new_with_binary(program.data);
+        let streams = memory.binary.add_var(4 * FOPEN_MAX, META_NO_SYMBOL);
+        for fd in 0..FOPEN_MAX {
+            let stream = VarPointer::new_binary(streams, 4 * fd);
+            memory.binary.set(stream, fd.to_be()).unwrap();
+        }
+
+        // The program didn't allocate its arguments, so they aren't on the heap,
+        // where they'd be reported as leaks
+        let mut argv = Vec::new();
+        for idx in 0..args.len() {
+            let arg = args[idx].as_bytes();
+            let var = memory.binary.add_var(arg.len() as u32 + 1, META_NO_SYMBOL);
+            memory.binary.get_full_var_range_mut(var)[..arg.len()].copy_from_slice(arg);
+            argv.push(VarPointer::new_binary(var, 0));
+        }
 
-        let memory = Memory::new_with_binary(program.data);
         let s = Self {
             args,
+            argv,
             memory,
             program,
             lib_funcs,
+            streams,
+            waiting_for_input: false,
+            exiting: false,
+            math_warnings: false,
+            domain_warnings: Vec::new(),
+            limits: Limits::DEFAULT,
+            steps: 0,
+            started: Instant::now(),
+            last_loop: None,
+            null_load: None,
+            strict: false,
+            strict_warnings: Vec::new(),
         };
         return s;
     }
 
+    /// Adds input for the program to read. An empty string closes stdin, so that
+    /// further reads see EOF.
+    pub fn push_stdin(&mut self, input: &str) {
+        if input.len() == 0 {
+            self.memory.stdin_closed = true;
+        } else {
+            self.memory.stdin.extend_from_slice(input.as_bytes());
+        }
+
+        self.waiting_for_input = false;
+    }
+
+    /// Whether `ready` accepts the unread part of stdin, or stdin is closed. If
+    /// not, the program waits for input, and the current op has to be retried
+    /// after `push_stdin`.
+    fn fill_stdin(&mut self, mut ready: impl FnMut(&[u8]) -> bool) -> bool {
+        if self.memory.stdin_closed || ready(self.memory.stdin_unread()) {
+            return true;
+        }
+
+        self.waiting_for_input = true;
+        return false;
+    }
+
+    /// Reads a line from `input` for a program that's waiting for one; reaching
+    /// the end of `input` closes stdin. Time spent waiting doesn't count
+    /// against the time limit.
+    fn read_stdin(&mut self, input: &mut impl BufRead) -> Result<(), IError> {
+        let waiting = Instant::now();
+        let mut line = Vec::new();
+        input
+            .read_until(b'\n', &mut line)
+            .map_err(|err| error!("ReadFailed", "failed to read from stdin ({})", err))?;
+
+        self.push_stdin(&String::from_utf8_lossy(&line));
+        self.started += waiting.elapsed();
+        return Ok(());
+    }
+
+    /// Returns every heap allocation that hasn't been freed, largest first
+    pub fn leaks(&self) -> Vec<Leak> {
+        let mut leaks: Vec<Leak> = Vec::new();
+        let heap = &self.memory.heap;
+        for (var, site) in heap.vars.iter().zip(self.memory.heap_sites.iter()) {
+            if !var.is_valid() {
+                continue;
+            }
+
+            match leaks.iter_mut().find(|leak| leak.loc == site.alloc) {
+                Some(leak) => {
+                    leak.blocks += 1;
+                    leak.bytes += var.len as u64;
+                }
+                None => leaks.push(Leak {
+                    loc: site.alloc,
+                    blocks: 1,
+                    bytes: var.len as u64,
+                }),
+            }
+        }
+
+        leaks.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+        return leaks;
+    }
+
+    /// Returns every file that was opened with fopen but never closed
+    pub fn unclosed_files(&self) -> Vec<UnclosedFile> {
+        let open = self.memory.open_files.iter();
+        let open = open.filter(|file| file.close.is_none());
+        let files = open.map(|file| UnclosedFile {
+            loc: file.open,
+            path: self.memory.files[file.file as usize].path.clone(),
+        });
+
+        return files.collect();
+    }
+
     pub fn diagnostic(&self) -> RuntimeDiagnostic {
         RuntimeDiagnostic {
             callstack: self.memory.callstack.len() as u32, // TODO handle overflow
             fp: self.memory.fp,
             pc: self.memory.pc,
             loc: self.program.ops[self.memory.pc as usize].loc,
+            waiting_for_input: self.waiting_for_input,
         }
     }
 
-    pub fn run(&mut self, mut io: impl Write) -> Result<i32, IError> {
+    pub fn run(&mut self, mut io: impl RuntimeIO) -> Result<i32, IError> {
+        self.started = Instant::now();
         loop {
-            let ret = self.run_op();
+            let ret = self.run_op_timed();
+            let mut wrote = false;
             for event in self.memory.events() {
-                let string = event.to_string();
-                write!(io, "{}", string)?;
+                match event {
+                    WriteEvent::StdoutWrite(value) => write!(io.out(), "{}", value)?,
+                    WriteEvent::StderrWrite(value) => write!(io.err(), "{}", value)?,
+                    WriteEvent::Unwind(_) => continue,
+                }
+                wrote = true;
+            }
+
+            if wrote {
+                io.out().flush()?; // so prompts show up before the program reads input
+                io.err().flush()?;
             }
 
             if let Some(exit) = ret? {
                 return Ok(exit);
             }
+
+            if self.waiting_for_input {
+                self.read_stdin(io.input())?;
+            }
         }
     }
 
     pub fn run_op_count(&mut self, mut count: u32) -> Result<Option<i32>, IError> {
+        self.started = Instant::now();
         let callstack_len = self.memory.callstack.len();
         while count > 0 {
-            if let Some(exit) = self.run_op()? {
+            if let Some(exit) = self.run_op_timed()? {
                 return Ok(Some(exit));
             }
+            if self.waiting_for_input {
+                break;
+            }
             count -= 1;
         }
 
@@ -282,11 +865,15 @@ impl Runtime {
         pc: u32,
         stack_size: u16,
     ) -> Result<Option<i32>, IError> {
+        self.started = Instant::now();
         let stack_size = stack_size as usize;
         while stack_size <= self.memory.callstack.len() && count > 0 && self.memory.pc != pc {
-            if let Some(exit) = self.run_op()? {
+            if let Some(exit) = self.run_op_timed()? {
                 return Ok(Some(exit));
             }
+            if self.waiting_for_input {
+                break;
+            }
 
             count -= 1;
         }
@@ -295,14 +882,197 @@ impl Runtime {
     }
 
     pub fn run_op(&mut self) -> Result<Option<i32>, IError> {
-        match self.run_op_internal() {
-            Ok(opt) => return Ok(opt),
-            Err(err) => {
-                self.memory
-                    .push_callstack(self.program.ops[self.memory.pc as usize].loc);
-                return Err(err);
+        self.started = Instant::now();
+        return self.run_op_timed();
+    }
+
+    /// Runs an op, as part of the run that began at `self.started`
+    fn run_op_timed(&mut self) -> Result<Option<i32>, IError> {
+        let result = self.count_step().and_then(|()| self.run_op_internal());
+        return result.map_err(|err| self.push_current_frame(err));
+    }
+
+    /// Counts an op that's about to run, and errors if running it would go
+    /// past the step or time limits. Functions called back from library
+    /// functions count against the same limits as the rest of the program.
+    fn count_step(&mut self) -> Result<(), IError> {
+        self.steps += 1;
+        if let Some(max_steps) = self.limits.max_steps {
+            if self.steps > max_steps {
+                return err!(
+                    "LimitExceeded",
+                    "the program ran for more than {} steps without finishing",
+                    max_steps
+                );
+            }
+        }
+
+        if let Some(max_time) = self.limits.max_time {
+            if self.steps % TIME_CHECK_INTERVAL == 0 && self.started.elapsed() > max_time {
+                return err!(
+                    "LimitExceeded",
+                    "the program ran for more than {} without finishing",
+                    describe_duration(max_time)
+                );
+            }
+        }
+
+        return Ok(());
+    }
+
+    /// Adds the op that's running to the callstack, so that `err` shows where
+    /// the program was when it happened
+    fn push_current_frame(&mut self, err: IError) -> IError {
+        self.memory
+            .push_callstack(self.program.ops[self.memory.pc as usize].loc);
+        return err;
+    }
+
+    /// Errors if the backwards jump at the current pc brings the program back
+    /// to exactly the state it was in the last time it got here; nothing can
+    /// change on later trips around the loop, so the loop never ends
+    fn check_loop(&mut self) -> Result<(), IError> {
+        let state = LoopState {
+            pc: self.memory.pc,
+            fp: self.memory.fp,
+            history_index: self.memory.history_index,
+            stack_len: self.memory.stack.data.len(),
+        };
+
+        // Stepping backwards can leave the last state ahead of, or level with,
+        // where the program is now, and then it says nothing about this loop
+        if let Some(prev) = self.last_loop {
+            let same_place = prev.pc == state.pc && prev.fp == state.fp;
+            let moved_on = prev.history_index < state.history_index;
+            if same_place
+                && moved_on
+                && self
+                    .memory
+                    .unchanged_since(prev.history_index, prev.stack_len)
+            {
+                self.last_loop = None;
+                return Err(error!(
+                    "InfiniteLoop",
+                    "this loop can never terminate, because nothing changes from one time around it to the next"
+                ));
+            }
+        }
+
+        self.last_loop = Some(state);
+        return Ok(());
+    }
+
+    /// Errors if allocating `bytes` more on the stack would go past the
+    /// limits on how big it can get
+    fn check_stack(&self, bytes: u32) -> Result<(), IError> {
+        if self.memory.stack.vars.len() >= MAX_STACK_VARS {
+            let err = error!(
+                "StackOverflow",
+                "the program had more than {} local variables at once", MAX_STACK_VARS
+            );
+            return Err(self.with_recursion_labels(err, None));
+        }
+
+        if let Some(max_bytes) = self.limits.max_stack_bytes {
+            if self.memory.stack.data.len() as u64 + bytes as u64 > max_bytes {
+                let err = error!(
+                    "StackOverflow",
+                    "the program used more than {} bytes of stack space",
+                    with_commas(max_bytes)
+                );
+                return Err(self.with_recursion_labels(err, None));
+            }
+        }
+
+        return Ok(());
+    }
+
+    /// Labels the first and last calls of the recursion that filled up the
+    /// stack with the arguments they were given. `pending` is the header of a
+    /// function that's about to be called from the current op.
+    fn with_recursion_labels(&self, mut err: IError, pending: Option<u32>) -> IError {
+        let callstack = &self.memory.callstack;
+
+        // Each frame is a call site; the function it called is whatever was
+        // running when the next frame was pushed
+        let mut calls = Vec::new();
+        for (idx, frame) in callstack.iter().enumerate().skip(1) {
+            let (callee, fp) = match callstack.get(idx + 1) {
+                Some(next) => (next.name, next.fp),
+                None => (self.memory.current_func, self.memory.fp),
+            };
+
+            calls.push((frame.loc, self.func_header(callee), fp));
+        }
+
+        if let Some(func) = pending {
+            let loc = self.program.ops[self.memory.pc as usize].loc;
+            calls.push((loc, Some(func), self.memory.stack_length() + 1));
+        }
+
+        let last = match calls.last() {
+            Some(&last) => last,
+            None => return err,
+        };
+
+        let repeats = calls.iter().rev().take_while(|call| call.0 == last.0);
+        let first_idx = calls.len() - repeats.count();
+        let first = calls[first_idx.saturating_sub(1)];
+
+        if let (loc, Some(func), fp) = first {
+            if loc != last.0 {
+                let message = format!("first call: {}", self.describe_call(func, fp));
+                err = err.with_label(loc, &message);
             }
         }
+
+        if let (loc, Some(func), fp) = last {
+            let message = format!("last call: {}", self.describe_call(func, fp));
+            err = err.with_label(loc, &message);
+        }
+
+        return err;
+    }
+
+    /// Finds the header of the function called `name`
+    fn func_header(&self, name: u32) -> Option<u32> {
+        let ops = self.program.ops.iter().enumerate();
+        let mut headers = ops.filter(|(_, op)| match op.op {
+            Opcode::Func { name: func, .. } => func == name,
+            _ => false,
+        });
+
+        return headers.next().map(|(idx, _)| idx as u32);
+    }
+
+    /// Describes a call to the function with header `func` whose frame starts
+    /// at `fp`, like `factorial(n = 3)`
+    fn describe_call(&self, func: u32, fp: u16) -> String {
+        let (name, params, param_count) = match self.program.ops[func as usize].op {
+            Opcode::Func {
+                name,
+                params,
+                param_count,
+            } => (name, params, param_count),
+            op => panic!("found function header {:?} (this is an error in tci)", op),
+        };
+
+        let mut args = Vec::new();
+        for param in 0..param_count {
+            let var = match self.program.symbols.get((params + param) as usize) {
+                Some(var) if (fp as u32) > param_count => var,
+                _ => continue,
+            };
+
+            let idx = fp - param_count as u16 + param as u16;
+            let value = self.describe_value(VarPointer::new_stack(idx, 0), var.decl_type);
+            let value = value.unwrap_or_else(|| "...".to_string());
+            let param_name = &self.program.files.symbols[var.symbol as usize];
+            args.push(format!("{} = {}", param_name, value));
+        }
+
+        let name = &self.program.files.symbols[name as usize];
+        return format!("{}({})", name, args.join(", "));
     }
 
     #[inline]
@@ -312,14 +1082,18 @@ impl Runtime {
         // .map_err(|err| error!("WriteFailed", "failed to write to logs ({})", err))?;
 
         let opcode = op.op;
+        self.memory.taint = 0;
+        self.memory.provenance = 0;
         match opcode {
-            Opcode::Func(_) => {}
+            Opcode::Func { .. } => {}
 
             Opcode::StackAlloc { bytes, symbol } => {
+                self.check_stack(bytes)?;
                 self.memory.add_stack_var(bytes, symbol);
             }
             Opcode::StackAllocDyn { symbol } => {
                 let space = u32::from_be(self.memory.pop_stack()?);
+                self.check_stack(space)?;
                 self.memory.add_stack_var(space, symbol);
             }
             Opcode::StackDealloc => {
@@ -333,7 +1107,7 @@ impl Runtime {
             Opcode::MakeTempI32(value) => self.memory.push_stack(value.to_be()),
             Opcode::MakeTempI64(value) => self.memory.push_stack(value.to_be()),
             Opcode::MakeTempU64(value) => self.memory.push_stack(value.to_be()),
-            Opcode::MakeTempF64(value) => self.memory.push_stack(value),
+            Opcode::MakeTempF64(value) => self.memory.push_stack(value.to_bits().to_be()),
             Opcode::MakeTempBinaryPtr { var, offset } => {
                 let ptr = VarPointer::new_binary(var, offset);
                 self.memory.push_stack(ptr);
@@ -415,25 +1189,69 @@ impl Runtime {
             Opcode::GetLocal { var, offset, bytes } => {
                 let ptr = VarPointer::new_stack(self.memory.fp_offset(var), offset);
                 self.memory.push_stack_bytes_from(ptr, bytes)?;
+                self.note_null_load(ptr, bytes);
             }
             Opcode::SetLocal { var, offset, bytes } => {
                 let ptr = VarPointer::new_stack(self.memory.fp_offset(var), offset);
                 self.memory.pop_stack_bytes_into(ptr, bytes)?;
             }
 
-            Opcode::Get { offset, bytes } => {
+            Opcode::Get {
+                offset,
+                bytes,
+                access,
+            } => {
                 let ptr: VarPointer = self.memory.pop_stack()?;
+                let sub_object = self.memory.provenance;
+                self.check_init(self.memory.taint, "this pointer dereference")?;
+                self.check_null(ptr, "reads")?;
                 // TODO check for overflow
                 let ptr = ptr.with_offset(ptr.offset().wrapping_add(offset as u32));
+                self.check_sub_object(sub_object, ptr, bytes)?;
                 self.memory.push_stack_bytes_from(ptr, bytes)?;
+                self.note_null_load(ptr, bytes);
+                if self.strict {
+                    self.check_alignment(ptr, access, "reads");
+                    self.check_effective_type(ptr, bytes, access);
+                }
             }
-            Opcode::Set { offset, bytes } => {
+            Opcode::Set {
+                offset,
+                bytes,
+                access,
+            } => {
                 let ptr: VarPointer = self.memory.pop_stack()?;
+                let sub_object = self.memory.provenance;
+                self.check_init(self.memory.taint, "this pointer dereference")?;
+                self.check_null(ptr, "writes")?;
                 // TODO check for overflow
                 let ptr = ptr.with_offset(ptr.offset().wrapping_add(offset as u32));
+                self.check_sub_object(sub_object, ptr, bytes)?;
                 self.memory.pop_stack_bytes_into(ptr, bytes)?;
+                if self.strict {
+                    self.check_alignment(ptr, access, "writes");
+                    self.set_effective_type(ptr, bytes, Some((access, self.memory.pc)));
+                }
             }
 
+            Opcode::MemberArray { struct_id, member } => {
+                let start: VarPointer = self.memory.pop_stack()?;
+                let len = self.struct_member(struct_id, member).decl_type.size();
+                let tag = self.memory.sub_object_tag(start, len, struct_id, member);
+                self.memory.provenance = tag;
+                self.memory.push_stack(start);
+            }
+
+            Opcode::AddI32 => {
+                let word2 = i32::from_be(self.memory.pop_stack()?);
+                let word1 = i32::from_be(self.memory.pop_stack()?);
+                let result = word1.checked_add(word2);
+                let result = result.ok_or_else(|| {
+                    let exact = word1 as i128 + word2 as i128;
+                    self.overflow_error(op.loc, format!("{} + {}", word1, word2), exact, "int")
+                })?;
+                self.memory.push_stack(result.to_be());
+            }
             Opcode::AddU32 => {
                 let word2 = u32::from_be(self.memory.pop_stack()?);
                 let word1 = u32::from_be(self.memory.pop_stack()?);
@@ -442,22 +1260,78 @@ impl Runtime {
             Opcode::SubI32 => {
                 let word2 = i32::from_be(self.memory.pop_stack()?);
                 let word1 = i32::from_be(self.memory.pop_stack()?);
+                let result = word1.checked_sub(word2);
+                let result = result.ok_or_else(|| {
+                    let exact = word1 as i128 - word2 as i128;
+                    self.overflow_error(op.loc, format!("{} - {}", word1, word2), exact, "int")
+                })?;
+                self.memory.push_stack(result.to_be());
+            }
+            Opcode::SubU32 => {
+                let word2 = u32::from_be(self.memory.pop_stack()?);
+                let word1 = u32::from_be(self.memory.pop_stack()?);
                 self.memory.push_stack(word1.wrapping_sub(word2).to_be());
             }
             Opcode::MulI32 => {
                 let word2 = i32::from_be(self.memory.pop_stack()?);
                 let word1 = i32::from_be(self.memory.pop_stack()?);
+                let result = word1.checked_mul(word2);
+                let result = result.ok_or_else(|| {
+                    let exact = word1 as i128 * word2 as i128;
+                    self.overflow_error(op.loc, format!("{} * {}", word1, word2), exact, "int")
+                })?;
+                self.memory.push_stack(result.to_be());
+            }
+            Opcode::MulU32 => {
+                let word2 = u32::from_be(self.memory.pop_stack()?);
+                let word1 = u32::from_be(self.memory.pop_stack()?);
                 self.memory.push_stack(word1.wrapping_mul(word2).to_be());
             }
             Opcode::DivI32 => {
                 let word2 = i32::from_be(self.memory.pop_stack()?);
                 let word1 = i32::from_be(self.memory.pop_stack()?);
-                self.memory.push_stack(word1.wrapping_div(word2).to_be());
+                self.check_divisor(word2 as u64)?;
+                if word1 == i32::MIN && word2 == -1 {
+                    let exact = -(word1 as i128);
+                    let values = format!("{} / {}", word1, word2);
+                    return Err(self.overflow_error(op.loc, values, exact, "int"));
+                }
+                self.memory.push_stack((word1 / word2).to_be());
+            }
+            Opcode::DivU32 => {
+                let word2 = u32::from_be(self.memory.pop_stack()?);
+                let word1 = u32::from_be(self.memory.pop_stack()?);
+                self.check_divisor(word2 as u64)?;
+                self.memory.push_stack((word1 / word2).to_be());
+            }
+            Opcode::ModI32 => {
+                let word2 = i32::from_be(self.memory.pop_stack()?);
+                let word1 = i32::from_be(self.memory.pop_stack()?);
+                self.check_divisor(word2 as u64)?;
+                if word1 == i32::MIN && word2 == -1 {
+                    return Err(self.remainder_overflow_error(op.loc, "INT_MIN"));
+                }
+                self.memory.push_stack((word1 % word2).to_be());
+            }
+            Opcode::ModU32 => {
+                let word2 = u32::from_be(self.memory.pop_stack()?);
+                let word1 = u32::from_be(self.memory.pop_stack()?);
+                self.check_divisor(word2 as u64)?;
+                self.memory.push_stack((word1 % word2).to_be());
+            }
+            Opcode::NegI32 => {
+                let word = i32::from_be(self.memory.pop_stack()?);
+                let result = word.checked_neg().ok_or_else(|| {
+                    let values = format!("-({})", word);
+                    self.overflow_error(op.loc, values, -(word as i128), "int")
+                })?;
+                self.memory.push_stack(result.to_be());
             }
             Opcode::DivU64 => {
                 let word2 = u64::from_be(self.memory.pop_stack()?);
                 let word1 = u64::from_be(self.memory.pop_stack()?);
-                self.memory.push_stack(word1.wrapping_div(word2).to_be());
+                self.check_divisor(word2)?;
+                self.memory.push_stack((word1 / word2).to_be());
             }
 
             Opcode::CompLeqI32 => {
@@ -498,6 +1372,60 @@ impl Runtime {
                 self.memory.push_stack((word1 == word2) as u8);
             }
 
+            Opcode::AddPtr => {
+                let delta = i64::from_be(self.memory.pop_stack()?);
+                let ptr: VarPointer = self.memory.pop_stack()?;
+                let offset = ptr.offset() as i64 + delta;
+
+                // Pointers can go anywhere in their object, or one past its end
+                match self.object_var(ptr) {
+                    Some(var) if offset < 0 || offset > var.len as i64 => {
+                        return Err(self.out_of_bounds_error(ptr, offset, var.len));
+                    }
+                    _ => self.memory.push_stack(ptr.with_offset(offset as u32)),
+                }
+            }
+            Opcode::SubPtr { elem_size } => {
+                let ptr2: VarPointer = self.memory.pop_stack()?;
+                let ptr1: VarPointer = self.memory.pop_stack()?;
+                self.check_same_object(ptr1, ptr2, "subtracts")?;
+                let diff = ptr1.offset() as i64 - ptr2.offset() as i64;
+                self.memory
+                    .push_stack((diff / elem_size.max(1) as i64).to_be());
+            }
+            Opcode::CompLtPtr => {
+                let ptr2: VarPointer = self.memory.pop_stack()?;
+                let ptr1: VarPointer = self.memory.pop_stack()?;
+                self.check_compared_freed(ptr1, ptr2)?;
+                self.check_same_object(ptr1, ptr2, "compares")?;
+                self.memory
+                    .push_stack((ptr1.offset() < ptr2.offset()) as u8);
+            }
+            Opcode::CompLeqPtr => {
+                let ptr2: VarPointer = self.memory.pop_stack()?;
+                let ptr1: VarPointer = self.memory.pop_stack()?;
+                self.check_compared_freed(ptr1, ptr2)?;
+                self.check_same_object(ptr1, ptr2, "compares")?;
+                self.memory
+                    .push_stack((ptr1.offset() <= ptr2.offset()) as u8);
+            }
+            Opcode::CompEqPtr => {
+                let ptr2: VarPointer = self.memory.pop_stack()?;
+                let ptr1: VarPointer = self.memory.pop_stack()?;
+                self.check_compared_freed(ptr1, ptr2)?;
+                self.memory.push_stack((ptr1 == ptr2) as u8);
+            }
+
+            Opcode::AddI64 => {
+                let word2 = i64::from_be(self.memory.pop_stack()?);
+                let word1 = i64::from_be(self.memory.pop_stack()?);
+                let result = word1.checked_add(word2);
+                let result = result.ok_or_else(|| {
+                    let exact = word1 as i128 + word2 as i128;
+                    self.overflow_error(op.loc, format!("{} + {}", word1, word2), exact, "long")
+                })?;
+                self.memory.push_stack(result.to_be());
+            }
             Opcode::AddU64 => {
                 let word2 = u64::from_be(self.memory.pop_stack()?);
                 let word1 = u64::from_be(self.memory.pop_stack()?);
@@ -507,7 +1435,12 @@ impl Runtime {
             Opcode::SubI64 => {
                 let word2 = i64::from_be(self.memory.pop_stack()?);
                 let word1 = i64::from_be(self.memory.pop_stack()?);
-                self.memory.push_stack(word1.wrapping_sub(word2).to_be());
+                let result = word1.checked_sub(word2);
+                let result = result.ok_or_else(|| {
+                    let exact = word1 as i128 - word2 as i128;
+                    self.overflow_error(op.loc, format!("{} - {}", word1, word2), exact, "long")
+                })?;
+                self.memory.push_stack(result.to_be());
             }
             Opcode::SubU64 => {
                 let word2 = u64::from_be(self.memory.pop_stack()?);
@@ -517,7 +1450,12 @@ impl Runtime {
             Opcode::MulI64 => {
                 let word2 = i64::from_be(self.memory.pop_stack()?);
                 let word1 = i64::from_be(self.memory.pop_stack()?);
-                self.memory.push_stack(word1.wrapping_mul(word2).to_be());
+                let result = word1.checked_mul(word2);
+                let result = result.ok_or_else(|| {
+                    let exact = word1 as i128 * word2 as i128;
+                    self.overflow_error(op.loc, format!("{} * {}", word1, word2), exact, "long")
+                })?;
+                self.memory.push_stack(result.to_be());
             }
             Opcode::MulU64 => {
                 let word2 = u64::from_be(self.memory.pop_stack()?);
@@ -527,21 +1465,100 @@ impl Runtime {
             Opcode::DivI64 => {
                 let word2 = i64::from_be(self.memory.pop_stack()?);
                 let word1 = i64::from_be(self.memory.pop_stack()?);
-                self.memory.push_stack(word1.wrapping_div(word2).to_be());
+                self.check_divisor(word2 as u64)?;
+                if word1 == i64::MIN && word2 == -1 {
+                    let exact = -(word1 as i128);
+                    let values = format!("{} / {}", word1, word2);
+                    return Err(self.overflow_error(op.loc, values, exact, "long"));
+                }
+                self.memory.push_stack((word1 / word2).to_be());
             }
-            Opcode::ModI64 => {
-                let word2 = u64::from_be(self.memory.pop_stack()?);
-                let word1 = u64::from_be(self.memory.pop_stack()?);
-                self.memory.push_stack((word1 % word2).to_be());
+            Opcode::NegI64 => {
+                let word = i64::from_be(self.memory.pop_stack()?);
+                let result = word.checked_neg().ok_or_else(|| {
+                    let values = format!("-({})", word);
+                    self.overflow_error(op.loc, values, -(word as i128), "long")
+                })?;
+                self.memory.push_stack(result.to_be());
             }
-
+            Opcode::ModI64 => {
+                let word2 = i64::from_be(self.memory.pop_stack()?);
+                let word1 = i64::from_be(self.memory.pop_stack()?);
+                self.check_divisor(word2 as u64)?;
+                if word1 == i64::MIN && word2 == -1 {
+                    return Err(self.remainder_overflow_error(op.loc, "LONG_MIN"));
+                }
+                self.memory.push_stack((word1 % word2).to_be());
+            }
+            Opcode::ModU64 => {
+                let word2 = u64::from_be(self.memory.pop_stack()?);
+                let word1 = u64::from_be(self.memory.pop_stack()?);
+                self.check_divisor(word2)?;
+                self.memory.push_stack((word1 % word2).to_be());
+            }
+
+            Opcode::AddF64 => {
+                let word2 = f64::from_bits(u64::from_be(self.memory.pop_stack()?));
+                let word1 = f64::from_bits(u64::from_be(self.memory.pop_stack()?));
+                self.memory.push_stack((word1 + word2).to_bits().to_be());
+            }
+            Opcode::SubF64 => {
+                let word2 = f64::from_bits(u64::from_be(self.memory.pop_stack()?));
+                let word1 = f64::from_bits(u64::from_be(self.memory.pop_stack()?));
+                self.memory.push_stack((word1 - word2).to_bits().to_be());
+            }
+            Opcode::MulF64 => {
+                let word2 = f64::from_bits(u64::from_be(self.memory.pop_stack()?));
+                let word1 = f64::from_bits(u64::from_be(self.memory.pop_stack()?));
+                self.memory.push_stack((word1 * word2).to_bits().to_be());
+            }
+            Opcode::DivF64 => {
+                let word2 = f64::from_bits(u64::from_be(self.memory.pop_stack()?));
+                let word1 = f64::from_bits(u64::from_be(self.memory.pop_stack()?));
+                self.memory.push_stack((word1 / word2).to_bits().to_be());
+            }
+
+            Opcode::CompLtF64 => {
+                let word2 = f64::from_bits(u64::from_be(self.memory.pop_stack()?));
+                let word1 = f64::from_bits(u64::from_be(self.memory.pop_stack()?));
+                self.memory.push_stack((word1 < word2) as u8);
+            }
+            Opcode::CompLeqF64 => {
+                let word2 = f64::from_bits(u64::from_be(self.memory.pop_stack()?));
+                let word1 = f64::from_bits(u64::from_be(self.memory.pop_stack()?));
+                self.memory.push_stack((word1 <= word2) as u8);
+            }
+            Opcode::CompEqF64 => {
+                let word2 = f64::from_bits(u64::from_be(self.memory.pop_stack()?));
+                let word1 = f64::from_bits(u64::from_be(self.memory.pop_stack()?));
+                self.memory.push_stack((word1 == word2) as u8);
+            }
+
+            Opcode::I64ToF64 => {
+                let val = i64::from_be(self.memory.pop_stack()?);
+                self.memory.push_stack((val as f64).to_bits().to_be());
+            }
+            Opcode::U64ToF64 => {
+                let val = u64::from_be(self.memory.pop_stack()?);
+                self.memory.push_stack((val as f64).to_bits().to_be());
+            }
+            Opcode::F64ToI64 => {
+                let val = f64::from_bits(u64::from_be(self.memory.pop_stack()?));
+                self.memory.push_stack((val as i64).to_be());
+            }
+
             Opcode::Jump(target) => {
+                if target <= self.memory.pc {
+                    self.check_loop()?;
+                }
+
                 self.memory.jump(target);
                 return Ok(None);
             }
 
             Opcode::JumpIfZero8(target) => {
                 let value: u8 = self.memory.pop_stack()?;
+                self.check_init(self.memory.taint, "this branch")?;
                 if value == 0 {
                     self.memory.jump(target);
                     return Ok(None);
@@ -549,6 +1566,7 @@ impl Runtime {
             }
             Opcode::JumpIfZero16(target) => {
                 let value: u16 = self.memory.pop_stack()?;
+                self.check_init(self.memory.taint, "this branch")?;
                 if value == 0 {
                     self.memory.jump(target);
                     return Ok(None);
@@ -556,6 +1574,7 @@ impl Runtime {
             }
             Opcode::JumpIfZero32(target) => {
                 let value: u32 = self.memory.pop_stack()?;
+                self.check_init(self.memory.taint, "this branch")?;
                 if value == 0 {
                     self.memory.jump(target);
                     return Ok(None);
@@ -563,6 +1582,7 @@ impl Runtime {
             }
             Opcode::JumpIfZero64(target) => {
                 let value: u64 = self.memory.pop_stack()?;
+                self.check_init(self.memory.taint, "this branch")?;
                 if value == 0 {
                     self.memory.jump(target);
                     return Ok(None);
@@ -571,6 +1591,7 @@ impl Runtime {
 
             Opcode::JumpIfNotZero8(target) => {
                 let value: u8 = self.memory.pop_stack()?;
+                self.check_init(self.memory.taint, "this branch")?;
                 if value != 0 {
                     self.memory.jump(target);
                     return Ok(None);
@@ -578,6 +1599,7 @@ impl Runtime {
             }
             Opcode::JumpIfNotZero16(target) => {
                 let value: u16 = self.memory.pop_stack()?;
+                self.check_init(self.memory.taint, "this branch")?;
                 if value != 0 {
                     self.memory.jump(target);
                     return Ok(None);
@@ -585,6 +1607,7 @@ impl Runtime {
             }
             Opcode::JumpIfNotZero32(target) => {
                 let value: u32 = self.memory.pop_stack()?;
+                self.check_init(self.memory.taint, "this branch")?;
                 if value != 0 {
                     self.memory.jump(target);
                     return Ok(None);
@@ -592,6 +1615,7 @@ impl Runtime {
             }
             Opcode::JumpIfNotZero64(target) => {
                 let value: u64 = self.memory.pop_stack()?;
+                self.check_init(self.memory.taint, "this branch")?;
                 if value != 0 {
                     self.memory.jump(target);
                     return Ok(None);
@@ -605,15 +1629,34 @@ impl Runtime {
 
             Opcode::Call(func) => {
                 let func_name = match self.program.ops[func as usize].op {
-                    Opcode::Func(name) => name,
+                    Opcode::Func { name, .. } => name,
                     op => panic!("found function header {:?} (this is an error in tci)", op),
                 };
+                if let Some(max_depth) = self.limits.max_depth {
+                    if self.memory.callstack.len() >= max_depth as usize {
+                        let err = error!(
+                            "StackOverflow",
+                            "the program made more than {} function calls without returning",
+                            with_commas(max_depth as u64)
+                        );
+                        return Err(self.with_recursion_labels(err, Some(func)));
+                    }
+                }
+
                 self.memory.call(func + 1, func_name, op.loc);
                 return Ok(None);
             }
             Opcode::LibCall(func_name) => {
                 if let Some(lib_func) = self.lib_funcs.get(&func_name) {
+                    self.waiting_for_input = false;
+                    let history_index = self.memory.history_index;
                     lib_func(self)?;
+                    if self.strict {
+                        self.forget_effective_types(history_index);
+                    }
+                    if self.waiting_for_input {
+                        return Ok(None); // run this call again once there's input
+                    }
                 } else {
                     return Err(error!(
                         "InvalidLibraryFunction",
@@ -624,7 +1667,8 @@ impl Runtime {
             }
 
             Opcode::Ecall(ECALL_EXIT) => {
-                return Ok(Some(i32::from_be(self.memory.pop_stack()?)));
+                let code = i32::from_be(self.memory.pop_stack()?);
+                return self.exit_with(code);
             }
             Opcode::Ecall(ECALL_ARGC) => {
                 self.memory.push_stack((self.args.len() as u32).to_be());
@@ -639,12 +1683,7 @@ impl Runtime {
                     ));
                 }
 
-                let arg = &self.args[arg_idx].as_bytes();
-                let var_pointer = self.memory.add_heap_var(arg.len() as u32 + 1);
-                let str_bytes = self.memory.get_var_slice_mut(var_pointer).unwrap();
-                str_bytes[..arg.len()].copy_from_slice(arg);
-                str_bytes[arg.len()] = 0;
-                self.memory.push_stack(var_pointer);
+                self.memory.push_stack(self.argv[arg_idx]);
             }
             Opcode::Ecall(call) => {
                 return err!("InvalidEnviromentCall", "invalid ecall value of {}", call);
@@ -655,321 +1694,2879 @@ impl Runtime {
         return Ok(None);
     }
 
-    pub fn cstring_bytes(&self, ptr: VarPointer) -> Result<&[u8], IError> {
-        let str_bytes = self.memory.get_var_slice(ptr)?;
-
-        let mut idx = str_bytes.len();
-        for (idx_, byte) in str_bytes.iter().enumerate() {
-            if *byte == 0 {
-                idx = idx_;
-                break;
-            }
+    /// The header address and name symbol of the function `ptr` points to, if it
+    /// points to one
+    pub fn function_at(&self, ptr: VarPointer) -> Option<(u32, u32)> {
+        if !ptr.is_binary() || ptr.offset() != 0 {
+            return None;
         }
 
-        if idx == str_bytes.len() {
-            return err!("MissingNullTerminator", "string missing null terminator");
+        let bytes = self.memory.get_var_slice(ptr).ok()?;
+        if bytes.len() != FUNC_PTR_SIZE as usize {
+            return None;
         }
 
-        return Ok(&str_bytes[0..idx]);
+        let addr = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let name = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        match self.program.ops.get(addr as usize).map(|op| op.op) {
+            Some(Opcode::Func {
+                name: func_name, ..
+            }) if func_name == name => {
+                return Some((addr, name));
+            }
+            _ => return None,
+        }
     }
-}
 
-pub fn memcpy(sel: &mut Runtime) -> Result<Option<i32>, IError> {
-    let stack_len = sel.memory.stack_length();
-    let size_param_ptr = VarPointer::new_stack(stack_len, 0);
-    let src_param_ptr = VarPointer::new_stack(stack_len - 1, 0);
-    let dest_param_ptr = VarPointer::new_stack(stack_len - 2, 0);
-    let ret_ptr = VarPointer::new_stack(stack_len - 3, 0);
+    /// Calls `func` with pointer arguments the same way a `Call` op would, and
+    /// runs the program until the call returns. The pc is left on the current
+    /// op, so the library function calling this can carry on afterwards.
+    pub fn call_function(
+        &mut self,
+        func: (u32, u32),
+        args: &[VarPointer],
+        ret_size: u32,
+    ) -> Result<Callback, IError> {
+        let (addr, name) = func;
+        let (pc, depth) = (self.memory.pc, self.memory.callstack.len());
+        let loc = self.program.ops[pc as usize].loc;
 
-    let size = u64::from_be(sel.memory.get_var(size_param_ptr)?);
-    let mut src: VarPointer = sel.memory.get_var(src_param_ptr)?;
-    let mut dest: VarPointer = sel.memory.get_var(dest_param_ptr)?;
-    let to_ret = dest;
+        self.memory.add_stack_var(ret_size, META_NO_SYMBOL);
+        for arg in args {
+            self.memory.add_stack_var(8, META_NO_SYMBOL);
+            let arg_ptr = VarPointer::new_stack(self.memory.stack_length(), 0);
+            self.memory.set(arg_ptr, *arg)?;
+        }
 
-    for _ in 0..size {
-        // PERFORMANCE this is so slow lmao
-        let byte: u8 = sel.memory.get_var(src)?;
-        sel.memory.set(dest, byte)?;
-        src = src.add(1);
-        dest = dest.add(1);
-    }
+        self.memory.call(addr + 1, name, loc);
+        while self.memory.callstack.len() > depth {
+            self.count_step()?;
+            if let Some(code) = self.run_op_internal()? {
+                return Ok(Callback::Exited(code));
+            }
 
-    sel.memory.set(ret_ptr, to_ret)?;
-    return Ok(None);
-}
+            if self.waiting_for_input {
+                return Err(error!(
+                    "WaitingForInput",
+                    "a function called by a library function tried to wait for input"
+                ));
+            }
+        }
 
-pub fn malloc(sel: &mut Runtime) -> Result<Option<i32>, IError> {
-    let top_ptr = VarPointer::new_stack(sel.memory.stack_length(), 0);
-    let ret_ptr = VarPointer::new_stack(sel.memory.stack_length() - 1, 0);
-    let size = u64::from_be(sel.memory.get_var(top_ptr)?);
-    let var_pointer = sel.memory.add_heap_var(size as u32); // TODO overflow
-    sel.memory.set(ret_ptr, var_pointer)?;
-    return Ok(None);
-}
+        for _ in args {
+            self.memory.pop_stack_var()?;
+        }
 
-pub fn realloc(sel: &mut Runtime) -> Result<Option<i32>, IError> {
-    let stack_len = sel.memory.stack_length();
-    let size_ptr = VarPointer::new_stack(stack_len, 0);
-    let to_free_ptr = VarPointer::new_stack(stack_len - 1, 0);
-    let ret_ptr = VarPointer::new_stack(stack_len - 2, 0);
+        let mut ret = Vec::new();
+        if ret_size != 0 {
+            let ret_ptr = VarPointer::new_stack(self.memory.stack_length(), 0);
+            ret.extend_from_slice(self.memory.get_var_slice(ret_ptr)?);
+        }
 
-    let to_free: VarPointer = sel.memory.get_var(to_free_ptr)?;
-    if !to_free.is_heap() {
-        return Err(error!(
-            "InvalidPointer",
-            "called realloc on a pointer that was not from the heap"
-        ));
+        self.memory.pop_stack_var()?;
+        self.memory.jump(pc);
+        return Ok(Callback::Returned(ret));
     }
 
-    if to_free.offset() != 0 {
-        return Err(error!(
-            "InvalidPointer",
-            "called realloc on a pointer that was {} bytes off of its allocation",
-            to_free.offset()
-        ));
-    }
+    /// Runs the functions registered with atexit, most recent first, and then
+    /// exits with `code`
+    pub fn exit_with(&mut self, code: i32) -> Result<Option<i32>, IError> {
+        if self.exiting {
+            return Ok(Some(code)); // exit was called from one of the handlers
+        }
 
-    let size = u64::from_be(sel.memory.get_var(size_ptr)?);
-    let new_alloc_ptr = sel.memory.add_heap_var(size as u32); // TODO overflow
+        self.exiting = true;
+        let handlers = self.memory.atexit.clone();
+        for handler in handlers.iter().rev() {
+            let func = match self.function_at(*handler) {
+                Some(func) => func,
+                None => {
+                    return err!(
+                        "InvalidFunctionPointer",
+                        "a function registered with atexit was overwritten before exit"
+                    );
+                }
+            };
 
-    let (mut src, mut dest) = (to_free, new_alloc_ptr);
-    for _ in 0..size {
-        // PERFORMANCE this is so slow lmao
-        let byte: u8 = sel.memory.get_var(src)?;
-        sel.memory.set(dest, byte)?;
-        src = src.add(1);
-        dest = dest.add(1);
-    }
+            if let Callback::Exited(code) = self.call_function(func, &[], 0)? {
+                return Ok(Some(code));
+            }
+        }
 
-    sel.memory.set(ret_ptr, new_alloc_ptr)?;
-    return Ok(None);
-}
+        return Ok(Some(code));
+    }
 
-pub fn free(sel: &mut Runtime) -> Result<Option<i32>, IError> {
-    let top_ptr = VarPointer::new_stack(sel.memory.stack_length(), 0);
-    let to_free: VarPointer = sel.memory.get_var(top_ptr)?;
-    return Ok(None);
-}
+    /// Describes the value of the variable called `name` in the current function,
+    /// if there is one that can be shown as a single value
+    pub fn describe_variable(&self, name: &str) -> Option<String> {
+        let named = |meta: u32| {
+            let var = self.program.symbols.get(meta as usize)?;
+            let symbol = self.program.files.symbols.get(var.symbol as usize)?;
+            return if *symbol == name {
+                Some(var.decl_type)
+            } else {
+                None
+            };
+        };
 
-pub fn exit(sel: &mut Runtime) -> Result<Option<i32>, IError> {
-    let top_ptr = VarPointer::new_stack(sel.memory.stack_length(), 0);
-    let exit_code = i32::from_be(sel.memory.get_var(top_ptr)?);
-    return Ok(Some(exit_code));
-}
+        let fp = self.memory.fp;
+        for idx in (fp..=self.memory.stack_length()).rev() {
+            let meta = self.memory.stack.vars[idx as usize - 1].meta;
+            if let Some(decl_type) = named(meta) {
+                return self.describe_value(VarPointer::new_stack(idx, 0), decl_type);
+            }
+        }
 
-pub fn printf(sel: &mut Runtime) -> Result<Option<i32>, IError> {
-    let top_ptr_offset = sel.memory.stack_length();
-    let top_ptr = VarPointer::new_stack(top_ptr_offset, 0);
-    let param_len = i32::from_be(sel.memory.get_var(top_ptr)?);
+        // Parameters sit right below the frame pointer
+        let header = self.program.ops[..=self.memory.pc as usize].iter().rev();
+        let header = header.filter_map(|op| match op.op {
+            Opcode::Func {
+                params,
+                param_count,
+                ..
+            } => Some((params, param_count)),
+            _ => None,
+        });
+        let (params, param_count) = header.take(1).next()?;
+        if self.memory.callstack.len() == 0 || (fp as u32) <= param_count {
+            return None;
+        }
 
-    let mut current_offset = top_ptr_offset - (param_len as u16);
-    let return_offset = current_offset - 1;
-    let format_ptr = VarPointer::new_stack(current_offset, 0); // TODO overflow
-    current_offset += 1;
+        for param in 0..param_count {
+            if let Some(decl_type) = named(params + param) {
+                let idx = fp - param_count as u16 + param as u16;
+                return self.describe_value(VarPointer::new_stack(idx, 0), decl_type);
+            }
+        }
 
-    let mut out = StringWriter::new();
+        return None;
+    }
 
-    let result = printf_internal(sel, format_ptr, current_offset, &mut out);
-    let out = out.into_string();
-    let len = out.len() as i32; // TODO overflow
-    write!(sel.memory.stdout(), "{}", out)?;
-    result?;
+    fn describe_value(&self, ptr: VarPointer, decl_type: TCType) -> Option<String> {
+        if decl_type.array_kind != TCArrayKind::None {
+            return None;
+        }
 
-    let return_ptr = VarPointer::new_stack(return_offset, 0); // TODO overflow
-    sel.memory.set(return_ptr, len.to_be())?;
+        let size = decl_type.size();
+        if self.memory.get_var_slice(ptr).ok()?.len() != size as usize {
+            return None;
+        }
 
-    return Ok(None);
-}
+        if self.memory.uninit_origin(ptr, size) != 0 {
+            return Some("uninitialized".to_string());
+        }
 
-#[allow(unused_assignments)] // TODO remove this when we make this fully standard compliant
-pub fn printf_internal(
-    sel: &mut Runtime,
-    format_ptr: VarPointer,
-    mut current_offset: u16,
-    mut out: &mut StringWriter,
-) -> Result<(), IError> {
-    // OPTIMIZE This does an unnecessary linear scan
-    let format_str = sel.cstring_bytes(sel.memory.get_var(format_ptr)?)?;
-    let map_err = |err| error!("WriteFailed", "failed to write to stdout ({})", err);
+        if decl_type.pointer_count > 0 {
+            let value: VarPointer = self.memory.get_var(ptr).ok()?;
+            if value == VarPointer::null() {
+                return Some("NULL".to_string());
+            }
 
-    // CREDIT heavily inspired by https://github.com/mpaland/printf/blob/master/printf.c
+            return Some(value.to_string());
+        }
 
-    const FLAGS_ZEROPAD: u32 = 1;
-    const FLAGS_LEFT: u32 = 2;
-    const FLAGS_PLUS: u32 = 4;
-    const FLAGS_SPACE: u32 = 8;
-    const FLAGS_HASH: u32 = 16;
-    const FLAGS_PRECISION: u32 = 32;
-    const FLAGS_LONG: u32 = 64;
-    const FLAGS_LONG_LONG: u32 = 128;
+        let value = match decl_type.kind {
+            TCTypeKind::I8 => {
+                let value = i8::from_be(self.memory.get_var(ptr).ok()?);
+                match value as u8 {
+                    b' '..=b'~' => format!("{} ('{}')", value, value as u8 as char),
+                    _ => value.to_string(),
+                }
+            }
+            TCTypeKind::U8 => u8::from_be(self.memory.get_var(ptr).ok()?).to_string(),
+            TCTypeKind::I32 => i32::from_be(self.memory.get_var(ptr).ok()?).to_string(),
+            TCTypeKind::U32 => u32::from_be(self.memory.get_var(ptr).ok()?).to_string(),
+            TCTypeKind::I64 => i64::from_be(self.memory.get_var(ptr).ok()?).to_string(),
+            TCTypeKind::U64 => u64::from_be(self.memory.get_var(ptr).ok()?).to_string(),
+            TCTypeKind::F64 => {
+                let bits = u64::from_be(self.memory.get_var(ptr).ok()?);
+                format!("{:?}", f64::from_bits(bits))
+            }
+            _ => return None,
+        };
 
-    let mut next_ptr = || {
-        let var_ptr = VarPointer::new_stack(current_offset, 0);
-        current_offset += 1;
-        return var_ptr;
-    };
+        return Some(value);
+    }
 
-    let parse_int = |begin: usize| {
-        let mut idx = begin;
-        if format_str[idx] >= b'0' && format_str[idx] <= b'9' {
-            let mut collect = 0;
-            loop {
-                collect *= 10;
-                collect += (format_str[idx] - b'0') as usize;
-                idx += 1;
+    /// The source code of the expression at `loc`
+    fn source_text(&self, loc: CodeLoc) -> &str {
+        use codespan_reporting::files::Files;
 
-                if format_str[idx] < b'0' || format_str[idx] > b'9' {
-                    break;
-                }
-            }
+        let source = self.program.files.source(loc.file).unwrap_or("");
+        let expr = source.get(loc.start as usize..loc.end as usize);
+        return expr.unwrap_or("this expression");
+    }
 
-            return Some((collect, idx - begin));
-        }
+    /// The error for a signed operation at `loc` that was done on `values`,
+    /// and whose result, `exact`, doesn't fit in `type_name`
+    fn overflow_error(&self, loc: CodeLoc, values: String, exact: i128, type_name: &str) -> IError {
+        return error!(
+            "IntegerOverflow",
+            "`{}` overflowed; it was {}, which is {} and outside the range of `{}`",
+            self.source_text(loc),
+            values,
+            exact,
+            type_name
+        );
+    }
 
-        return None;
-    };
+    /// The error for `min % -1`, which overflows in the division that computes
+    /// it even though the remainder itself would be 0
+    fn remainder_overflow_error(&self, loc: CodeLoc, min: &str) -> IError {
+        return error!(
+            "IntegerOverflow",
+            "`{}` overflowed; computing it divides {} by -1, which overflows",
+            self.source_text(loc),
+            min
+        );
+    }
 
-    let mut idx = 0;
-    while idx < format_str.len() {
-        let mut idx2 = idx;
-        while idx2 < format_str.len() && format_str[idx2] != b'%' {
-            idx2 += 1;
+    /// The variable that `ptr` points into, if there is one
+    fn object_var(&self, ptr: VarPointer) -> Option<Var> {
+        if ptr.var_idx() == 0 {
+            return None;
         }
 
-        write_utf8_lossy(&mut out, &format_str[idx..idx2]).map_err(map_err)?;
+        let vars = if ptr.is_stack() {
+            &self.memory.stack.vars
+        } else if ptr.is_heap() {
+            &self.memory.heap.vars
+        } else {
+            &self.memory.binary.vars
+        };
 
-        if idx2 == format_str.len() {
-            break;
-        }
+        return vars.get(ptr.var_idx() - 1).map(|var| *var);
+    }
 
-        // format_str[idx2] == b'%'
+    /// Where the stack variable that `ptr` points into was declared, if it
+    /// was declared in the program rather than made by the interpreter
+    fn stack_var_decl(&self, ptr: VarPointer) -> Option<&RuntimeVar> {
+        let var = self.memory.stack.vars.get(ptr.var_idx().checked_sub(1)?)?;
+        return self.program.symbols.get(var.meta as usize);
+    }
 
-        idx2 += 1;
-        if idx2 == format_str.len() {
-            return Err(error!(
-                "InvalidFormatString",
-                "format string ends with a single '%'; to print out a '%' use '%%'"
-            ));
+    /// Names the object that `ptr` points into, like `` `buf` `` or `the block
+    /// from `malloc(8)``
+    fn object_name(&self, ptr: VarPointer) -> String {
+        if ptr.var_idx() == 0 {
+            return "NULL".to_string();
         }
 
-        // format specifier?  %[flags][width][.precision][length]
-        let mut flags = 0;
-        let mut width = 0;
-        let mut precision = 0;
-
-        loop {
-            match format_str[idx2] {
-                b'0' => flags |= FLAGS_ZEROPAD,
-                b'-' => flags |= FLAGS_LEFT,
-                b'+' => flags |= FLAGS_PLUS,
-                b' ' => flags |= FLAGS_SPACE,
-                b'#' => flags |= FLAGS_HASH,
-                _ => break,
+        if ptr.is_heap() {
+            match self.memory.heap_sites.get(ptr.var_idx() - 1) {
+                Some(site) => return format!("the block from `{}`", self.source_text(site.alloc)),
+                None => return "a block on the heap".to_string(),
             }
-            idx += 1;
         }
 
-        if let Some((w, diff)) = parse_int(idx2) {
-            idx2 += diff;
-            width = w;
-        } else if format_str[idx2] == b'*' {
-            let mut next = i32::from_be(sel.memory.get_var(next_ptr())?);
-            if next < 0 {
-                flags |= FLAGS_LEFT;
-                next *= -1;
+        if ptr.is_binary() {
+            return "a string literal".to_string();
+        }
+
+        match self.stack_var_decl(ptr) {
+            Some(decl) if decl.symbol == META_NO_SYMBOL => {
+                return format!("the argument `{}`", self.source_text(decl.loc));
             }
+            Some(decl) => return format!("`{}`", self.program.files.symbols[decl.symbol as usize]),
+            None => return "a temporary value".to_string(),
+        }
+    }
 
-            width = next as usize;
-            idx2 += 1;
+    /// Labels where the object that `ptr` points into was declared or
+    /// allocated
+    fn with_object_label(&self, err: IError, ptr: VarPointer) -> IError {
+        if ptr.var_idx() == 0 || ptr.is_binary() {
+            return err;
         }
 
-        if format_str[idx2] == b'.' {
-            flags |= FLAGS_PRECISION;
-            idx2 += 1;
+        if ptr.is_heap() {
+            let site = match self.memory.heap_sites.get(ptr.var_idx() - 1) {
+                Some(site) => site,
+                None => return err,
+            };
 
-            if let Some((prec, diff)) = parse_int(idx2) {
-                idx2 += diff;
-                precision = prec;
-            } else if format_str[idx2] == b'*' {
-                let next = i32::from_be(sel.memory.get_var(next_ptr())?);
-                precision = if next > 0 { next } else { 0 } as usize;
-                idx2 += 1;
+            let err = err.with_label(site.alloc, "allocated here");
+            match site.free {
+                Some(free) => return err.with_label(free, "freed here"),
+                None => return err,
             }
         }
 
-        match format_str[idx2] {
-            b'l' => {
-                flags |= FLAGS_LONG;
-                idx2 += 1;
-                if format_str[idx2] == b'l' {
-                    flags |= FLAGS_LONG_LONG;
-                    idx2 += 1;
-                }
+        match self.stack_var_decl(ptr) {
+            Some(decl) if decl.symbol == META_NO_SYMBOL => {
+                return err.with_label(decl.loc, "passed here")
             }
-            _ => {}
+            Some(decl) => {
+                let name = self.program.files.symbols[decl.symbol as usize];
+                return err.with_label(decl.loc, &format!("`{}` is declared here", name));
+            }
+            None => return err,
         }
+    }
 
-        match format_str[idx2] {
-            b'u' => {
-                let base = 10;
-                flags &= !FLAGS_HASH;
-                if (flags & FLAGS_PRECISION) != 0 {
-                    flags &= !FLAGS_ZEROPAD;
-                }
+    /// The error for pointer arithmetic at the current pc that moved `ptr` to
+    /// `offset`, outside of its object, which is `len` bytes long
+    fn out_of_bounds_error(&self, ptr: VarPointer, offset: i64, len: u32) -> IError {
+        let loc = self.program.ops[self.memory.pc as usize].loc;
+        let (expr, object) = (self.source_text(loc), self.object_name(ptr));
+        let err = if offset < 0 {
+            error!(
+                "PointerOutOfBounds",
+                "`{}` points {} bytes before the start of {}", expr, -offset, object
+            )
+        } else {
+            error!(
+                "PointerOutOfBounds",
+                "`{}` points {} bytes past the end of {}, which is {} bytes long; \
+                 pointers can only go one past the end of their object",
+                expr,
+                offset - len as i64,
+                object,
+                len
+            )
+        };
+
+        return self.with_object_label(err, ptr);
+    }
+
+    fn struct_member(&self, struct_id: u32, member: u32) -> &'static TCStructMember {
+        let members = self.program.types.get(&struct_id).and_then(|t| t.members);
+        let mut members = members.into_iter().flatten();
+        match members.find(|m| m.ident == member) {
+            Some(member) => return member,
+            None => panic!("struct member doesn't exist (this is an error in tci)"),
+        }
+    }
+
+    /// Errors if `bytes` bytes at `ptr` don't fit in the array member of a
+    /// struct that `ptr` was made from, if it was made from one
+    fn check_sub_object(&self, tag: u32, ptr: VarPointer, bytes: u32) -> Result<(), IError> {
+        let sub = match self.memory.sub_object(tag) {
+            Some(sub) if sub.start.with_offset(0) == ptr.with_offset(0) => sub,
+            _ => return Ok(()),
+        };
+
+        let delta = ptr.offset() as i64 - sub.start.offset() as i64;
+        if delta >= 0 && delta + bytes as i64 <= sub.len as i64 {
+            return Ok(());
+        }
+
+        let member = self.struct_member(sub.struct_id, sub.member);
+        return Err(self.member_bounds_error(sub.struct_id, member, delta));
+    }
+
+    /// The error for indexing the array `member` of a struct at `delta` bytes
+    /// from its start, which is outside of the array but maybe still inside
+    /// the struct
+    fn member_bounds_error(&self, struct_id: u32, member: &TCStructMember, delta: i64) -> IError {
+        let loc = self.program.ops[self.memory.pc as usize].loc;
+        let name = self.program.files.symbols[member.ident as usize];
+        let elem_type = TCType::new(member.decl_type.kind, member.decl_type.pointer_count);
+        let (index, len) = match member.decl_type.array_kind {
+            TCArrayKind::Fixed(len) => (delta.div_euclid(elem_type.size().max(1) as i64), len),
+            TCArrayKind::None => (delta, 1),
+        };
+
+        let err = error!(
+            "MemberOutOfBounds",
+            "`{}` uses index {} of `{}`, which only has {} elements; that's {}",
+            self.source_text(loc),
+            index,
+            name,
+            len,
+            self.landed_in(struct_id, member.offset as i64 + delta)
+        );
+
+        let label = format!("`{}` is declared here with {} elements", name, len);
+        return err.with_label(member.loc, &label);
+    }
+
+    /// Says which member of a struct the byte at `offset` is in, since that's
+    /// the one that gets quietly overwritten
+    fn landed_in(&self, struct_id: u32, offset: i64) -> String {
+        let symbols = &self.program.files.symbols;
+        let members = self.program.types.get(&struct_id).and_then(|t| t.members);
+        let mut members = members.into_iter().flatten();
+        let landed = members.find(|m| {
+            let start = m.offset as i64;
+            start <= offset && offset < start + m.decl_type.size() as i64
+        });
+
+        match landed {
+            Some(m) => return format!("inside `{}` instead", symbols[m.ident as usize]),
+            None if offset < 0 => {
+                return format!(
+                    "before the start of the `struct {}`",
+                    symbols[struct_id as usize]
+                )
+            }
+            None => {
+                return format!(
+                    "past the end of the `struct {}`",
+                    symbols[struct_id as usize]
+                )
+            }
+        }
+    }
+
+    /// Errors if the pointers used by the op at the current pc point into
+    /// different objects, which makes subtracting or ordering them meaningless
+    fn check_same_object(
+        &self,
+        ptr1: VarPointer,
+        ptr2: VarPointer,
+        verb: &str,
+    ) -> Result<(), IError> {
+        if ptr1.with_offset(0) == ptr2.with_offset(0) {
+            return Ok(());
+        }
+
+        let loc = self.program.ops[self.memory.pc as usize].loc;
+        let err = error!(
+            "UnrelatedPointers",
+            "`{}` {} pointers to two different objects, {} and {}",
+            self.source_text(loc),
+            verb,
+            self.object_name(ptr1),
+            self.object_name(ptr2)
+        );
+
+        let err = self.with_object_label(err, ptr1);
+        return Err(self.with_object_label(err, ptr2));
+    }
+
+    /// Errors if either of the pointers compared by the op at the current pc
+    /// points into memory that's been freed; the value of a pointer is
+    /// indeterminate once its memory is freed
+    fn check_compared_freed(&self, ptr1: VarPointer, ptr2: VarPointer) -> Result<(), IError> {
+        let freed = |ptr: VarPointer| {
+            let var = self.object_var(ptr);
+            return ptr.is_heap() && var.map(|var| !var.is_valid()).unwrap_or(false);
+        };
+
+        let (freed_ptr, other) = match (freed(ptr1), freed(ptr2)) {
+            (true, _) => (ptr1, ptr2),
+            (false, true) => (ptr2, ptr1),
+            (false, false) => return Ok(()),
+        };
+
+        let loc = self.program.ops[self.memory.pc as usize].loc;
+        let (expr, freed_name) = (self.source_text(loc), self.object_name(freed_ptr));
+        if other.with_offset(0) == freed_ptr.with_offset(0) {
+            let err = error!(
+                "UseAfterFree",
+                "`{}` compares two pointers to {}, which has already been freed", expr, freed_name
+            );
+            return Err(self.with_object_label(err, freed_ptr));
+        }
+
+        let other_name = match other.var_idx() {
+            0 => "NULL".to_string(),
+            _ => format!("a pointer to {}", self.object_name(other)),
+        };
+        let err = error!(
+            "UseAfterFree",
+            "`{}` compares a pointer to {}, which has already been freed, with {}",
+            expr,
+            freed_name,
+            other_name
+        );
+
+        let err = self.with_object_label(err, freed_ptr);
+        return Err(self.with_object_label(err, other));
+    }
+
+    /// Errors if the divisor of the division or modulo at the current pc is 0
+    fn check_divisor(&self, divisor: u64) -> Result<(), IError> {
+        if divisor != 0 {
+            return Ok(());
+        }
+
+        let loc = self.program.ops[self.memory.pc as usize].loc;
+        let err = error!(
+            "DivideByZero",
+            "`{}` divides by zero",
+            self.source_text(loc)
+        );
+
+        // The divisor is the last thing evaluated before the division, so
+        // the op right before this one belongs to it
+        let divisor_loc = self.program.ops[self.memory.pc as usize - 1].loc;
+        if divisor_loc.file == loc.file
+            && loc.start <= divisor_loc.start
+            && divisor_loc.end <= loc.end
+        {
+            return Err(err.with_label(divisor_loc, "this is 0"));
+        }
+
+        return Err(err);
+    }
+
+    /// Remembers where a pointer-sized NULL was loaded from, right after the
+    /// op at the current pc loaded `bytes` bytes from `ptr`
+    fn note_null_load(&mut self, ptr: VarPointer, bytes: u32) {
+        if bytes != 8 {
+            return;
+        }
+
+        if let Ok(0u64) = self.memory.get_var::<u64>(ptr) {
+            self.null_load = Some((ptr, self.memory.pc));
+        }
+    }
+
+    /// Errors if the op at the current pc dereferences the NULL pointer `ptr`.
+    /// When the pointer was read out of a variable, the error names it and
+    /// points at the last write to that variable, which is where the NULL
+    /// came from.
+    fn check_null(&self, ptr: VarPointer, verb: &str) -> Result<(), IError> {
+        if ptr.var_idx() != 0 {
+            return Ok(());
+        }
+
+        let loc = self.program.ops[self.memory.pc as usize].loc;
+        let expr = self.source_text(loc);
+        let (from, load_pc) = match self.null_load {
+            Some(load) => load,
+            None => {
+                return Err(error!(
+                    "NullPointer",
+                    "`{}` {} through a NULL pointer", expr, verb
+                ))
+            }
+        };
+
+        // Only trust the load if it was part of this expression
+        let load_loc = self.program.ops[load_pc as usize].loc;
+        let inside = load_loc.file == loc.file && loc.start <= load_loc.start;
+        if !inside || load_loc.end > loc.end || load_loc == loc {
+            return Err(error!(
+                "NullPointer",
+                "`{}` {} through a NULL pointer", expr, verb
+            ));
+        }
+
+        let name = self.source_text(load_loc);
+        let err = error!(
+            "NullPointer",
+            "`{}` {} through `{}`, which is NULL", expr, verb, name
+        );
+
+        match self.last_write(from, 8) {
+            Some(write) => {
+                let label = format!("`{}` was last set here", name);
+                return Err(err.with_label(write, &label));
+            }
+            None => return Err(err),
+        }
+    }
+
+    /// Warns if the op at the current pc accesses memory through `ptr`, which
+    /// isn't aligned for the kind of value being accessed
+    fn check_alignment(&mut self, ptr: VarPointer, access: Access, verb: &str) {
+        let align = access.align().max(1);
+        if ptr.offset() % align == 0 {
+            return;
+        }
+
+        let loc = self.program.ops[self.memory.pc as usize].loc;
+        let message = format!(
+            "`{}` {} {} at offset {} of {}, which isn't a multiple of its alignment, {}",
+            self.source_text(loc),
+            verb,
+            access.describe(),
+            ptr.offset(),
+            self.object_name(ptr),
+            align
+        );
+
+        self.strict_warn("MisalignedAccess", message, None);
+    }
+
+    /// Warns if the op at the current pc reads `bytes` bytes of heap memory at
+    /// `ptr` as a kind of value that's incompatible with how it was written
+    fn check_effective_type(&mut self, ptr: VarPointer, bytes: u32, access: Access) {
+        if let Access::Char | Access::Bytes { .. } = access {
+            return;
+        }
+
+        let range = match self.heap_type_range(ptr, bytes) {
+            Some(range) => range,
+            None => return,
+        };
+
+        let written = self.memory.heap_types[range].iter();
+        let written = written.filter_map(|&t| self.effective_type(t));
+        let (written, pc) = match written.filter(|(t, _)| *t != access).next() {
+            Some(written) => written,
+            None => return,
+        };
+
+        let loc = self.program.ops[self.memory.pc as usize].loc;
+        let message = format!(
+            "`{}` reads {} out of {}, but that memory was last written as {}",
+            self.source_text(loc),
+            access.describe(),
+            self.object_name(ptr),
+            written.describe()
+        );
+
+        let write_loc = self.program.ops[pc as usize].loc;
+        self.strict_warn("IncompatibleRead", message, Some((write_loc, written)));
+    }
+
+    /// The type that the op at `written - 1` wrote, and its pc, if `written`
+    /// is an entry of `Memory::heap_types` that says there's an effective type
+    fn effective_type(&self, written: u32) -> Option<(Access, u32)> {
+        let pc = written.checked_sub(1)?;
+        match self.program.ops[pc as usize].op {
+            Opcode::Set { access, .. } => return Some((access, pc)),
+            _ => return None,
+        }
+    }
+
+    /// Records `effective` as the type of the `bytes` bytes at `ptr`, if they
+    /// are on the heap. Writes through `char` and copies of whole structs
+    /// don't say anything about the type, so they clear it instead.
+    fn set_effective_type(
+        &mut self,
+        ptr: VarPointer,
+        bytes: u32,
+        effective: Option<(Access, u32)>,
+    ) {
+        let range = match self.heap_type_range(ptr, bytes) {
+            Some(range) => range,
+            None => return,
+        };
+
+        let written = match effective {
+            Some((Access::Char, _)) | Some((Access::Bytes { .. }, _)) | None => 0,
+            Some((_, pc)) => pc + 1,
+        };
+
+        let types = vec![written; range.len()];
+        self.memory.set_heap_types(range.start, &types);
+    }
+
+    /// Clears the effective type of any heap memory written since
+    /// `history_index`, which library functions do without saying what type
+    /// they're writing
+    fn forget_effective_types(&mut self, history_index: usize) {
+        let end = self.memory.history_index.max(history_index);
+        let history = &self.memory.history[history_index..end];
+        let writes = history.iter().filter_map(|action| match action.kind {
+            MAKind::SetValue {
+                ptr,
+                value_start,
+                value_end_overwrite_start,
+                ..
+            } if ptr.is_heap() => Some((ptr, (value_end_overwrite_start - value_start) as u32)),
+            _ => None,
+        });
+
+        for (ptr, bytes) in writes.collect::<Vec<_>>() {
+            self.set_effective_type(ptr, bytes, None);
+        }
+    }
+
+    /// Where the effective types of the `bytes` bytes at `ptr` are kept, if
+    /// `ptr` points into the heap
+    fn heap_type_range(&self, ptr: VarPointer, bytes: u32) -> Option<Range<usize>> {
+        if !ptr.is_heap() {
+            return None;
+        }
+
+        let (start, end) = self.memory.heap.get_var_range(ptr, bytes).ok()?;
+        return Some(start..end);
+    }
+
+    fn strict_warn(
+        &mut self,
+        kind: &'static str,
+        message: String,
+        written: Option<(CodeLoc, Access)>,
+    ) {
+        let loc = self.program.ops[self.memory.pc as usize].loc;
+        let warnings = &mut self.strict_warnings;
+        if warnings.iter().any(|w| w.kind == kind && w.loc == loc) {
+            return;
+        }
+
+        warnings.push(StrictWarning {
+            kind,
+            message,
+            loc,
+            written,
+        });
+    }
+
+    /// Where the program last wrote to any of the `len` bytes at `ptr`,
+    /// according to the memory history
+    fn last_write(&self, ptr: VarPointer, len: u32) -> Option<CodeLoc> {
+        let history = &self.memory.history[..self.memory.history_index];
+        for action in history.iter().rev() {
+            let (dest, start, end) = match action.kind {
+                MAKind::SetValue {
+                    ptr,
+                    value_start,
+                    value_end_overwrite_start,
+                    ..
+                } => (ptr, value_start, value_end_overwrite_start),
+                _ => continue,
+            };
+
+            if dest.with_offset(0) != ptr.with_offset(0) {
+                continue;
+            }
+
+            let written = dest.offset()..(dest.offset() + (end - start) as u32);
+            if written.start < ptr.offset() + len && ptr.offset() < written.end {
+                return self.program.ops.get(action.tag as usize).map(|op| op.loc);
+            }
+        }
+
+        return None;
+    }
+
+    /// Errors if `origin` says a value came from uninitialized memory
+    pub fn check_init(&self, origin: u32, action: &str) -> Result<(), IError> {
+        if origin == 0 {
+            return Ok(());
+        }
+
+        let loc = self.program.ops[origin as usize - 1].loc;
+        return Err(error!(
+            "UninitializedMemory",
+            "{} depends on memory that was never initialized", action
+        )
+        .with_label(loc, "the memory was created here"));
+    }
+
+    pub fn cstring_bytes(&self, ptr: VarPointer) -> Result<&[u8], IError> {
+        let str_bytes = self.memory.get_var_slice(ptr)?;
+
+        let mut idx = str_bytes.len();
+        for (idx_, byte) in str_bytes.iter().enumerate() {
+            if *byte == 0 {
+                idx = idx_;
+                break;
+            }
+        }
+
+        if idx == str_bytes.len() {
+            return err!("MissingNullTerminator", "string missing null terminator");
+        }
+
+        let origin = self.memory.uninit_origin(ptr, idx as u32);
+        self.check_init(origin, "reading this string")?;
+
+        return Ok(&str_bytes[0..idx]);
+    }
+}
+
+pub fn memcpy(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let (dest_var, src_var) = (first_param(sel, 3), first_param(sel, 3) + 1);
+    let ret_ptr = VarPointer::new_stack(dest_var - 1, 0);
+
+    let size = u64::from_be(sel.memory.get_var(VarPointer::new_stack(src_var + 1, 0))?);
+    let dest = pointer_arg(sel, dest_var)?;
+    let src = pointer_arg(sel, src_var)?;
+    check_bounds(sel, "memcpy", src_var, src, size)?;
+    check_bounds(sel, "memcpy", dest_var, dest, size)?;
+
+    let same_object =
+        BufferKind::of(dest) == BufferKind::of(src) && dest.var_idx() == src.var_idx();
+    let (dest_offset, src_offset) = (dest.offset() as u64, src.offset() as u64);
+    if same_object && dest_offset < src_offset + size && src_offset < dest_offset + size {
+        let mut err = error!(
+            "OverlappingCopy",
+            "memcpy was called with `{}` and `{}`, which overlap; use memmove instead",
+            arg_name(sel, dest_var),
+            arg_name(sel, src_var)
+        );
+
+        let labels = [
+            (dest_var, "copying into here"),
+            (src_var, "copying from here"),
+        ];
+        for (var, message) in labels.iter() {
+            if let Some(loc) = arg_loc(sel, *var) {
+                err = err.with_label(loc, message);
+            }
+        }
+
+        return Err(err);
+    }
+
+    sel.memory.copy(dest, src, size as u32)?;
+    sel.memory.set(ret_ptr, dest)?;
+    return Ok(None);
+}
+
+pub fn memmove(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let (dest_var, src_var) = (first_param(sel, 3), first_param(sel, 3) + 1);
+    let ret_ptr = VarPointer::new_stack(dest_var - 1, 0);
+
+    let size = u64::from_be(sel.memory.get_var(VarPointer::new_stack(src_var + 1, 0))?);
+    let dest = pointer_arg(sel, dest_var)?;
+    let src = pointer_arg(sel, src_var)?;
+    check_bounds(sel, "memmove", src_var, src, size)?;
+    check_bounds(sel, "memmove", dest_var, dest, size)?;
+
+    sel.memory.copy(dest, src, size as u32)?;
+    sel.memory.set(ret_ptr, dest)?;
+    return Ok(None);
+}
+
+pub fn memset(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let dest_var = first_param(sel, 3);
+    let ret_ptr = VarPointer::new_stack(dest_var - 1, 0);
+
+    let value = i32::from_be(sel.memory.get_var(VarPointer::new_stack(dest_var + 1, 0))?);
+    let size = u64::from_be(sel.memory.get_var(VarPointer::new_stack(dest_var + 2, 0))?);
+    let dest = pointer_arg(sel, dest_var)?;
+    check_bounds(sel, "memset", dest_var, dest, size)?;
+
+    sel.memory
+        .write_bytes(dest, &vec![value as u8; size as usize])?;
+    sel.memory.set(ret_ptr, dest)?;
+    return Ok(None);
+}
+
+pub fn memcmp(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let (a_var, b_var) = (first_param(sel, 3), first_param(sel, 3) + 1);
+    let ret_ptr = VarPointer::new_stack(a_var - 1, 0);
+
+    let size = u64::from_be(sel.memory.get_var(VarPointer::new_stack(b_var + 1, 0))?);
+    let a = bytes_arg(sel, "memcmp", a_var, size)?;
+    let b = bytes_arg(sel, "memcmp", b_var, size)?;
+
+    let result = compare_bytes(&a, &b, size as usize);
+    sel.memory.set(ret_ptr, result.to_be())?;
+    return Ok(None);
+}
+
+pub fn strlen(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let s_var = first_param(sel, 1);
+    let ret_ptr = VarPointer::new_stack(s_var - 1, 0);
+
+    let (_, s) = string_arg(sel, "strlen", s_var, usize::MAX)?;
+    sel.memory.set(ret_ptr, (s.len() as u64).to_be())?;
+    return Ok(None);
+}
+
+pub fn strcpy(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let (dest_var, src_var) = (first_param(sel, 2), first_param(sel, 2) + 1);
+    let ret_ptr = VarPointer::new_stack(dest_var - 1, 0);
+
+    let dest = pointer_arg(sel, dest_var)?;
+    let (_, mut src) = string_arg(sel, "strcpy", src_var, usize::MAX)?;
+    src.push(0);
+    check_bounds(sel, "strcpy", dest_var, dest, src.len() as u64)?;
+
+    sel.memory.write_bytes(dest, &src)?;
+    sel.memory.set(ret_ptr, dest)?;
+    return Ok(None);
+}
+
+pub fn strncpy(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let (dest_var, src_var) = (first_param(sel, 3), first_param(sel, 3) + 1);
+    let ret_ptr = VarPointer::new_stack(dest_var - 1, 0);
+
+    let size = u64::from_be(sel.memory.get_var(VarPointer::new_stack(src_var + 1, 0))?);
+    let dest = pointer_arg(sel, dest_var)?;
+    let (_, mut src) = string_arg(sel, "strncpy", src_var, size as usize)?;
+    src.resize(size as usize, 0);
+    check_bounds(sel, "strncpy", dest_var, dest, size)?;
+
+    sel.memory.write_bytes(dest, &src)?;
+    sel.memory.set(ret_ptr, dest)?;
+    return Ok(None);
+}
+
+pub fn strcat(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let (dest_var, src_var) = (first_param(sel, 2), first_param(sel, 2) + 1);
+    let ret_ptr = VarPointer::new_stack(dest_var - 1, 0);
+
+    let (dest, existing) = string_arg(sel, "strcat", dest_var, usize::MAX)?;
+    let (_, mut src) = string_arg(sel, "strcat", src_var, usize::MAX)?;
+    src.push(0);
+    let needed = (existing.len() + src.len()) as u64;
+    check_bounds(sel, "strcat", dest_var, dest, needed)?;
+
+    let end = dest.with_offset(dest.offset() + existing.len() as u32);
+    sel.memory.write_bytes(end, &src)?;
+    sel.memory.set(ret_ptr, dest)?;
+    return Ok(None);
+}
+
+pub fn strncat(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let (dest_var, src_var) = (first_param(sel, 3), first_param(sel, 3) + 1);
+    let ret_ptr = VarPointer::new_stack(dest_var - 1, 0);
+
+    let size = u64::from_be(sel.memory.get_var(VarPointer::new_stack(src_var + 1, 0))?);
+    let (dest, existing) = string_arg(sel, "strncat", dest_var, usize::MAX)?;
+    let (_, mut src) = string_arg(sel, "strncat", src_var, size as usize)?;
+    src.push(0);
+    let needed = (existing.len() + src.len()) as u64;
+    check_bounds(sel, "strncat", dest_var, dest, needed)?;
+
+    let end = dest.with_offset(dest.offset() + existing.len() as u32);
+    sel.memory.write_bytes(end, &src)?;
+    sel.memory.set(ret_ptr, dest)?;
+    return Ok(None);
+}
+
+pub fn strcmp(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let (a_var, b_var) = (first_param(sel, 2), first_param(sel, 2) + 1);
+    let ret_ptr = VarPointer::new_stack(a_var - 1, 0);
+
+    let (_, a) = string_arg(sel, "strcmp", a_var, usize::MAX)?;
+    let (_, b) = string_arg(sel, "strcmp", b_var, usize::MAX)?;
+
+    let result = compare_bytes(&a, &b, a.len().max(b.len()) + 1);
+    sel.memory.set(ret_ptr, result.to_be())?;
+    return Ok(None);
+}
+
+pub fn strncmp(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let (a_var, b_var) = (first_param(sel, 3), first_param(sel, 3) + 1);
+    let ret_ptr = VarPointer::new_stack(a_var - 1, 0);
+
+    let size = u64::from_be(sel.memory.get_var(VarPointer::new_stack(b_var + 1, 0))?);
+    let (_, a) = string_arg(sel, "strncmp", a_var, size as usize)?;
+    let (_, b) = string_arg(sel, "strncmp", b_var, size as usize)?;
+
+    let result = compare_bytes(&a, &b, size as usize);
+    sel.memory.set(ret_ptr, result.to_be())?;
+    return Ok(None);
+}
+
+pub fn strchr(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let s_var = first_param(sel, 2);
+    let ret_ptr = VarPointer::new_stack(s_var - 1, 0);
+
+    let value = i32::from_be(sel.memory.get_var(VarPointer::new_stack(s_var + 1, 0))?) as u8;
+    let (ptr, s) = string_arg(sel, "strchr", s_var, usize::MAX)?;
+    let found = match value {
+        0 => Some(s.len()),
+        value => s.iter().position(|byte| *byte == value),
+    };
+
+    let ret = match found {
+        Some(idx) => ptr.with_offset(ptr.offset() + idx as u32),
+        None => VarPointer::null(),
+    };
+
+    sel.memory.set(ret_ptr, ret)?;
+    return Ok(None);
+}
+
+pub fn strrchr(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let s_var = first_param(sel, 2);
+    let ret_ptr = VarPointer::new_stack(s_var - 1, 0);
+
+    let value = i32::from_be(sel.memory.get_var(VarPointer::new_stack(s_var + 1, 0))?) as u8;
+    let (ptr, s) = string_arg(sel, "strrchr", s_var, usize::MAX)?;
+    let found = match value {
+        0 => Some(s.len()),
+        value => s.iter().rposition(|byte| *byte == value),
+    };
+
+    let ret = match found {
+        Some(idx) => ptr.with_offset(ptr.offset() + idx as u32),
+        None => VarPointer::null(),
+    };
+
+    sel.memory.set(ret_ptr, ret)?;
+    return Ok(None);
+}
+
+pub fn strstr(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let (haystack_var, needle_var) = (first_param(sel, 2), first_param(sel, 2) + 1);
+    let ret_ptr = VarPointer::new_stack(haystack_var - 1, 0);
+
+    let (ptr, haystack) = string_arg(sel, "strstr", haystack_var, usize::MAX)?;
+    let (_, needle) = string_arg(sel, "strstr", needle_var, usize::MAX)?;
+    let found = if needle.len() == 0 {
+        Some(0)
+    } else {
+        let mut windows = haystack.windows(needle.len());
+        windows.position(|window| window == &needle[..])
+    };
+
+    let ret = match found {
+        Some(idx) => ptr.with_offset(ptr.offset() + idx as u32),
+        None => VarPointer::null(),
+    };
+
+    sel.memory.set(ret_ptr, ret)?;
+    return Ok(None);
+}
+
+pub fn strdup(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let s_var = first_param(sel, 1);
+    let ret_ptr = VarPointer::new_stack(s_var - 1, 0);
+
+    let (_, mut s) = string_arg(sel, "strdup", s_var, usize::MAX)?;
+    s.push(0);
+
+    let loc = sel.program.ops[sel.memory.pc as usize].loc;
+    let copy = sel.memory.add_heap_var(s.len() as u32, loc); // TODO overflow
+    sel.memory.write_bytes(copy, &s)?;
+    sel.memory.set(ret_ptr, copy)?;
+    return Ok(None);
+}
+
+/// Stack index of the first parameter of a library call with `count` parameters;
+/// the return value lives right below it
+fn first_param(sel: &Runtime, count: u16) -> u16 {
+    return sel.memory.stack_length() - count + 1;
+}
+
+fn pointer_arg(sel: &Runtime, var: u16) -> Result<VarPointer, IError> {
+    return sel.memory.get_var(VarPointer::new_stack(var, 0));
+}
+
+/// Where the argument stored in the stack variable `var` was written at the call
+/// site
+fn arg_loc(sel: &Runtime, var: u16) -> Option<CodeLoc> {
+    let meta = sel.memory.stack.vars[var as usize - 1].meta;
+    return sel
+        .program
+        .symbols
+        .get(meta as usize)
+        .map(|symbol| symbol.loc);
+}
+
+/// The type the argument stored in the stack variable `var` had at the call
+/// site
+fn arg_type(sel: &Runtime, var: u16) -> Option<TCType> {
+    let meta = sel.memory.stack.vars[var as usize - 1].meta;
+    return sel
+        .program
+        .symbols
+        .get(meta as usize)
+        .map(|symbol| symbol.decl_type);
+}
+
+/// The source text of the argument stored in the stack variable `var`
+fn arg_name(sel: &Runtime, var: u16) -> &str {
+    match arg_loc(sel, var) {
+        Some(loc) => {
+            let source = sel.program.files.files[loc.file as usize]._source;
+            return &source[(loc.start as usize)..(loc.end as usize)];
+        }
+        None => return "argument",
+    }
+}
+
+/// Errors if `len` bytes starting at `ptr` would run out of the array member of
+/// a struct that the argument in `var` was made from
+fn check_sub_object_bounds(
+    sel: &Runtime,
+    func: &str,
+    var: u16,
+    sub: SubObject,
+    ptr: VarPointer,
+    len: u64,
+) -> Result<(), IError> {
+    let (start, end) = (sub.start.offset(), sub.start.offset() + sub.len);
+    let same_object = sub.start.with_offset(0) == ptr.with_offset(0);
+    if !same_object || ptr.offset() < start || ptr.offset() > end {
+        return Ok(());
+    }
+
+    let room = (end - ptr.offset()) as u64;
+    if len <= room {
+        return Ok(());
+    }
+
+    let member = sel.struct_member(sub.struct_id, sub.member);
+    let name = sel.program.files.symbols[member.ident as usize];
+    let mut err = error!(
+        "MemberOutOfBounds",
+        "{} needs {} bytes at `{}`, but there are only {} bytes left in `{}`; the rest is {}",
+        func,
+        len,
+        arg_name(sel, var),
+        room,
+        name,
+        sel.landed_in(sub.struct_id, member.offset as i64 + sub.len as i64)
+    );
+
+    if let Some(loc) = arg_loc(sel, var) {
+        err = err.with_label(loc, &format!("this points into `{}`", name));
+    }
+
+    let label = format!("`{}` is declared here with {} bytes", name, sub.len);
+    return Err(err.with_label(member.loc, &label));
+}
+
+/// Errors if `len` bytes starting at `ptr` don't fit in the object it points to
+fn check_bounds(
+    sel: &Runtime,
+    func: &str,
+    var: u16,
+    ptr: VarPointer,
+    len: u64,
+) -> Result<(), IError> {
+    if len == 0 {
+        return Ok(());
+    }
+
+    let room = sel.memory.get_var_slice(ptr)?.len() as u64;
+    if let Some(sub) = sel.memory.var_sub_object(var) {
+        check_sub_object_bounds(sel, func, var, sub, ptr, len)?;
+    }
+
+    if len <= room {
+        return Ok(());
+    }
+
+    let mut err = error!(
+        "BufferOverflow",
+        "{} needs {} bytes at `{}`, but there are only {} bytes left in the object it points to",
+        func,
+        len,
+        arg_name(sel, var),
+        room
+    );
+
+    if let Some(loc) = arg_loc(sel, var) {
+        err = err.with_label(loc, &format!("this has room for {} bytes", room));
+    }
+
+    return Err(err);
+}
+
+/// Reads `len` bytes from the pointer argument in `var`
+fn bytes_arg(sel: &Runtime, func: &str, var: u16, len: u64) -> Result<Vec<u8>, IError> {
+    let ptr = pointer_arg(sel, var)?;
+    check_bounds(sel, func, var, ptr, len)?;
+
+    let origin = sel.memory.uninit_origin(ptr, len as u32);
+    sel.check_init(origin, &format!("reading `{}`", arg_name(sel, var)))?;
+    return Ok(sel.memory.get_var_slice(ptr)?[..len as usize].to_vec());
+}
+
+/// Reads the null-terminated string argument in `var`, stopping after at most
+/// `max` bytes. The terminator isn't included in the result.
+fn string_arg(
+    sel: &Runtime,
+    func: &str,
+    var: u16,
+    max: usize,
+) -> Result<(VarPointer, Vec<u8>), IError> {
+    let ptr = pointer_arg(sel, var)?;
+    let bytes = sel.memory.get_var_slice(ptr)?;
+    let limit = bytes.len().min(max);
+    let len = match bytes[..limit].iter().position(|byte| *byte == 0) {
+        Some(len) => len,
+        None if limit == max => limit,
+        None => {
+            let mut err = error!(
+                "MissingNullTerminator",
+                "{} read past the end of `{}` without finding a null terminator",
+                func,
+                arg_name(sel, var)
+            );
+
+            if let Some(loc) = arg_loc(sel, var) {
+                err = err.with_label(loc, "this string isn't null-terminated");
+            }
+
+            return Err(err);
+        }
+    };
+
+    let origin = sel.memory.uninit_origin(ptr, len as u32);
+    sel.check_init(origin, &format!("reading `{}`", arg_name(sel, var)))?;
+    return Ok((ptr, bytes[..len].to_vec()));
+}
+
+/// Compares up to `len` bytes the way C's comparison functions do; bytes past
+/// the end of either slice count as zero
+fn compare_bytes(a: &[u8], b: &[u8], len: usize) -> i32 {
+    for idx in 0..len {
+        let left = a.get(idx).map(|x| *x).unwrap_or(0);
+        let right = b.get(idx).map(|x| *x).unwrap_or(0);
+        if left != right {
+            return left as i32 - right as i32;
+        }
+
+        if left == 0 && idx >= a.len() {
+            return 0;
+        }
+    }
+
+    return 0;
+}
+
+pub fn malloc(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let top_ptr = VarPointer::new_stack(sel.memory.stack_length(), 0);
+    let ret_ptr = VarPointer::new_stack(sel.memory.stack_length() - 1, 0);
+    let size = u64::from_be(sel.memory.get_var(top_ptr)?);
+    let loc = sel.program.ops[sel.memory.pc as usize].loc;
+    let var_pointer = sel.memory.add_heap_var(size as u32, loc); // TODO overflow
+    sel.memory.set(ret_ptr, var_pointer)?;
+    return Ok(None);
+}
+
+pub fn realloc(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let stack_len = sel.memory.stack_length();
+    let size_ptr = VarPointer::new_stack(stack_len, 0);
+    let to_free_ptr = VarPointer::new_stack(stack_len - 1, 0);
+    let ret_ptr = VarPointer::new_stack(stack_len - 2, 0);
+
+    let to_free: VarPointer = sel.memory.get_var(to_free_ptr)?;
+    if !to_free.is_heap() {
+        return Err(error!(
+            "InvalidPointer",
+            "called realloc on a pointer that was not from the heap"
+        ));
+    }
+
+    if to_free.offset() != 0 {
+        return Err(error!(
+            "InvalidPointer",
+            "called realloc on a pointer that was {} bytes off of its allocation",
+            to_free.offset()
+        ));
+    }
+
+    let size = u64::from_be(sel.memory.get_var(size_ptr)?);
+    let old_size = sel.memory.get_var_slice(to_free)?.len() as u64;
+    let loc = sel.program.ops[sel.memory.pc as usize].loc;
+    let new_alloc_ptr = sel.memory.add_heap_var(size as u32, loc); // TODO overflow
+
+    sel.memory
+        .copy(new_alloc_ptr, to_free, size.min(old_size) as u32)?;
+    sel.memory.free_heap_var(to_free, loc)?;
+    sel.memory.set(ret_ptr, new_alloc_ptr)?;
+    return Ok(None);
+}
+
+pub fn free(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let top_ptr = VarPointer::new_stack(sel.memory.stack_length(), 0);
+    let to_free: VarPointer = sel.memory.get_var(top_ptr)?;
+    if to_free.var_idx() == 0 && to_free.offset() == 0 {
+        return Ok(None); // free(NULL) does nothing
+    }
+
+    let loc = sel.program.ops[sel.memory.pc as usize].loc;
+    sel.memory.free_heap_var(to_free, loc)?;
+    return Ok(None);
+}
+
+pub fn exit(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let top_ptr = VarPointer::new_stack(sel.memory.stack_length(), 0);
+    let exit_code = i32::from_be(sel.memory.get_var(top_ptr)?);
+    return sel.exit_with(exit_code);
+}
+
+pub fn calloc(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let count_var = first_param(sel, 2);
+    let ret_ptr = VarPointer::new_stack(count_var - 1, 0);
+
+    let count = u64::from_be(sel.memory.get_var(VarPointer::new_stack(count_var, 0))?);
+    let size = u64::from_be(
+        sel.memory
+            .get_var(VarPointer::new_stack(count_var + 1, 0))?,
+    );
+    let len = match count.checked_mul(size) {
+        Some(len) if len <= u32::MAX as u64 => len as u32,
+        _ => {
+            // the size can't be represented, so the allocation fails like it would in C
+            sel.memory.set(ret_ptr, VarPointer::null())?;
+            return Ok(None);
+        }
+    };
+
+    let loc = sel.program.ops[sel.memory.pc as usize].loc;
+    let alloc = sel.memory.add_heap_var(len, loc);
+    if len != 0 {
+        sel.memory.write_bytes(alloc, &vec![0; len as usize])?;
+    }
+
+    sel.memory.set(ret_ptr, alloc)?;
+    return Ok(None);
+}
+
+pub fn atoi(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let s_var = first_param(sel, 1);
+    let ret_ptr = VarPointer::new_stack(s_var - 1, 0);
+
+    let (_, s) = string_arg(sel, "atoi", s_var, usize::MAX)?;
+    let value = parse_integer(&s, 10).signed() as i32;
+    sel.memory.set(ret_ptr, value.to_be())?;
+    return Ok(None);
+}
+
+pub fn atol(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let s_var = first_param(sel, 1);
+    let ret_ptr = VarPointer::new_stack(s_var - 1, 0);
+
+    let (_, s) = string_arg(sel, "atol", s_var, usize::MAX)?;
+    let value = parse_integer(&s, 10).signed();
+    sel.memory.set(ret_ptr, value.to_be())?;
+    return Ok(None);
+}
+
+pub fn strtol(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let s_var = first_param(sel, 3);
+    let ret_ptr = VarPointer::new_stack(s_var - 1, 0);
+
+    let parsed = strtol_internal(sel, "strtol", s_var)?;
+    sel.memory.set(ret_ptr, parsed.signed().to_be())?;
+    return Ok(None);
+}
+
+pub fn strtoul(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let s_var = first_param(sel, 3);
+    let ret_ptr = VarPointer::new_stack(s_var - 1, 0);
+
+    let parsed = strtol_internal(sel, "strtoul", s_var)?;
+    sel.memory.set(ret_ptr, parsed.unsigned().to_be())?;
+    return Ok(None);
+}
+
+/// Parses the string in `s_var` in the base in `s_var + 2`, and points the
+/// `char **` in `s_var + 1` past the digits if it isn't NULL
+fn strtol_internal(sel: &mut Runtime, func: &str, s_var: u16) -> Result<ParsedInt, IError> {
+    let base = i32::from_be(sel.memory.get_var(VarPointer::new_stack(s_var + 2, 0))?);
+    let end_ptr = pointer_arg(sel, s_var + 1)?;
+
+    let (ptr, s) = string_arg(sel, func, s_var, usize::MAX)?;
+    let parsed = match base {
+        0 | 2..=36 => parse_integer(&s, base as u32),
+        _ => ParsedInt::default(), // C leaves the result unspecified, but 0 is typical
+    };
+
+    if end_ptr != VarPointer::null() {
+        let end = ptr.with_offset(ptr.offset() + parsed.len as u32);
+        sel.memory.set(end_ptr, end)?;
+    }
+
+    return Ok(parsed);
+}
+
+pub fn abs(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let value_var = first_param(sel, 1);
+    let ret_ptr = VarPointer::new_stack(value_var - 1, 0);
+
+    let value = i32::from_be(sel.memory.get_var(VarPointer::new_stack(value_var, 0))?);
+    sel.memory.set(ret_ptr, value.wrapping_abs().to_be())?;
+    return Ok(None);
+}
+
+pub fn labs(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let value_var = first_param(sel, 1);
+    let ret_ptr = VarPointer::new_stack(value_var - 1, 0);
+
+    let value = i64::from_be(sel.memory.get_var(VarPointer::new_stack(value_var, 0))?);
+    sel.memory.set(ret_ptr, value.wrapping_abs().to_be())?;
+    return Ok(None);
+}
+
+/// The example generator from the C standard, so that the same seed gives the
+/// same numbers every time
+pub fn rand(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let ret_ptr = VarPointer::new_stack(sel.memory.stack_length(), 0);
+
+    let next = sel
+        .memory
+        .rand_next
+        .wrapping_mul(1103515245)
+        .wrapping_add(12345);
+    sel.memory.set_rand(next);
+
+    let value = (next / 65536) % (RAND_MAX + 1);
+    sel.memory.set(ret_ptr, (value as i32).to_be())?;
+    return Ok(None);
+}
+
+pub fn srand(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let top_ptr = VarPointer::new_stack(sel.memory.stack_length(), 0);
+    let seed = u32::from_be(sel.memory.get_var(top_ptr)?);
+    sel.memory.set_rand(seed);
+    return Ok(None);
+}
+
+pub fn atexit(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let func_var = first_param(sel, 1);
+    let ret_ptr = VarPointer::new_stack(func_var - 1, 0);
+
+    function_arg(sel, "atexit", func_var)?;
+    let func = pointer_arg(sel, func_var)?;
+    sel.memory.add_atexit(func);
+    sel.memory.set(ret_ptr, 0i32.to_be())?;
+    return Ok(None);
+}
+
+/// Sorts with a binary insertion sort, which keeps equal elements in order and
+/// calls the comparison function as few times as it reasonably can
+pub fn qsort(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let base_var = first_param(sel, 4);
+    let count = u64::from_be(sel.memory.get_var(VarPointer::new_stack(base_var + 1, 0))?);
+    let size = u64::from_be(sel.memory.get_var(VarPointer::new_stack(base_var + 2, 0))?);
+    let func = function_arg(sel, "qsort", base_var + 3)?;
+
+    let base = pointer_arg(sel, base_var)?;
+    check_bounds(sel, "qsort", base_var, base, count.saturating_mul(size))?;
+    if size == 0 {
+        return Ok(None);
+    }
+
+    let (count, size) = (count as u32, size as u32); // both fit, since the array does
+    let at = |idx: u32| base.with_offset(base.offset() + idx * size);
+    for idx in 1..count {
+        let (mut low, mut high) = (0, idx);
+        while low < high {
+            let mid = (low + high) / 2;
+            match sel.call_function(func, &[at(idx), at(mid)], 4)? {
+                Callback::Returned(ret) if callback_i32(&ret) < 0 => high = mid,
+                Callback::Returned(_) => low = mid + 1,
+                Callback::Exited(code) => return Ok(Some(code)),
+            }
+        }
+
+        if low == idx {
+            continue;
+        }
+
+        sel.memory.add_stack_var(size, META_NO_SYMBOL);
+        let temp = VarPointer::new_stack(sel.memory.stack_length(), 0);
+        sel.memory.copy(temp, at(idx), size)?;
+        sel.memory.copy(at(low + 1), at(low), (idx - low) * size)?;
+        sel.memory.copy(at(low), temp, size)?;
+        sel.memory.pop_stack_var()?;
+    }
+
+    return Ok(None);
+}
+
+pub fn bsearch(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let key_var = first_param(sel, 5);
+    let ret_ptr = VarPointer::new_stack(key_var - 1, 0);
+
+    let count = u64::from_be(sel.memory.get_var(VarPointer::new_stack(key_var + 2, 0))?);
+    let size = u64::from_be(sel.memory.get_var(VarPointer::new_stack(key_var + 3, 0))?);
+    let func = function_arg(sel, "bsearch", key_var + 4)?;
+
+    let key = pointer_arg(sel, key_var)?;
+    let base = pointer_arg(sel, key_var + 1)?;
+    check_bounds(
+        sel,
+        "bsearch",
+        key_var + 1,
+        base,
+        count.saturating_mul(size),
+    )?;
+
+    let (count, size) = (count as u32, size as u32);
+    let at = |idx: u32| base.with_offset(base.offset() + idx * size);
+    let (mut low, mut high) = (0, count);
+    let mut found = VarPointer::null();
+    while low < high {
+        let mid = (low + high) / 2;
+        let order = match sel.call_function(func, &[key, at(mid)], 4)? {
+            Callback::Returned(ret) => callback_i32(&ret),
+            Callback::Exited(code) => return Ok(Some(code)),
+        };
+
+        if order < 0 {
+            high = mid;
+        } else if order > 0 {
+            low = mid + 1;
+        } else {
+            found = at(mid);
+            break;
+        }
+    }
+
+    sel.memory.set(ret_ptr, found)?;
+    return Ok(None);
+}
+
+pub fn tci_assert(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let cond_var = first_param(sel, 1);
+    let cond = i32::from_be(sel.memory.get_var(VarPointer::new_stack(cond_var, 0))?);
+    if cond != 0 {
+        return Ok(None);
+    }
+
+    let text = arg_name(sel, cond_var);
+    let mut err = error!("AssertionFailed", "assertion `{}` failed", text);
+    let loc = match arg_loc(sel, cond_var) {
+        Some(loc) => loc,
+        None => return Err(err),
+    };
+
+    err = err.with_label(loc, "this was false");
+    let mut described: Vec<&str> = Vec::new();
+    for (offset, name) in variable_names(text) {
+        if described.contains(&name) {
+            continue;
+        }
+
+        described.push(name);
+        if let Some(value) = sel.describe_variable(name) {
+            let start = loc.start + offset as u32;
+            let name_loc = l(start, start + name.len() as u32, loc.file);
+            err = err.with_label(name_loc, &format!("`{}` is {}", name, value));
+        }
+    }
+
+    return Err(err);
+}
+
+/// The identifiers in `text` that could be variables, along with their offsets;
+/// function names, struct members and anything inside literals are skipped
+fn variable_names(text: &str) -> Vec<(usize, &str)> {
+    use crate::lexer::is_ident_char;
+
+    let bytes = text.as_bytes();
+    let mut names = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let begin = idx;
+        idx += 1;
+        match bytes[begin] {
+            quote @ b'\'' | quote @ b'"' => {
+                while idx < bytes.len() && bytes[idx] != quote {
+                    idx += if bytes[idx] == b'\\' { 2 } else { 1 };
+                }
+
+                idx += 1;
+            }
+            b'0'..=b'9' => {
+                while idx < bytes.len() && is_ident_char(bytes[idx]) {
+                    idx += 1; // suffixes like the `L` in `10L` aren't names
+                }
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                while idx < bytes.len() && is_ident_char(bytes[idx]) {
+                    idx += 1;
+                }
+
+                let before = text[..begin].trim_end();
+                let after = text[idx..].trim_start();
+                if !before.ends_with('.') && !before.ends_with("->") && !after.starts_with('(') {
+                    names.push((begin, &text[begin..idx]));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    return names;
+}
+
+macro_rules! ctype_class {
+    ($name:ident, $test:expr) => {
+        pub fn $name(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+            let c_var = first_param(sel, 1);
+            let ret_ptr = VarPointer::new_stack(c_var - 1, 0);
+
+            let c = i32::from_be(sel.memory.get_var(VarPointer::new_stack(c_var, 0))?);
+            let test: fn(u8) -> bool = $test;
+            let result = c != -1 && test(c as u8); // EOF isn't in any class
+            sel.memory.set(ret_ptr, (result as i32).to_be())?;
+            return Ok(None);
+        }
+    };
+}
+
+ctype_class!(isalnum, |c| c.is_ascii_alphanumeric());
+ctype_class!(isalpha, |c| c.is_ascii_alphabetic());
+ctype_class!(isdigit, |c| c.is_ascii_digit());
+ctype_class!(isxdigit, |c| c.is_ascii_hexdigit());
+ctype_class!(islower, |c| c.is_ascii_lowercase());
+ctype_class!(isupper, |c| c.is_ascii_uppercase());
+ctype_class!(isspace, |c| c == b' ' || (c >= 9 && c <= 13));
+ctype_class!(ispunct, |c| c.is_ascii_punctuation());
+ctype_class!(isprint, |c| c >= b' ' && c <= b'~');
+ctype_class!(iscntrl, |c| c.is_ascii_control());
+
+pub fn tolower(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let c_var = first_param(sel, 1);
+    let ret_ptr = VarPointer::new_stack(c_var - 1, 0);
+
+    let c = i32::from_be(sel.memory.get_var(VarPointer::new_stack(c_var, 0))?);
+    let result = match c {
+        0x41..=0x5a => c + 0x20,
+        c => c,
+    };
+
+    sel.memory.set(ret_ptr, result.to_be())?;
+    return Ok(None);
+}
+
+pub fn toupper(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let c_var = first_param(sel, 1);
+    let ret_ptr = VarPointer::new_stack(c_var - 1, 0);
+
+    let c = i32::from_be(sel.memory.get_var(VarPointer::new_stack(c_var, 0))?);
+    let result = match c {
+        0x61..=0x7a => c - 0x20,
+        c => c,
+    };
+
+    sel.memory.set(ret_ptr, result.to_be())?;
+    return Ok(None);
+}
+
+fn f64_arg(sel: &Runtime, var: u16) -> Result<f64, IError> {
+    let bits = u64::from_be(sel.memory.get_var(VarPointer::new_stack(var, 0))?);
+    return Ok(f64::from_bits(bits));
+}
+
+/// Warns that `func` was given a value outside its domain, if the runtime was
+/// asked to; the function still returns NaN or infinity like in C
+fn domain_warning(
+    sel: &mut Runtime,
+    func: &str,
+    var: u16,
+    why: &'static str,
+) -> Result<(), IError> {
+    if !sel.math_warnings {
+        return Ok(());
+    }
+
+    let loc = sel.program.ops[sel.memory.pc as usize].loc;
+    if sel.domain_warnings.iter().any(|w| w.loc == loc) {
+        return Ok(());
+    }
+
+    let value = f64_arg(sel, var)?;
+    let message = format!(
+        "{} was given `{}`, which is {}",
+        func,
+        arg_name(sel, var),
+        value
+    );
+
+    let arg = arg_loc(sel, var);
+    sel.domain_warnings.push(DomainWarning {
+        message,
+        loc,
+        arg,
+        why,
+    });
+    return Ok(());
+}
+
+macro_rules! math_func {
+    ($name:ident, $func:expr) => {
+        pub fn $name(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+            let x_var = first_param(sel, 1);
+            let ret_ptr = VarPointer::new_stack(x_var - 1, 0);
+
+            let func: fn(f64) -> f64 = $func;
+            let result = func(f64_arg(sel, x_var)?);
+            sel.memory.set(ret_ptr, result.to_bits().to_be())?;
+            return Ok(None);
+        }
+    };
+}
+
+math_func!(fabs, |x| x.abs());
+math_func!(floor, |x| x.floor());
+math_func!(ceil, |x| x.ceil());
+math_func!(sin, |x| x.sin());
+math_func!(cos, |x| x.cos());
+math_func!(exp, |x| x.exp());
+
+pub fn sqrt(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let x_var = first_param(sel, 1);
+    let ret_ptr = VarPointer::new_stack(x_var - 1, 0);
+
+    let x = f64_arg(sel, x_var)?;
+    if x < 0.0 {
+        domain_warning(
+            sel,
+            "sqrt",
+            x_var,
+            "negative numbers don't have a square root",
+        )?;
+    }
+
+    sel.memory.set(ret_ptr, x.sqrt().to_bits().to_be())?;
+    return Ok(None);
+}
+
+pub fn log(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let x_var = first_param(sel, 1);
+    let ret_ptr = VarPointer::new_stack(x_var - 1, 0);
+
+    let x = f64_arg(sel, x_var)?;
+    if x == 0.0 {
+        domain_warning(sel, "log", x_var, "the logarithm of 0 is negative infinity")?;
+    } else if x < 0.0 {
+        domain_warning(sel, "log", x_var, "negative numbers don't have a logarithm")?;
+    }
+
+    sel.memory.set(ret_ptr, x.ln().to_bits().to_be())?;
+    return Ok(None);
+}
+
+pub fn pow(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let base_var = first_param(sel, 2);
+    let ret_ptr = VarPointer::new_stack(base_var - 1, 0);
+
+    let base = f64_arg(sel, base_var)?;
+    let exponent = f64_arg(sel, base_var + 1)?;
+    if base < 0.0 && exponent.fract() != 0.0 {
+        let why = "negative numbers can't be raised to a fractional power";
+        domain_warning(sel, "pow", base_var, why)?;
+    } else if base == 0.0 && exponent < 0.0 {
+        let why = "0 to a negative power is infinity";
+        domain_warning(sel, "pow", base_var, why)?;
+    }
+
+    sel.memory
+        .set(ret_ptr, base.powf(exponent).to_bits().to_be())?;
+    return Ok(None);
+}
+
+/// Reads the function pointer argument in `var`
+fn function_arg(sel: &Runtime, func: &str, var: u16) -> Result<(u32, u32), IError> {
+    let ptr = pointer_arg(sel, var)?;
+    if let Some(function) = sel.function_at(ptr) {
+        return Ok(function);
+    }
+
+    let mut err = error!(
+        "InvalidFunctionPointer",
+        "{} was given `{}`, which doesn't point to a function",
+        func,
+        arg_name(sel, var)
+    );
+
+    if let Some(loc) = arg_loc(sel, var) {
+        err = err.with_label(loc, "this should be the name of a function");
+    }
+
+    return Err(err);
+}
+
+fn callback_i32(ret: &[u8]) -> i32 {
+    return i32::from_be_bytes([ret[0], ret[1], ret[2], ret[3]]);
+}
+
+/// An integer parsed the way strtol parses it
+#[derive(Debug, Default)]
+pub struct ParsedInt {
+    pub magnitude: u64,
+    pub negative: bool,
+    pub overflow: bool, // the magnitude didn't fit in 64 bits
+    pub len: usize,     // bytes used, or 0 if there weren't any digits
+}
+
+impl ParsedInt {
+    /// The value as a long, clamped to LONG_MIN and LONG_MAX
+    pub fn signed(&self) -> i64 {
+        if self.negative {
+            if self.overflow || self.magnitude > i64::MIN.unsigned_abs() {
+                return i64::MIN;
+            }
+
+            return (self.magnitude as i64).wrapping_neg();
+        }
+
+        if self.overflow || self.magnitude > i64::MAX as u64 {
+            return i64::MAX;
+        }
+
+        return self.magnitude as i64;
+    }
+
+    /// The value as an unsigned long; a minus sign negates it modulo 2^64
+    pub fn unsigned(&self) -> u64 {
+        if self.overflow {
+            return u64::MAX;
+        } else if self.negative {
+            return self.magnitude.wrapping_neg();
+        }
+
+        return self.magnitude;
+    }
+}
+
+/// Parses leading whitespace, an optional sign, and then digits in `base`; a
+/// base of 0 means octal after a leading `0`, hex after `0x`, and decimal otherwise
+fn parse_integer(s: &[u8], mut base: u32) -> ParsedInt {
+    let mut parsed = ParsedInt::default();
+    let mut idx = 0;
+    while idx < s.len() && (s[idx] == b' ' || (s[idx] >= 9 && s[idx] <= 13)) {
+        idx += 1;
+    }
+
+    if idx < s.len() && (s[idx] == b'+' || s[idx] == b'-') {
+        parsed.negative = s[idx] == b'-';
+        idx += 1;
+    }
+
+    let digit = |idx: usize, base: u32| s.get(idx).and_then(|c| (*c as char).to_digit(base));
+    let hex_prefix = s[idx..].starts_with(b"0x") || s[idx..].starts_with(b"0X");
+    if (base == 0 || base == 16) && hex_prefix && digit(idx + 2, 16).is_some() {
+        idx += 2;
+        base = 16;
+    } else if base == 0 {
+        base = if s[idx..].starts_with(b"0") { 8 } else { 10 };
+    }
+
+    let start = idx;
+    while let Some(value) = digit(idx, base) {
+        let next = parsed.magnitude.checked_mul(base as u64);
+        match next.and_then(|next| next.checked_add(value as u64)) {
+            Some(next) => parsed.magnitude = next,
+            None => parsed.overflow = true,
+        }
+
+        idx += 1;
+    }
+
+    if idx != start {
+        parsed.len = idx;
+    }
+
+    return parsed;
+}
+
+pub fn printf(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let top_ptr_offset = sel.memory.stack_length();
+    let top_ptr = VarPointer::new_stack(top_ptr_offset, 0);
+    let param_len = i32::from_be(sel.memory.get_var(top_ptr)?);
+
+    let mut current_offset = top_ptr_offset - (param_len as u16);
+    let return_offset = current_offset - 1;
+    let format_ptr = VarPointer::new_stack(current_offset, 0); // TODO overflow
+    current_offset += 1;
+
+    let mut out = StringWriter::new();
+
+    let result = printf_internal(sel, format_ptr, current_offset, &mut out);
+    let out = out.into_string();
+    let len = out.len() as i32; // TODO overflow
+    write!(sel.memory.stdout(), "{}", out)?;
+    result?;
+
+    let return_ptr = VarPointer::new_stack(return_offset, 0); // TODO overflow
+    sel.memory.set(return_ptr, len.to_be())?;
+
+    return Ok(None);
+}
+
+fn printf_arg<T: Copy>(sel: &Runtime, ptr: VarPointer) -> Result<T, IError> {
+    let value = sel.memory.get_var(ptr)?;
+    let origin = sel.memory.uninit_origin(ptr, mem::size_of::<T>() as u32);
+    sel.check_init(origin, "this output")?;
+    return Ok(value);
+}
+
+#[allow(unused_assignments)] // TODO remove this when we make this fully standard compliant
+pub fn printf_internal(
+    sel: &mut Runtime,
+    format_ptr: VarPointer,
+    mut current_offset: u16,
+    mut out: &mut StringWriter,
+) -> Result<(), IError> {
+    // OPTIMIZE This does an unnecessary linear scan
+    let format_str = sel.cstring_bytes(sel.memory.get_var(format_ptr)?)?;
+    let map_err = |err| error!("WriteFailed", "failed to write to stdout ({})", err);
+
+    // CREDIT heavily inspired by https://github.com/mpaland/printf/blob/master/printf.c
+
+    const FLAGS_ZEROPAD: u32 = 1;
+    const FLAGS_LEFT: u32 = 2;
+    const FLAGS_PLUS: u32 = 4;
+    const FLAGS_SPACE: u32 = 8;
+    const FLAGS_HASH: u32 = 16;
+    const FLAGS_PRECISION: u32 = 32;
+    const FLAGS_LONG: u32 = 64;
+    const FLAGS_LONG_LONG: u32 = 128;
+
+    let mut next_ptr = || {
+        let var_ptr = VarPointer::new_stack(current_offset, 0);
+        current_offset += 1;
+        return var_ptr;
+    };
+
+    let parse_int = |begin: usize| {
+        let mut idx = begin;
+        if format_str[idx] >= b'0' && format_str[idx] <= b'9' {
+            let mut collect = 0;
+            loop {
+                collect *= 10;
+                collect += (format_str[idx] - b'0') as usize;
+                idx += 1;
+
+                if format_str[idx] < b'0' || format_str[idx] > b'9' {
+                    break;
+                }
+            }
+
+            return Some((collect, idx - begin));
+        }
+
+        return None;
+    };
+
+    let mut idx = 0;
+    while idx < format_str.len() {
+        let mut idx2 = idx;
+        while idx2 < format_str.len() && format_str[idx2] != b'%' {
+            idx2 += 1;
+        }
+
+        write_utf8_lossy(&mut out, &format_str[idx..idx2]).map_err(map_err)?;
+
+        if idx2 == format_str.len() {
+            break;
+        }
+
+        // format_str[idx2] == b'%'
+
+        idx2 += 1;
+        if idx2 == format_str.len() {
+            return Err(error!(
+                "InvalidFormatString",
+                "format string ends with a single '%'; to print out a '%' use '%%'"
+            ));
+        }
+
+        // format specifier?  %[flags][width][.precision][length]
+        let mut flags = 0;
+        let mut width = 0;
+        let mut precision = 0;
+
+        loop {
+            match format_str[idx2] {
+                b'0' => flags |= FLAGS_ZEROPAD,
+                b'-' => flags |= FLAGS_LEFT,
+                b'+' => flags |= FLAGS_PLUS,
+                b' ' => flags |= FLAGS_SPACE,
+                b'#' => flags |= FLAGS_HASH,
+                _ => break,
+            }
+            idx2 += 1;
+        }
+
+        if let Some((w, diff)) = parse_int(idx2) {
+            idx2 += diff;
+            width = w;
+        } else if format_str[idx2] == b'*' {
+            let mut next = i32::from_be(printf_arg(sel, next_ptr())?);
+            if next < 0 {
+                flags |= FLAGS_LEFT;
+                next *= -1;
+            }
+
+            width = next as usize;
+            idx2 += 1;
+        }
+
+        if format_str[idx2] == b'.' {
+            flags |= FLAGS_PRECISION;
+            idx2 += 1;
+
+            if let Some((prec, diff)) = parse_int(idx2) {
+                idx2 += diff;
+                precision = prec;
+            } else if format_str[idx2] == b'*' {
+                let next = i32::from_be(printf_arg(sel, next_ptr())?);
+                precision = if next > 0 { next } else { 0 } as usize;
+                idx2 += 1;
+            }
+        }
+
+        match format_str[idx2] {
+            b'l' => {
+                flags |= FLAGS_LONG;
+                idx2 += 1;
+                if format_str[idx2] == b'l' {
+                    flags |= FLAGS_LONG_LONG;
+                    idx2 += 1;
+                }
+            }
+            _ => {}
+        }
+
+        match format_str[idx2] {
+            b'u' => {
+                let base = 10;
+                flags &= !FLAGS_HASH;
+                if (flags & FLAGS_PRECISION) != 0 {
+                    flags &= !FLAGS_ZEROPAD;
+                }
                 flags &= !(FLAGS_PLUS | FLAGS_SPACE);
 
-                if (flags & FLAGS_LONG_LONG) != 0 {
-                    let value = u64::from_be(sel.memory.get_var(next_ptr())?);
-                    write!(&mut out, "{}", value).map_err(map_err)?;
-                } else if (flags & FLAGS_LONG) != 0 {
-                    let value = u64::from_be(sel.memory.get_var(next_ptr())?);
-                    write!(&mut out, "{}", value).map_err(map_err)?;
-                } else {
-                    let value = u32::from_be(sel.memory.get_var(next_ptr())?);
-                    write!(&mut out, "{}", value).map_err(map_err)?;
-                }
+                if (flags & FLAGS_LONG_LONG) != 0 {
+                    let value = u64::from_be(printf_arg(sel, next_ptr())?);
+                    write!(&mut out, "{}", value).map_err(map_err)?;
+                } else if (flags & FLAGS_LONG) != 0 {
+                    let value = u64::from_be(printf_arg(sel, next_ptr())?);
+                    write!(&mut out, "{}", value).map_err(map_err)?;
+                } else {
+                    let value = u32::from_be(printf_arg(sel, next_ptr())?);
+                    write!(&mut out, "{}", value).map_err(map_err)?;
+                }
+            }
+            b'i' | b'd' => {
+                let base = 10;
+                flags &= !FLAGS_HASH;
+                if (flags & FLAGS_PRECISION) != 0 {
+                    flags &= !FLAGS_ZEROPAD;
+                }
+
+                if (flags & FLAGS_LONG_LONG) != 0 {
+                    let value = i64::from_be(printf_arg(sel, next_ptr())?);
+                    write!(&mut out, "{}", value).map_err(map_err)?;
+                } else if (flags & FLAGS_LONG) != 0 {
+                    let value = i64::from_be(printf_arg(sel, next_ptr())?);
+                    write!(&mut out, "{}", value).map_err(map_err)?;
+                } else {
+                    let value = i32::from_be(printf_arg(sel, next_ptr())?);
+                    write!(&mut out, "{}", value).map_err(map_err)?;
+                }
+            }
+            conversion @ (b'f' | b'e' | b'g') => {
+                let value = f64::from_bits(u64::from_be(printf_arg(sel, next_ptr())?));
+                let precision = match (flags & FLAGS_PRECISION) != 0 {
+                    true => precision,
+                    false => 6,
+                };
+
+                let hash = (flags & FLAGS_HASH) != 0;
+                let mut text = format_double(value, conversion, precision, hash);
+                if (flags & FLAGS_PLUS) != 0 && !text.starts_with('-') {
+                    text.insert(0, '+');
+                } else if (flags & FLAGS_SPACE) != 0 && !text.starts_with('-') {
+                    text.insert(0, ' ');
+                }
+
+                let pad = width.saturating_sub(text.len());
+                if (flags & FLAGS_ZEROPAD) != 0 && value.is_finite() {
+                    let sign = text.starts_with(|c| c == '-' || c == '+' || c == ' ') as usize;
+                    text.insert_str(sign, &"0".repeat(pad));
+                } else {
+                    text.insert_str(0, &" ".repeat(pad));
+                }
+
+                write!(&mut out, "{}", text).map_err(map_err)?;
+            }
+            b'c' => {
+                let value: u8 = printf_arg(sel, next_ptr())?;
+                write!(&mut out, "{}", char::from(value)).map_err(map_err)?;
+            }
+            b'%' => {
+                write_utf8_lossy(&mut out, &[b'%']).map_err(map_err)?;
+            }
+            b's' => {
+                let char_ptr = printf_arg(sel, next_ptr())?;
+
+                write_utf8_lossy(&mut out, sel.cstring_bytes(char_ptr)?).map_err(map_err)?;
+            }
+            byte => {
+                return Err(error!(
+                    "InvalidFormatString",
+                    "got byte '{}' after '%'",
+                    char::from(byte)
+                ))
+            }
+        }
+
+        idx = idx2 + 1;
+    }
+
+    return Ok(());
+}
+
+/// Formats `value` with commas between each group of three digits
+fn with_commas(value: u64) -> String {
+    let digits = value.to_string();
+    let mut out = String::new();
+    for (idx, digit) in digits.chars().enumerate() {
+        if idx != 0 && (digits.len() - idx) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(digit);
+    }
+
+    return out;
+}
+
+fn describe_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    if millis % 1000 != 0 {
+        return format!("{} milliseconds", millis);
+    }
+
+    match millis / 1000 {
+        1 => return "1 second".to_string(),
+        seconds => return format!("{} seconds", seconds),
+    }
+}
+
+/// Formats `value` like C's %f, %e and %g conversions
+fn format_double(value: f64, conversion: u8, precision: usize, hash: bool) -> String {
+    if value.is_nan() {
+        return "nan".to_string();
+    } else if value.is_infinite() {
+        return if value < 0.0 { "-inf" } else { "inf" }.to_string();
+    }
+
+    // Rust writes exponents like `e-7`, C writes them like `e-07`
+    let exponential = |precision: usize| {
+        let text = format!("{:.*e}", precision, value);
+        let (mantissa, exp) = text.split_at(text.find('e').unwrap());
+        let exp: i32 = exp[1..].parse().unwrap();
+        let sign = if exp < 0 { '-' } else { '+' };
+        return (format!("{}e{}{:02}", mantissa, sign, exp.abs()), exp);
+    };
+
+    match conversion {
+        b'f' => return format!("{:.*}", precision, value),
+        b'e' => return exponential(precision).0,
+        _ => {}
+    }
+
+    let precision = precision.max(1);
+    let (text, exp) = exponential(precision - 1);
+    let mut text = match (exp as i64) < precision as i64 && exp >= -4 {
+        true => format!("{:.*}", (precision as i64 - 1 - exp as i64) as usize, value),
+        false => text,
+    };
+
+    if !hash && text.contains('.') {
+        let exp_start = text.find('e').unwrap_or(text.len());
+        let exponent = text.split_off(exp_start);
+        let trimmed = text.trim_end_matches('0').trim_end_matches('.');
+        text = format!("{}{}", trimmed, exponent);
+    }
+
+    return text;
+}
+
+pub fn tci_stream(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let fd_var = first_param(sel, 1);
+    let ret_ptr = VarPointer::new_stack(fd_var - 1, 0);
+
+    let fd = i32::from_be(sel.memory.get_var(VarPointer::new_stack(fd_var, 0))?);
+    if fd < 0 || fd > 2 {
+        return Err(error!("InvalidStream", "there's no standard stream {}", fd));
+    }
+
+    let stream = VarPointer::new_binary(sel.streams, 4 * fd as u32);
+    sel.memory.set(ret_ptr, stream)?;
+    return Ok(None);
+}
+
+/// Reads the FILE* argument in `var`, checking that it's open and that `mode`
+/// (FILE_READ or FILE_WRITE) is allowed on it. Returns its file descriptor.
+fn stream_arg(sel: &Runtime, func: &str, var: u16, mode: u8) -> Result<u32, IError> {
+    let ptr = pointer_arg(sel, var)?;
+    let name = arg_name(sel, var);
+    let label = |err: IError, message: &str| match arg_loc(sel, var) {
+        Some(loc) => err.with_label(loc, message),
+        None => err,
+    };
+
+    if ptr == VarPointer::null() {
+        let err = error!(
+            "InvalidStream",
+            "{} was given `{}`, which is NULL", func, name
+        );
+        return Err(label(err, "fopen returns NULL when it can't open a file"));
+    }
+
+    let is_stream = ptr.is_binary() && ptr.var_idx() == sel.streams as usize;
+    if !is_stream || ptr.offset() % 4 != 0 {
+        let err = error!("InvalidStream", "`{}` isn't a FILE* from fopen", name);
+        return Err(label(err, "this should be a FILE*"));
+    }
+
+    let fd = ptr.offset() / 4;
+    let allowed = match fd {
+        0 => FILE_READ,
+        1 | 2 => FILE_WRITE,
+        fd => match sel.memory.open_files.get(fd as usize - 3) {
+            Some(file) => match file.close {
+                Some(close) => {
+                    let err = error!(
+                        "InvalidStream",
+                        "{} used `{}` after it was closed", func, name
+                    )
+                    .with_label(close, "the file was closed here");
+                    return Err(label(err, "this file is closed"));
+                }
+                None => file.mode,
+            },
+            None => {
+                let err = error!("InvalidStream", "`{}` isn't a FILE* from fopen", name);
+                return Err(label(err, "this should be a FILE*"));
+            }
+        },
+    };
+
+    if allowed & mode == 0 {
+        let (action, opened) = match mode {
+            FILE_READ => ("read from", "for writing only"),
+            _ => ("write to", "for reading only"),
+        };
+
+        let err = error!(
+            "InvalidStream",
+            "{} can't {} `{}`, because it's open {}", func, action, name, opened
+        );
+        return Err(label(err, &format!("this can't be used to {}", action)));
+    }
+
+    return Ok(fd);
+}
+
+/// Makes sure the stream `fd` has input that `ready` accepts, or has reached its
+/// end. Returns false if the program has to wait for more of stdin.
+fn fill_stream(
+    sel: &mut Runtime,
+    fd: u32,
+    ready: impl FnMut(&[u8]) -> bool,
+) -> Result<bool, IError> {
+    if fd == 0 {
+        return Ok(sel.fill_stdin(ready));
+    }
+
+    return Ok(true); // files are never waiting on more input
+}
+
+fn stream_unread(sel: &Runtime, fd: u32) -> &[u8] {
+    if fd == 0 {
+        return sel.memory.stdin_unread();
+    }
+
+    let file = sel.memory.open_files[fd as usize - 3];
+    let contents = &sel.memory.files[file.file as usize].contents;
+    return &contents[(file.pos as usize).min(contents.len())..];
+}
+
+fn consume_stream(sel: &mut Runtime, fd: u32, len: u32) {
+    if len == 0 {
+        return;
+    }
+
+    if fd == 0 {
+        sel.memory.consume_stdin(len);
+        return;
+    }
+
+    let pos = sel.memory.open_files[fd as usize - 3].pos;
+    sel.memory.seek_file(fd, pos + len);
+}
+
+fn write_stream(sel: &mut Runtime, fd: u32, bytes: &[u8]) -> Result<(), IError> {
+    match fd {
+        1 => sel.memory.stdout().write_all(bytes)?,
+        2 => sel.memory.stderr().write_all(bytes)?,
+        fd => {
+            let open = sel.memory.open_files[fd as usize - 3];
+            let len = sel.memory.files[open.file as usize].contents.len() as u32;
+            let pos = match open.mode & FILE_APPEND {
+                0 => open.pos.min(len),
+                _ => len,
+            };
+
+            let overwritten = (len - pos).min(bytes.len() as u32);
+            sel.memory.splice_file(open.file, pos, overwritten, bytes);
+            sel.memory.seek_file(fd, pos + bytes.len() as u32);
+        }
+    }
+
+    return Ok(());
+}
+
+pub fn fopen(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let (path_var, mode_var) = (first_param(sel, 2), first_param(sel, 2) + 1);
+    let ret_ptr = VarPointer::new_stack(path_var - 1, 0);
+
+    let (_, path) = string_arg(sel, "fopen", path_var, usize::MAX)?;
+    let (_, mode_str) = string_arg(sel, "fopen", mode_var, usize::MAX)?;
+    let mode = match &mode_str[..] {
+        b"r" | b"rb" => FILE_READ,
+        b"w" | b"wb" => FILE_WRITE,
+        b"a" | b"ab" => FILE_WRITE | FILE_APPEND,
+        b"r+" | b"rb+" | b"r+b" => FILE_READ | FILE_WRITE,
+        b"w+" | b"wb+" | b"w+b" => FILE_READ | FILE_WRITE,
+        b"a+" | b"ab+" | b"a+b" => FILE_READ | FILE_WRITE | FILE_APPEND,
+        _ => {
+            let mut err = error!(
+                "InvalidFileMode",
+                "\"{}\" isn't a file mode; try \"r\" to read, \"w\" to write, or \"a\" to append",
+                String::from_utf8_lossy(&mode_str)
+            );
+
+            if let Some(loc) = arg_loc(sel, mode_var) {
+                err = err.with_label(loc, "this mode is invalid");
             }
-            b'i' | b'd' => {
-                let base = 10;
-                flags &= !FLAGS_HASH;
-                if (flags & FLAGS_PRECISION) != 0 {
-                    flags &= !FLAGS_ZEROPAD;
-                }
 
-                if (flags & FLAGS_LONG_LONG) != 0 {
-                    let value = i64::from_be(sel.memory.get_var(next_ptr())?);
-                    write!(&mut out, "{}", value).map_err(map_err)?;
-                } else if (flags & FLAGS_LONG) != 0 {
-                    let value = i64::from_be(sel.memory.get_var(next_ptr())?);
-                    write!(&mut out, "{}", value).map_err(map_err)?;
-                } else {
-                    let value = i32::from_be(sel.memory.get_var(next_ptr())?);
-                    write!(&mut out, "{}", value).map_err(map_err)?;
-                }
+            return Err(err);
+        }
+    };
+
+    let path = String::from_utf8_lossy(&path).to_string();
+    let truncate = mode_str[0] == b'w';
+    let fd = match sel.memory.open_files.len() as u32 + 3 {
+        fd if fd < FOPEN_MAX => Some(fd),
+
+        // Every descriptor has been used, so reuse one whose file was closed;
+        // until then, using a closed FILE* can always be caught
+        _ => {
+            let mut open = sel.memory.open_files.iter();
+            let closed = open.position(|file| file.close.is_some());
+            closed.map(|idx| idx as u32 + 3)
+        }
+    };
+
+    let file = match sel.memory.find_file(&path) {
+        _ if fd.is_none() => None,
+        Some(file) => Some(file),
+        None if mode_str[0] == b'r' => None,
+        None => Some(sel.memory.create_file(&path)),
+    };
+
+    let (fd, file) = match (fd, file) {
+        (Some(fd), Some(file)) => (fd, file),
+        _ => {
+            sel.memory.set(ret_ptr, VarPointer::null())?;
+            return Ok(None);
+        }
+    };
+
+    if truncate {
+        let len = sel.memory.files[file as usize].contents.len() as u32;
+        sel.memory.splice_file(file, 0, len, &[]);
+    }
+
+    let loc = sel.program.ops[sel.memory.pc as usize].loc;
+    sel.memory.open_file(fd, file, 0, mode, loc);
+    sel.memory
+        .set(ret_ptr, VarPointer::new_binary(sel.streams, 4 * fd))?;
+    return Ok(None);
+}
+
+pub fn fclose(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let stream_var = first_param(sel, 1);
+    let ret_ptr = VarPointer::new_stack(stream_var - 1, 0);
+
+    let fd = stream_arg(sel, "fclose", stream_var, FILE_READ | FILE_WRITE)?;
+    if fd > 2 {
+        let loc = sel.program.ops[sel.memory.pc as usize].loc;
+        sel.memory.close_file(fd, loc);
+    }
+
+    sel.memory.set(ret_ptr, 0i32)?;
+    return Ok(None);
+}
+
+pub fn getchar(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let ret_ptr = VarPointer::new_stack(first_param(sel, 0) - 1, 0);
+    return read_char(sel, 0, ret_ptr);
+}
+
+pub fn fgetc(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let stream_var = first_param(sel, 1);
+    let ret_ptr = VarPointer::new_stack(stream_var - 1, 0);
+
+    let fd = stream_arg(sel, "fgetc", stream_var, FILE_READ)?;
+    return read_char(sel, fd, ret_ptr);
+}
+
+fn read_char(sel: &mut Runtime, fd: u32, ret_ptr: VarPointer) -> Result<Option<i32>, IError> {
+    if !fill_stream(sel, fd, |unread| unread.len() > 0)? {
+        return Ok(None);
+    }
+
+    let result = match stream_unread(sel, fd).first() {
+        Some(byte) => *byte as i32,
+        None => -1,
+    };
+
+    if result != -1 {
+        consume_stream(sel, fd, 1);
+    }
+
+    sel.memory.set(ret_ptr, result.to_be())?;
+    return Ok(None);
+}
+
+pub fn fputc(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let (c_var, stream_var) = (first_param(sel, 2), first_param(sel, 2) + 1);
+    let ret_ptr = VarPointer::new_stack(c_var - 1, 0);
+
+    let c = i32::from_be(sel.memory.get_var(VarPointer::new_stack(c_var, 0))?) as u8;
+    let fd = stream_arg(sel, "fputc", stream_var, FILE_WRITE)?;
+    write_stream(sel, fd, &[c])?;
+
+    sel.memory.set(ret_ptr, (c as i32).to_be())?;
+    return Ok(None);
+}
+
+pub fn fgets(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let (s_var, size_var) = (first_param(sel, 3), first_param(sel, 3) + 1);
+    let ret_ptr = VarPointer::new_stack(s_var - 1, 0);
+
+    let dest = pointer_arg(sel, s_var)?;
+    let size = i32::from_be(sel.memory.get_var(VarPointer::new_stack(size_var, 0))?);
+    let fd = stream_arg(sel, "fgets", size_var + 1, FILE_READ)?;
+
+    if size <= 0 {
+        sel.memory.set(ret_ptr, VarPointer::null())?;
+        return Ok(None);
+    }
+
+    let max = size as usize - 1;
+    let ready = |unread: &[u8]| unread.len() >= max || unread.contains(&b'\n');
+    if !fill_stream(sel, fd, ready)? {
+        return Ok(None);
+    }
+
+    let unread = stream_unread(sel, fd);
+    let mut line = match unread[..unread.len().min(max)]
+        .iter()
+        .position(|b| *b == b'\n')
+    {
+        Some(newline) => unread[..(newline + 1)].to_vec(),
+        None => unread[..unread.len().min(max)].to_vec(),
+    };
+
+    if line.len() == 0 && max > 0 {
+        sel.memory.set(ret_ptr, VarPointer::null())?;
+        return Ok(None);
+    }
+
+    let read = line.len() as u32;
+    line.push(0);
+    check_bounds(sel, "fgets", s_var, dest, line.len() as u64)?;
+
+    consume_stream(sel, fd, read);
+    sel.memory.write_bytes(dest, &line)?;
+    sel.memory.set(ret_ptr, dest)?;
+    return Ok(None);
+}
+
+pub fn fprintf(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let top_ptr_offset = sel.memory.stack_length();
+    let param_len = i32::from_be(
+        sel.memory
+            .get_var(VarPointer::new_stack(top_ptr_offset, 0))?,
+    );
+    let stream_var = top_ptr_offset - (param_len as u16); // TODO overflow
+    let ret_ptr = VarPointer::new_stack(stream_var - 1, 0);
+
+    let fd = stream_arg(sel, "fprintf", stream_var, FILE_WRITE)?;
+    let format_ptr = VarPointer::new_stack(stream_var + 1, 0);
+
+    let mut out = StringWriter::new();
+    let result = printf_internal(sel, format_ptr, stream_var + 2, &mut out);
+    let out = out.into_string();
+    write_stream(sel, fd, out.as_bytes())?;
+    result?;
+
+    sel.memory.set(ret_ptr, (out.len() as i32).to_be())?;
+    return Ok(None);
+}
+
+pub fn scanf(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let top_ptr_offset = sel.memory.stack_length();
+    let param_len = i32::from_be(
+        sel.memory
+            .get_var(VarPointer::new_stack(top_ptr_offset, 0))?,
+    );
+    let format_var = top_ptr_offset - (param_len as u16); // TODO overflow
+
+    let ret_ptr = VarPointer::new_stack(format_var - 1, 0);
+    return scanf_internal(sel, "scanf", 0, format_var, param_len as u16 - 1, ret_ptr);
+}
+
+pub fn fscanf(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let top_ptr_offset = sel.memory.stack_length();
+    let param_len = i32::from_be(
+        sel.memory
+            .get_var(VarPointer::new_stack(top_ptr_offset, 0))?,
+    );
+    let stream_var = top_ptr_offset - (param_len as u16); // TODO overflow
+
+    let ret_ptr = VarPointer::new_stack(stream_var - 1, 0);
+
+    let fd = stream_arg(sel, "fscanf", stream_var, FILE_READ)?;
+    let arg_count = param_len as u16 - 2;
+    return scanf_internal(sel, "fscanf", fd, stream_var + 1, arg_count, ret_ptr);
+}
+
+/// Runs scanf's format string in `format_var` against the stream `fd`, storing
+/// through the `arg_count` pointers after it
+fn scanf_internal(
+    sel: &mut Runtime,
+    func: &str,
+    fd: u32,
+    format_var: u16,
+    arg_count: u16,
+    ret_ptr: VarPointer,
+) -> Result<Option<i32>, IError> {
+    let (_, format) = string_arg(sel, func, format_var, usize::MAX)?;
+    let directives = parse_scan_format(&format)?;
+    let conversions = directives.iter().filter(|d| d.assigns()).count();
+    if conversions > arg_count as usize {
+        return Err(error!(
+            "InvalidFormatString",
+            "{} format string has {} conversions but only {} pointers to store them in",
+            func,
+            conversions,
+            arg_count
+        ));
+    }
+
+    for var in (format_var + 1)..(format_var + 1 + conversions as u16) {
+        if arg_type(sel, var).map_or(true, |t| t.is_pointer()) {
+            continue;
+        }
+
+        let mut err = error!(
+            "InvalidFormatString",
+            "{} needs a pointer to store each value in, but `{}` isn't a pointer",
+            func,
+            arg_name(sel, var)
+        );
+
+        if let Some(loc) = arg_loc(sel, var) {
+            err = err.with_label(loc, "did you mean to pass its address with `&`?");
+        }
+
+        return Err(err);
+    }
+
+    let mut result = None;
+    let ready = |unread: &[u8]| {
+        result = scan(&directives, unread, false);
+        result.is_some()
+    };
+    if !fill_stream(sel, fd, ready)? {
+        return Ok(None);
+    }
+
+    let result = match result {
+        Some(result) => result,
+        None => scan(&directives, stream_unread(sel, fd), true).unwrap(),
+    };
+
+    for (idx, value) in result.values.iter().enumerate() {
+        let var = format_var + 1 + idx as u16;
+        let dest = pointer_arg(sel, var)?;
+        check_bounds(sel, func, var, dest, value.len() as u64)?;
+        sel.memory.write_bytes(dest, value)?;
+    }
+
+    consume_stream(sel, fd, result.consumed as u32);
+    let ret = match result.input_failure && result.values.len() == 0 {
+        true => -1,
+        false => result.values.len() as i32,
+    };
+
+    sel.memory.set(ret_ptr, ret.to_be())?;
+    return Ok(None);
+}
+
+pub fn fread(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let ptr_var = first_param(sel, 4);
+    let ret_ptr = VarPointer::new_stack(ptr_var - 1, 0);
+
+    let dest = pointer_arg(sel, ptr_var)?;
+    let size = u64::from_be(sel.memory.get_var(VarPointer::new_stack(ptr_var + 1, 0))?);
+    let count = u64::from_be(sel.memory.get_var(VarPointer::new_stack(ptr_var + 2, 0))?);
+    let fd = stream_arg(sel, "fread", ptr_var + 3, FILE_READ)?;
+    let len = size.saturating_mul(count);
+    check_bounds(sel, "fread", ptr_var, dest, len)?;
+
+    if !fill_stream(sel, fd, |unread| unread.len() as u64 >= len)? {
+        return Ok(None);
+    }
+
+    let unread = stream_unread(sel, fd);
+    let items = match size {
+        0 => 0,
+        size => (unread.len() as u64).min(len) / size,
+    };
+    let bytes = unread[..((items * size) as usize)].to_vec();
+
+    consume_stream(sel, fd, bytes.len() as u32);
+    sel.memory.write_bytes(dest, &bytes)?;
+    sel.memory.set(ret_ptr, items.to_be())?;
+    return Ok(None);
+}
+
+pub fn fwrite(sel: &mut Runtime) -> Result<Option<i32>, IError> {
+    let ptr_var = first_param(sel, 4);
+    let ret_ptr = VarPointer::new_stack(ptr_var - 1, 0);
+
+    let size = u64::from_be(sel.memory.get_var(VarPointer::new_stack(ptr_var + 1, 0))?);
+    let count = u64::from_be(sel.memory.get_var(VarPointer::new_stack(ptr_var + 2, 0))?);
+    let fd = stream_arg(sel, "fwrite", ptr_var + 3, FILE_WRITE)?;
+    let bytes = bytes_arg(sel, "fwrite", ptr_var, size.saturating_mul(count))?;
+
+    write_stream(sel, fd, &bytes)?;
+    let written = if size == 0 { 0 } else { count };
+    sel.memory.set(ret_ptr, written.to_be())?;
+    return Ok(None);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScanDirective {
+    Space,
+    Literal(u8),
+    Convert {
+        conversion: u8,
+        width: Option<usize>,
+        size: u32,
+        suppress: bool,
+    },
+}
+
+impl ScanDirective {
+    fn assigns(&self) -> bool {
+        match self {
+            ScanDirective::Convert { suppress, .. } => return !suppress,
+            _ => return false,
+        }
+    }
+}
+
+struct ScanResult {
+    values: Vec<Vec<u8>>, // the bytes to store through each pointer argument
+    consumed: usize,
+    input_failure: bool,
+}
+
+/// Parses a scanf format string, erroring on anything scanf doesn't support
+fn parse_scan_format(format: &[u8]) -> Result<Vec<ScanDirective>, IError> {
+    let mut directives = Vec::new();
+    let mut idx = 0;
+    while idx < format.len() {
+        let byte = format[idx];
+        idx += 1;
+
+        if byte.is_ascii_whitespace() {
+            if directives.last() != Some(&ScanDirective::Space) {
+                directives.push(ScanDirective::Space);
             }
-            b'c' => {
-                let value: u8 = sel.memory.get_var(next_ptr())?;
-                write!(&mut out, "{}", char::from(value)).map_err(map_err)?;
+            continue;
+        }
+
+        if byte != b'%' {
+            directives.push(ScanDirective::Literal(byte));
+            continue;
+        }
+
+        if format.get(idx) == Some(&b'%') {
+            directives.push(ScanDirective::Space);
+            directives.push(ScanDirective::Literal(b'%'));
+            idx += 1;
+            continue;
+        }
+
+        let suppress = format.get(idx) == Some(&b'*');
+        if suppress {
+            idx += 1;
+        }
+
+        let mut width = None;
+        while idx < format.len() && format[idx].is_ascii_digit() {
+            let digit = (format[idx] - b'0') as usize;
+            width = Some(width.unwrap_or(0) * 10 + digit);
+            idx += 1;
+        }
+
+        if width == Some(0) {
+            return Err(error!(
+                "InvalidFormatString",
+                "scanf conversions can't have a width of 0"
+            ));
+        }
+
+        let mut size = 4;
+        if format.get(idx) == Some(&b'h') {
+            size = 2;
+            idx += 1;
+            if format.get(idx) == Some(&b'h') {
+                size = 1;
+                idx += 1;
             }
-            b'%' => {
-                write_utf8_lossy(&mut out, &[b'%']).map_err(map_err)?;
+        } else if format.get(idx) == Some(&b'l') {
+            size = 8;
+            idx += 1;
+            if format.get(idx) == Some(&b'l') {
+                idx += 1;
             }
-            b's' => {
-                let char_ptr = sel.memory.get_var(next_ptr())?;
+        }
 
-                write_utf8_lossy(&mut out, sel.cstring_bytes(char_ptr)?).map_err(map_err)?;
+        let conversion = match format.get(idx) {
+            Some(byte @ (b'd' | b'i' | b'u' | b'x')) => *byte,
+            Some(byte @ (b'c' | b's')) if size == 4 => *byte,
+            Some(byte) => {
+                return Err(error!(
+                    "InvalidFormatString",
+                    "scanf doesn't support the conversion '{}'",
+                    char::from(*byte)
+                ))
             }
-            byte => {
+            None => {
                 return Err(error!(
                     "InvalidFormatString",
-                    "got byte '{}' after '%'",
-                    char::from(byte)
+                    "format string ends with a single '%'; to match a '%' use '%%'"
                 ))
             }
+        };
+        idx += 1;
+
+        directives.push(ScanDirective::Convert {
+            conversion,
+            width,
+            size,
+            suppress,
+        });
+    }
+
+    return Ok(directives);
+}
+
+/// Matches `directives` against `input`, the way scanf would. Returns `None` if
+/// the result could still change when more input arrives and `closed` is false.
+fn scan(directives: &[ScanDirective], input: &[u8], closed: bool) -> Option<ScanResult> {
+    let mut result = ScanResult {
+        values: Vec::new(),
+        consumed: 0,
+        input_failure: false,
+    };
+
+    let mut pos = 0;
+    let skip_space = |pos: &mut usize| {
+        while *pos < input.len() && input[*pos].is_ascii_whitespace() {
+            *pos += 1;
         }
 
-        idx = idx2 + 1;
+        return *pos < input.len() || closed;
+    };
+
+    for directive in directives {
+        let (conversion, width, size, suppress) = match *directive {
+            ScanDirective::Space => {
+                if !skip_space(&mut pos) {
+                    return None;
+                }
+                continue;
+            }
+            ScanDirective::Literal(byte) => match input.get(pos) {
+                Some(found) if *found == byte => {
+                    pos += 1;
+                    continue;
+                }
+                Some(_) => break,
+                None if closed => {
+                    result.input_failure = true;
+                    break;
+                }
+                None => return None,
+            },
+            ScanDirective::Convert {
+                conversion,
+                width,
+                size,
+                suppress,
+            } => (conversion, width, size, suppress),
+        };
+
+        if conversion != b'c' && !skip_space(&mut pos) {
+            return None;
+        }
+
+        if pos == input.len() {
+            result.input_failure = true;
+            break;
+        }
+
+        let width = width.unwrap_or(match conversion {
+            b'c' => 1,
+            _ => usize::MAX,
+        });
+        let end = pos.saturating_add(width).min(input.len());
+        let value = match conversion {
+            b'c' => {
+                if end - pos < width && !closed {
+                    return None;
+                } else if end - pos < width {
+                    result.input_failure = true;
+                    break;
+                }
+
+                input[pos..end].to_vec()
+            }
+            b's' => {
+                let len = match input[pos..end].iter().position(|b| b.is_ascii_whitespace()) {
+                    Some(len) => len,
+                    None if end == input.len() && end - pos < width && !closed => return None,
+                    None => end - pos,
+                };
+
+                let mut value = input[pos..(pos + len)].to_vec();
+                value.push(0);
+                pos += len;
+                value
+            }
+            _ => {
+                let radix = match conversion {
+                    b'x' => 16,
+                    _ => 10,
+                };
+
+                let text = &input[pos..end];
+                let mut len = match text.first() {
+                    Some(b'-') | Some(b'+') => 1,
+                    _ => 0,
+                };
+                let digits_start = len;
+                while len < text.len() && (text[len] as char).is_digit(radix) {
+                    len += 1;
+                }
+
+                if len == text.len() && end == input.len() && end - pos < width && !closed {
+                    return None;
+                }
+
+                if len == digits_start {
+                    break; // matching failure
+                }
+
+                let digits = core::str::from_utf8(&text[digits_start..len]).unwrap();
+                let magnitude = u64::from_str_radix(digits, radix).unwrap_or(u64::MAX);
+                let value = match text[0] {
+                    b'-' => (magnitude as i64).wrapping_neg(),
+                    _ => magnitude as i64,
+                };
+
+                pos += len;
+                value.to_be_bytes()[(8 - size as usize)..].to_vec()
+            }
+        };
+
+        if conversion == b'c' {
+            pos = end;
+        }
+
+        if !suppress {
+            result.values.push(value);
+        }
+        result.consumed = pos;
     }
 
-    return Ok(());
+    result.consumed = result.consumed.max(pos);
+    return Some(result);
 }
@@ -11,6 +11,10 @@ pub enum TokenKind<'a> {
     Ident(u32),
     TypeIdent(u32),
     IntLiteral(i32),
+    UIntLiteral(u32),
+    LongLiteral(i64),
+    ULongLiteral(u64),
+    DoubleLiteral(u64), // the bits of the value, so that tokens can be compared
     StringLiteral(&'a str),
     CharLiteral(i8),
 
@@ -115,7 +119,8 @@ pub fn invalid_token(file: u32, begin: usize, end: usize) -> Error {
         "invalid token",
         l(begin as u32, end as u32, file),
         "token found here"
-    );
+    )
+    .with_code("TCI0014");
 }
 
 pub type TokenDb<'a> = HashMap<u32, &'a [Token<'a>]>;
@@ -259,7 +264,8 @@ impl<'b> Lexer<'b> {
                         "unexpected end of file",
                         l(ident_begin as u32, begin as u32, self.file),
                         "EOF found here"
-                    ));
+                    )
+                    .with_code("TCI0015"));
                 }
 
                 while self.peek_check(data, is_ident_char) {
@@ -272,7 +278,8 @@ impl<'b> Lexer<'b> {
                         "expected an identifer for macro declaration",
                         l(ident_begin as u32, ident_begin as u32 + 1, self.file),
                         "This should be an identifier"
-                    ));
+                    )
+                    .with_code("TCI0016"));
                 }
 
                 let id = symbols.translate_add(ident_begin..self.current, self.file);
@@ -382,8 +389,11 @@ impl<'b> Lexer<'b> {
                             l(begin as u32, self.current as u32, self.file),
                             format!("got error '{}'", err)
                         )
+                        .with_code("TCI0017")
                     };
-                    let include_id = symbols.add_from_symbols(self.file, id).map_err(map_err)?;
+                    let include_id = symbols
+                        .add_from_symbols(self.file, id, false)
+                        .map_err(map_err)?;
                     self.output.push(Token::new(
                         TokenKind::Include(include_id),
                         begin..self.current,
@@ -395,7 +405,8 @@ impl<'b> Lexer<'b> {
                             "include cycle detected",
                             l(begin as u32, self.current as u32, self.file),
                             "found here"
-                        ));
+                        )
+                        .with_code("TCI0018"));
                     }
 
                     if let Some(_) = token_db.get(&include_id) {
@@ -421,18 +432,59 @@ impl<'b> Lexer<'b> {
                     if !self.peek_eq(data, b'\n') && !self.peek_eq_series(data, &CRLF) {
                         return Err(expected_newline("include", begin, self.current, self.file));
                     }
+
+                    if id < INIT_SYMS.files.len() as u32 {
+                        self.output.push(Token::new(
+                            TokenKind::IncludeSys(id),
+                            begin..self.current,
+                            self.file,
+                        ));
+
+                        if let Some(_) = token_db.get(&id) {
+                            return Ok(());
+                        }
+
+                        let toks =
+                            Lexer::new(id).lex_file(buckets, incomplete, token_db, symbols)?;
+                        token_db.insert(id, toks);
+                        return Ok(());
+                    }
+
+                    let map_err = |err| {
+                        error!(
+                            "Error finding file",
+                            l(begin as u32, self.current as u32, self.file),
+                            format!("got error '{}'", err)
+                        )
+                        .with_code("TCI0017")
+                    };
+                    let include_id = symbols
+                        .add_from_symbols(self.file, id, true)
+                        .map_err(map_err)?;
                     self.output.push(Token::new(
-                        TokenKind::IncludeSys(id),
+                        TokenKind::Include(include_id),
                         begin..self.current,
                         self.file,
                     ));
 
-                    if let Some(_) = token_db.get(&id) {
+                    if incomplete.contains(&include_id) {
+                        return Err(error!(
+                            "include cycle detected",
+                            l(begin as u32, self.current as u32, self.file),
+                            "found here"
+                        )
+                        .with_code("TCI0018"));
+                    }
+
+                    if let Some(_) = token_db.get(&include_id) {
                         return Ok(());
                     }
 
-                    let toks = Lexer::new(id).lex_file(buckets, incomplete, token_db, symbols)?;
-                    token_db.insert(id, toks);
+                    incomplete.insert(include_id);
+                    let toks =
+                        Lexer::new(include_id).lex_file(buckets, incomplete, token_db, symbols)?;
+                    token_db.insert(include_id, toks);
+                    incomplete.remove(&include_id);
                     return Ok(());
                 }
             }
@@ -441,7 +493,8 @@ impl<'b> Lexer<'b> {
                     "invalid compiler directive",
                     l(begin as u32, self.current as u32, self.file),
                     "directive found here"
-                ));
+                )
+                .with_code("TCI0016"));
             }
         }
 
@@ -513,14 +566,89 @@ impl<'b> Lexer<'b> {
             }
 
             x if (x >= b'0' && x <= b'9') => {
-                let mut int_value: i32 = (x - b'0') as i32;
+                let mut value = Some((x - b'0') as u64);
                 while self.peek_check(data, |b| b >= b'0' && b <= b'9') {
-                    int_value *= 10;
-                    int_value += (data[self.current] - b'0') as i32;
+                    let digit = (data[self.current] - b'0') as u64;
+                    value = value.and_then(|v| v.checked_mul(10)?.checked_add(digit));
                     self.current += 1;
                 }
 
-                ret_tok!(TokenKind::IntLiteral(int_value));
+                let is_fraction = self.peek_eq(data, b'.');
+                let is_exponent = self.peek_eq(data, b'e') || self.peek_eq(data, b'E');
+                if is_fraction || is_exponent {
+                    if is_fraction {
+                        self.current += 1;
+                        while self.peek_check(data, |b| b >= b'0' && b <= b'9') {
+                            self.current += 1;
+                        }
+                    }
+
+                    if self.peek_eq(data, b'e') || self.peek_eq(data, b'E') {
+                        self.current += 1;
+                        if self.peek_eq(data, b'+') || self.peek_eq(data, b'-') {
+                            self.current += 1;
+                        }
+
+                        if !self.peek_check(data, |b| b >= b'0' && b <= b'9') {
+                            return Err(error!(
+                                "exponent has no digits",
+                                l(begin as u32, self.current as u32, self.file),
+                                "expected digits after the `e`"
+                            )
+                            .with_code("TCI0019"));
+                        }
+
+                        while self.peek_check(data, |b| b >= b'0' && b <= b'9') {
+                            self.current += 1;
+                        }
+                    }
+
+                    let text = unsafe { std::str::from_utf8_unchecked(&data[begin..self.current]) };
+                    let value: f64 = text.parse().unwrap();
+                    ret_tok!(TokenKind::DoubleLiteral(value.to_bits()));
+                }
+
+                let (mut unsigned, mut long) = (false, false);
+                loop {
+                    if !unsigned && (self.peek_eq(data, b'u') || self.peek_eq(data, b'U')) {
+                        unsigned = true;
+                        self.current += 1;
+                    } else if !long && (self.peek_eq(data, b'l') || self.peek_eq(data, b'L')) {
+                        long = true;
+                        self.current += 1;
+                        if self.peek_eq(data, data[self.current - 1]) {
+                            self.current += 1; // long long is the same size as long
+                        }
+                    } else {
+                        break;
+                    }
+                }
+
+                let value = match value {
+                    Some(value) => value,
+                    None => {
+                        return Err(error!(
+                            "integer literal is too large",
+                            l(begin as u32, self.current as u32, self.file),
+                            "this doesn't fit in an unsigned long"
+                        )
+                        .with_code("TCI0019"));
+                    }
+                };
+
+                // Like in C, the literal gets the first type its value fits in
+                match (unsigned, long) {
+                    (false, false) if value <= i32::MAX as u64 => {
+                        ret_tok!(TokenKind::IntLiteral(value as i32))
+                    }
+                    (true, false) if value <= u32::MAX as u64 => {
+                        ret_tok!(TokenKind::UIntLiteral(value as u32))
+                    }
+                    (false, _) if value <= i64::MAX as u64 => {
+                        ret_tok!(TokenKind::LongLiteral(value as i64))
+                    }
+                    _ => ret_tok!(TokenKind::ULongLiteral(value)),
+                }
             }
 
             b'\"' => {
@@ -543,7 +671,8 @@ impl<'b> Lexer<'b> {
                         "empty character literal",
                         l(begin as u32, self.current as u32, self.file),
                         "found here"
-                    ));
+                    )
+                    .with_code("TCI0020"));
                 }
 
                 let closing = self.expect(data)?;
@@ -552,7 +681,8 @@ impl<'b> Lexer<'b> {
                         "expected closing single quote",
                         l(begin as u32, self.current as u32, self.file),
                         "this should be a closing single quote"
-                    ));
+                    )
+                    .with_code("TCI0020"));
                 }
 
                 ret_tok!(TokenKind::CharLiteral(byte as i8));
@@ -718,7 +848,7 @@ impl<'b> Lexer<'b> {
     #[inline]
     pub fn expect(&mut self, data: &[u8]) -> Result<u8, Error> {
         if self.current == data.len() {
-            return Err(error!("unexpected end of file"));
+            return Err(error!("unexpected end of file").with_code("TCI0015"));
         }
 
         let cur = self.current;
@@ -729,7 +859,7 @@ impl<'b> Lexer<'b> {
     #[inline]
     pub fn peek_expect(&self, data: &[u8]) -> Result<u8, Error> {
         if self.current == data.len() {
-            return Err(error!("unexpected end of file"));
+            return Err(error!("unexpected end of file").with_code("TCI0015"));
         }
 
         return Ok(data[self.current]);
@@ -822,7 +952,8 @@ impl<'b> Lexer<'b> {
                     "character is not valid ascii",
                     l(self.current as u32 - 1, self.current as u32, self.file),
                     "invalid character literal here"
-                ));
+                )
+                .with_code("TCI0020"));
             }
 
             if cur_b == surround {
@@ -835,13 +966,15 @@ impl<'b> Lexer<'b> {
                         "invalid character found when parsing string literal",
                         l(self.current as u32 - 1, self.current as u32, self.file),
                         "invalid character here"
-                    ));
+                    )
+                    .with_code("TCI0020"));
                 } else {
                     return Err(error!(
                         "invalid character found when parsing character literal",
                         l(self.current as u32 - 1, self.current as u32, self.file),
                         "invalid character here"
-                    ));
+                    )
+                    .with_code("TCI0020"));
                 }
             }
 
@@ -859,7 +992,8 @@ impl<'b> Lexer<'b> {
                         "invalid escape sequence",
                         l(self.current as u32 - 2, self.current as u32, self.file),
                         "invalid escape sequence here"
-                    ))
+                    )
+                    .with_code("TCI0020"))
                 }
             }
         }
@@ -884,5 +1018,6 @@ pub fn expected_newline(
         &format!("expected newline after {} directive", directive_name),
         l(begin as u32, current as u32, file),
         "directive here"
-    );
+    )
+    .with_code("TCI0016");
 }
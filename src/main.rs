@@ -12,7 +12,9 @@ mod assembler;
 mod ast;
 mod buckets;
 mod commands;
+mod explain;
 mod filedb;
+mod flow;
 mod interpreter;
 mod lexer;
 mod parser;
@@ -34,14 +36,19 @@ use rust_embed::RustEmbed;
 use std::borrow::Cow;
 use util::*;
 
-fn compile(env: &mut FileDb) -> Result<Program<'static>, Vec<Error>> {
+/// Compiles every file in `env`, returning the program along with any warnings.
+/// If compilation fails, the returned errors are followed by the warnings found
+/// up to that point.
+fn compile(env: &mut FileDb) -> Result<(Program<'static>, Vec<Error>), Vec<Error>> {
     let mut buckets = buckets::BucketList::with_capacity(2 * env.size());
     let mut buckets_begin = buckets;
     let mut tokens = lexer::TokenDb::new();
     let mut errors: Vec<Error> = Vec::new();
+    let mut warnings: Vec<Error> = Vec::new();
 
     let files_list = env.vec();
-    let files = files_list.iter();
+    let defines = env.defines;
+    let files = files_list.iter().chain(defines.iter());
     files.for_each(|&id| {
         let result = lexer::lex_file(buckets, &mut tokens, env, id);
         match result {
@@ -63,13 +70,16 @@ fn compile(env: &mut FileDb) -> Result<Program<'static>, Vec<Error>> {
 
     tokens = tokens
         .keys()
-        .filter_map(|&file| match preprocessor::preprocess_file(&tokens, file) {
-            Ok(toks) => Some((file, toks)),
-            Err(err) => {
-                errors.push(err);
-                None
-            }
-        })
+        .filter(|&&file| Some(file) != defines)
+        .filter_map(
+            |&file| match preprocessor::preprocess_file(&tokens, defines, file) {
+                Ok(toks) => Some((file, toks)),
+                Err(err) => {
+                    errors.push(err);
+                    None
+                }
+            },
+        )
         .map(|(file, toks)| {
             if let Some(n) = buckets.next() {
                 buckets = n;
@@ -90,8 +100,8 @@ fn compile(env: &mut FileDb) -> Result<Program<'static>, Vec<Error>> {
 
         match parser.parse_tokens(buckets, &tokens, file) {
             Ok(x) => return Some(x),
-            Err(err) => {
-                errors.push(err);
+            Err(errs) => {
+                errors.extend(errs);
                 return None;
             }
         }
@@ -109,13 +119,17 @@ fn compile(env: &mut FileDb) -> Result<Program<'static>, Vec<Error>> {
             buckets = n;
         }
 
-        let tfuncs = match type_checker::check_file(buckets, ast, env) {
+        let mut tfuncs = match type_checker::check_file(buckets, ast, env) {
             Ok(x) => x,
-            Err(err) => {
-                errors.push(err);
+            Err(errs) => {
+                let (errs, warns): (Vec<Error>, Vec<Error>) =
+                    errs.into_iter().partition(|err| !err.is_warning());
+                errors.extend(errs);
+                warnings.extend(warns);
                 return;
             }
         };
+        warnings.append(&mut tfuncs.warnings);
 
         match assembler.add_file(tfuncs) {
             Ok(()) => {}
@@ -126,19 +140,24 @@ fn compile(env: &mut FileDb) -> Result<Program<'static>, Vec<Error>> {
     });
 
     if errors.len() != 0 {
+        errors.extend(warnings);
         return Err(errors);
     }
 
     let program = match assembler.assemble(&env) {
         Ok(x) => x,
-        Err(err) => return Err(err.into()),
+        Err(err) => {
+            errors.push(err);
+            errors.extend(warnings);
+            return Err(errors);
+        }
     };
 
     while let Some(b) = unsafe { buckets_begin.dealloc() } {
         buckets_begin = b;
     }
 
-    Ok(program)
+    Ok((program, warnings))
 }
 
 fn emit_err(errs: &[Error], files: &FileDb, writer: &mut impl WriteColor) {
@@ -153,25 +172,174 @@ fn run_from_args(args: Vec<String>) -> ! {
 
     let writer = StandardStream::stderr(ColorChoice::Always);
 
+    if args.len() > 1 && args[1] == "--explain" {
+        let code = match args.get(2) {
+            Some(code) => code,
+            None => {
+                eprintln!("tci: missing argument to '--explain'");
+                std::process::exit(1);
+            }
+        };
+
+        match explain::lookup(code) {
+            Some(explanation) => print!("{}", explain::render(explanation)),
+            None => {
+                eprintln!("tci: '{}' is not a known error code", code);
+                std::process::exit(1);
+            }
+        }
+
+        std::process::exit(0);
+    }
+
     let mut files = FileDb::new(true);
-    for arg in args.iter().skip(1) {
-        files.add_from_fs(&arg).unwrap();
+    let mut defines: Vec<(&str, &str)> = Vec::new();
+    let mut disabled_lints: Vec<Lint> = Vec::new();
+    let mut sources = Vec::new();
+    let mut data_files: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut math_warnings = false;
+    let mut strict = false;
+    let mut limits = interpreter::Limits::DEFAULT;
+    let mut args_iter = args.iter().skip(1);
+    while let Some(arg) = args_iter.next() {
+        if arg == "-w" {
+            disabled_lints = Lint::ALL.to_vec();
+            continue;
+        }
+
+        if arg == "--math-warnings" {
+            math_warnings = true;
+            continue;
+        }
+
+        if arg == "--strict" {
+            strict = true;
+            continue;
+        }
+
+        if arg.starts_with("-W") {
+            let (name, enable) = match arg[2..].strip_prefix("no-") {
+                Some(name) => (name, false),
+                None => (&arg[2..], true),
+            };
+
+            let lint = match Lint::from_name(name) {
+                Some(lint) => lint,
+                None => {
+                    eprintln!("tci: unknown warning option '{}'", arg);
+                    std::process::exit(1);
+                }
+            };
+
+            disabled_lints.retain(|&prev| prev != lint);
+            if !enable {
+                disabled_lints.push(lint);
+            }
+
+            continue;
+        }
+
+        let (flag, value) = match arg.as_str() {
+            "-D" | "-U" | "-I" | "--data" | "--max-steps" | "--time-limit" | "--max-depth"
+            | "--max-stack" => match args_iter.next() {
+                Some(value) => (arg.as_str(), value.as_str()),
+                None => {
+                    eprintln!("tci: missing argument to '{}'", arg);
+                    std::process::exit(1);
+                }
+            },
+            x if x.starts_with("-D") || x.starts_with("-U") || x.starts_with("-I") => {
+                (&x[..2], &x[2..])
+            }
+            x => {
+                sources.push(x);
+                continue;
+            }
+        };
+
+        match flag {
+            "-D" => {
+                let (name, value) = match value.find('=') {
+                    Some(idx) => (&value[..idx], &value[(idx + 1)..]),
+                    None => (value, "1"),
+                };
+
+                if name.len() == 0 || !name.bytes().all(lexer::is_ident_char) {
+                    eprintln!("tci: macro name '{}' is not an identifier", name);
+                    std::process::exit(1);
+                }
+
+                defines.retain(|&(prev, _)| prev != name);
+                defines.push((name, value));
+            }
+            "-U" => defines.retain(|&(prev, _)| prev != value),
+            "-I" => files.add_include_dir(value),
+            "--data" => match std::fs::read(value) {
+                Ok(contents) => data_files.push((value.to_string(), contents)),
+                Err(err) => {
+                    eprintln!("tci: couldn't read data file '{}' ({})", value, err);
+                    std::process::exit(1);
+                }
+            },
+            "--max-steps" => match value.parse() {
+                Ok(steps) => limits.max_steps = Some(steps),
+                Err(_) => {
+                    eprintln!("tci: '{}' is not a number of steps", value);
+                    std::process::exit(1);
+                }
+            },
+            "--time-limit" => match value.parse() {
+                Ok(seconds) => limits.max_time = Some(std::time::Duration::from_secs(seconds)),
+                Err(_) => {
+                    eprintln!("tci: '{}' is not a number of seconds", value);
+                    std::process::exit(1);
+                }
+            },
+            "--max-depth" => match value.parse() {
+                Ok(depth) => limits.max_depth = Some(depth),
+                Err(_) => {
+                    eprintln!("tci: '{}' is not a number of calls", value);
+                    std::process::exit(1);
+                }
+            },
+            "--max-stack" => match value.parse() {
+                Ok(bytes) => limits.max_stack_bytes = Some(bytes),
+                Err(_) => {
+                    eprintln!("tci: '{}' is not a number of bytes", value);
+                    std::process::exit(1);
+                }
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    if defines.len() != 0 {
+        let mut source = String::new();
+        for (name, value) in defines {
+            source.push_str(&format!("#define {} {}\n", name, value));
+        }
+        files.add_defines(&source).unwrap();
+    }
+
+    for source in sources {
+        files.add_from_fs(source).unwrap();
     }
     mem::drop(args);
 
+    let is_enabled = |err: &Error| match err.severity {
+        Severity::Warning(lint) => !disabled_lints.contains(&lint),
+        Severity::Error => true,
+    };
+
     let program = match compile(&mut files) {
-        Ok(program) => program,
-        Err(errs) => {
-            let config = codespan_reporting::term::Config::default();
-            for err in errs {
-                codespan_reporting::term::emit(
-                    &mut writer.lock(),
-                    &config,
-                    &files,
-                    &err.diagnostic(),
-                )
-                .unwrap();
-            }
+        Ok((program, mut warnings)) => {
+            warnings.retain(is_enabled);
+            emit_err(&warnings, &files, &mut writer.lock());
+            program
+        }
+        Err(mut errs) => {
+            errs.retain(is_enabled);
+            emit_err(&errs, &files, &mut writer.lock());
             std::process::exit(1);
         }
     };
@@ -179,8 +347,37 @@ fn run_from_args(args: Vec<String>) -> ! {
     mem::drop(files);
 
     let mut runtime = interpreter::Runtime::new(program, StringArray::new());
-    match runtime.run(std::io::stdout()) {
-        Ok(code) => std::process::exit(code),
+    runtime.math_warnings = math_warnings;
+    runtime.strict = strict;
+    runtime.limits = limits;
+    for (path, contents) in data_files {
+        runtime.memory.add_file(&path, &contents);
+    }
+    let result = runtime.run(runtime::DefaultIO::new());
+    if runtime.domain_warnings.len() != 0 {
+        let warnings = interpreter::render_domain_warnings(&runtime.domain_warnings, &program);
+        print!("{}", warnings);
+    }
+
+    if runtime.strict_warnings.len() != 0 {
+        let warnings = interpreter::render_strict_warnings(&runtime.strict_warnings, &program);
+        print!("{}", warnings);
+    }
+
+    match result {
+        Ok(code) => {
+            let leaks = runtime.leaks();
+            if leaks.len() != 0 {
+                print!("{}", interpreter::render_leaks(&leaks, &program));
+            }
+
+            let files = runtime.unclosed_files();
+            if files.len() != 0 {
+                print!("{}", interpreter::render_unclosed_files(&files, &program));
+            }
+
+            std::process::exit(code);
+        }
         Err(err) => {
             let print = interpreter::render_err(&err, &runtime.memory.callstack, &program);
             print!("{}", print);
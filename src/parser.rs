@@ -9,6 +9,7 @@ pub type AstDb<'a> = HashMap<u32, &'a [GlobalStmt<'a>]>;
 
 pub struct Parser<'b> {
     pub db: AstDb<'b>,
+    pub errors: Vec<Error>,
 }
 
 pub fn peek_o<'a>(tokens: &[Token<'a>], current: &usize) -> Option<Token<'a>> {
@@ -20,7 +21,7 @@ pub fn peek2_o<'a>(tokens: &[Token<'a>], current: &usize) -> Option<Token<'a>> {
 }
 
 pub fn peek<'a>(tokens: &[Token<'a>], current: &usize) -> Result<Token<'a>, Error> {
-    let map_err = || error!("expected token");
+    let map_err = || error!("expected token").with_code("TCI0015");
     peek_o(tokens, current).ok_or_else(map_err)
 }
 
@@ -45,7 +46,10 @@ pub fn peek_type_or_expr<'a>(tokens: &'a [Token<'a>], current: &usize) -> Result
 
 impl<'b> Parser<'b> {
     pub fn new() -> Self {
-        Self { db: HashMap::new() }
+        Self {
+            db: HashMap::new(),
+            errors: Vec::new(),
+        }
     }
 
     pub fn parse_tokens<'a>(
@@ -53,14 +57,18 @@ impl<'b> Parser<'b> {
         buckets: BucketListRef<'b>,
         token_db: &TokenDb<'a>,
         file: u32,
-    ) -> Result<ASTProgram<'b>, Error> {
+    ) -> Result<ASTProgram<'b>, Vec<Error>> {
         if let Some(stmts) = self.db.get(&file) {
             return Ok(ASTProgram { stmts });
         }
 
         let mut parser = Parser::new();
         let mut parse_result = Vec::new();
-        parser.parse_tokens_rec(buckets, token_db, file, &mut parse_result)?;
+        parser.parse_tokens_rec(buckets, token_db, file, &mut parse_result);
+        if parser.errors.len() != 0 {
+            return Err(parser.errors);
+        }
+
         let stmts = buckets.add_array(parse_result);
         let prev = self.db.insert(file, stmts);
         debug_assert!(prev.is_none());
@@ -73,7 +81,7 @@ impl<'b> Parser<'b> {
         tdb: &TokenDb<'a>,
         file: u32,
         parse_result: &mut Vec<GlobalStmt<'b>>,
-    ) -> Result<(), Error> {
+    ) {
         let tokens = tdb[&file];
         let mut current = 0;
 
@@ -82,14 +90,18 @@ impl<'b> Parser<'b> {
                 break;
             }
 
-            self.parse_global_decls(buckets, tdb, tokens, &mut current, parse_result)?;
+            let start = current;
+            let result = self.parse_global_decls(buckets, tdb, tokens, &mut current, parse_result);
+            if let Err(err) = result {
+                self.errors.push(err);
+                current = start;
+                recover_global_decl(tokens, &mut current);
+            }
 
             while let Some(next) = buckets.next() {
                 buckets = next;
             }
         }
-
-        return Ok(());
     }
 
     #[inline]
@@ -253,7 +265,8 @@ impl<'b> Parser<'b> {
                 ),
                 question_tok.loc,
                 "expected ':' because of matching '?' here"
-            ));
+            )
+            .with_code("TCI0001"));
         }
 
         let if_false = self.parse_bool_or(buckets, tokens, current)?;
@@ -825,7 +838,8 @@ impl<'b> Parser<'b> {
                                 "interpreted as parameter declaration".to_string(),
                                 comma_tok.loc,
                                 format!("interpreted as {:?}", comma_tok)
-                            ));
+                            )
+                            .with_code("TCI0001"));
                         }
                     }
 
@@ -918,6 +932,30 @@ impl<'b> Parser<'b> {
                     loc: tok.loc,
                 })
             }
+            TokenKind::UIntLiteral(i) => {
+                return Ok(Expr {
+                    kind: ExprKind::UIntLiteral(i),
+                    loc: tok.loc,
+                })
+            }
+            TokenKind::LongLiteral(i) => {
+                return Ok(Expr {
+                    kind: ExprKind::LongLiteral(i),
+                    loc: tok.loc,
+                })
+            }
+            TokenKind::ULongLiteral(i) => {
+                return Ok(Expr {
+                    kind: ExprKind::ULongLiteral(i),
+                    loc: tok.loc,
+                })
+            }
+            TokenKind::DoubleLiteral(bits) => {
+                return Ok(Expr {
+                    kind: ExprKind::DoubleLiteral(f64::from_bits(bits)),
+                    loc: tok.loc,
+                })
+            }
             TokenKind::CharLiteral(c) => {
                 return Ok(Expr {
                     kind: ExprKind::CharLiteral(c),
@@ -950,6 +988,9 @@ impl<'b> Parser<'b> {
                 let end_loc = expect_rbrace(tokens, current, tok.loc)?;
 
                 if expr_list.len() == 0 {
+                    // Keep the parentheses, so that an enclosing expression's
+                    // location covers them too
+                    expr.loc = l_from(start_loc, end_loc);
                     return Ok(expr);
                 } else {
                     expr_list.push(expr);
@@ -972,6 +1013,9 @@ impl<'b> Parser<'b> {
                 let end_loc = expect_rparen(tokens, current, tok.loc)?;
 
                 if expr_list.len() == 0 {
+                    // Keep the parentheses, so that an enclosing expression's
+                    // location covers them too
+                    expr.loc = l_from(start_loc, end_loc);
                     return Ok(expr);
                 } else {
                     expr_list.push(expr);
@@ -1012,7 +1056,8 @@ impl<'b> Parser<'b> {
                         return Err(error!(
                             "array dimension value must be at least 1",
                             expr.loc, "invalid array dimension found here"
-                        ));
+                        )
+                        .with_code("TCI0022"));
                     }
 
                     array_dims.push(value as u32);
@@ -1021,7 +1066,8 @@ impl<'b> Parser<'b> {
                     return Err(error!(
                         "TCI currently doesn't accept anything but integer literals as array dimensions",
                         expr.loc, "non-conforming expression found here"
-                    ))
+                    )
+                    .with_code("TCI0022"))
                 }
             }
 
@@ -1241,7 +1287,8 @@ impl<'b> Parser<'b> {
             return Err(error!(
                 "declared a primitive data type",
                 decl_type.loc, "declared a primitive datatype here"
-            ));
+            )
+            .with_code("TCI0023"));
         }
 
         let (mut decls, decl) = self.parse_multi_decl(buckets, tokens, current)?;
@@ -1328,14 +1375,23 @@ impl<'b> Parser<'b> {
                 "this was parsed as a function declaration".to_string(),
                 end_decl_tok.loc,
                 "expected a ';' or '{' here".to_string()
-            ));
+            )
+            .with_code("TCI0001"));
         }
 
         let mut body = Vec::new();
         while peek(tokens, current)?.kind != TokenKind::RBrace {
-            body.push(self.parse_stmt(buckets, tokens, current)?);
+            let start = *current;
+            match self.parse_stmt(buckets, tokens, current) {
+                Ok(stmt) => body.push(stmt),
+                Err(err) => {
+                    self.errors.push(err);
+                    *current = start;
+                    recover_stmt(tokens, current);
+                }
+            }
         }
-        let _tok = pop(tokens, current).unwrap();
+        let body_end = pop(tokens, current).unwrap().loc;
 
         let body = buckets.add_array(body);
         ret_stmt!(GlobalStmt {
@@ -1347,12 +1403,13 @@ impl<'b> Parser<'b> {
                 ident: decl.recv.ident,
                 params,
                 body,
+                body_end,
             },
         });
     }
 
     pub fn parse_block<'a>(
-        &self,
+        &mut self,
         buckets: BucketListRef<'b>,
         tokens: &'a [Token<'a>],
         current: &mut usize,
@@ -1363,7 +1420,15 @@ impl<'b> Parser<'b> {
 
                 let mut stmts = Vec::new();
                 while peek(tokens, current)?.kind != TokenKind::RBrace {
-                    stmts.push(self.parse_stmt(buckets, tokens, current)?);
+                    let start = *current;
+                    match self.parse_stmt(buckets, tokens, current) {
+                        Ok(stmt) => stmts.push(stmt),
+                        Err(err) => {
+                            self.errors.push(err);
+                            *current = start;
+                            recover_stmt(tokens, current);
+                        }
+                    }
                 }
                 let end_loc = pop(tokens, current)?.loc;
 
@@ -1394,7 +1459,7 @@ impl<'b> Parser<'b> {
     }
 
     pub fn parse_stmt<'a>(
-        &self,
+        &mut self,
         buckets: BucketListRef<'b>,
         tokens: &'a [Token<'a>],
         current: &mut usize,
@@ -1628,6 +1693,14 @@ impl<'b> Parser<'b> {
             TokenKind::Char => kind = ASTTypeKind::Char,
             TokenKind::Long => kind = ASTTypeKind::Long,
             TokenKind::Unsigned => kind = ASTTypeKind::Unsigned,
+            TokenKind::Double => kind = ASTTypeKind::Double,
+            TokenKind::Float => {
+                return Err(error!(
+                    "TCI doesn't support float; use double instead",
+                    tok.loc, "float used here"
+                )
+                .with_code("TCI0024"));
+            }
             TokenKind::TypeIdent(ident) => {
                 pop(tokens, current).unwrap();
 
@@ -1695,7 +1768,8 @@ impl<'b> Parser<'b> {
                         return Err(error!(
                             "token not allowed in this context",
                             tok.loc, "illegal token found here"
-                        ));
+                        )
+                        .with_code("TCI0001"));
                     }
                 )*
             }};
@@ -1749,6 +1823,10 @@ impl<'b> Parser<'b> {
                     reject!(Char, Void, Int, Long, Unsigned);
                     break;
                 }
+                ATK::Double => {
+                    reject!(Char, Void, Int, Long, Unsigned, Double);
+                    break;
+                }
 
                 ATK::LongInt => {
                     reject!(Char, Void, Int);
@@ -1846,12 +1924,57 @@ impl<'b> Parser<'b> {
     }
 }
 
+/// Skips the statement starting at `current`, so that parsing can continue
+/// after an error. Stops before a `}` that closes the enclosing block.
+pub fn recover_stmt<'a>(tokens: &[Token<'a>], current: &mut usize) {
+    let (mut braces, mut parens) = (0, 0);
+    while let Some(tok) = peek_o(tokens, current) {
+        match tok.kind {
+            TokenKind::RBrace if braces == 0 => return,
+            TokenKind::LBrace => braces += 1,
+            TokenKind::RBrace => braces -= 1,
+            TokenKind::LParen => parens += 1,
+            TokenKind::RParen if parens > 0 => parens -= 1,
+            _ => {}
+        }
+
+        *current += 1;
+        match tok.kind {
+            TokenKind::RBrace if braces == 0 => return,
+            TokenKind::Semicolon if braces == 0 && parens == 0 => return,
+            _ => {}
+        }
+    }
+}
+
+/// Skips the global declaration starting at `current`, i.e. up to and
+/// including the next top-level `;` or the `}` that closes a function body.
+pub fn recover_global_decl<'a>(tokens: &[Token<'a>], current: &mut usize) {
+    let mut braces = 0;
+    while let Some(tok) = peek_o(tokens, current) {
+        *current += 1;
+        match tok.kind {
+            TokenKind::LBrace => braces += 1,
+            TokenKind::RBrace if braces > 1 => braces -= 1,
+            TokenKind::RBrace => {
+                if let Some(TokenKind::Semicolon) = peek_o(tokens, current).map(|t| t.kind) {
+                    *current += 1;
+                }
+                return;
+            }
+            TokenKind::Semicolon if braces == 0 => return,
+            _ => {}
+        }
+    }
+}
+
 pub fn unexpected_token(parsing_what: &str, tok: &Token) -> Error {
     return error!(
         &format!("unexpected token while parsing {}", parsing_what),
         tok.loc,
         format!("this was interpreted as {:?}", tok)
-    );
+    )
+    .with_code("TCI0001");
 }
 
 pub fn any_ident_o<'a>(tokens: &'a [Token<'a>], current: &mut usize) -> Option<(u32, CodeLoc)> {
@@ -1884,7 +2007,8 @@ pub fn expect_any_ident<'a>(
                 "this was interpreted as {:?} when it should be an identifier",
                 tok
             )
-        ));
+        )
+        .with_code("TCI0001"));
     }
 }
 
@@ -1903,7 +2027,8 @@ pub fn expect_type_ident<'a>(
                 "this was interpreted as {:?} when it should be a type identifier",
                 tok
             )
-        ));
+        )
+        .with_code("TCI0001"));
     }
 }
 
@@ -1922,7 +2047,8 @@ pub fn expect_ident<'a>(
                 "this was interpreted as {:?} when it should be an identifier",
                 tok
             )
-        ));
+        )
+        .with_code("TCI0001"));
     }
 }
 
@@ -1939,7 +2065,8 @@ pub fn expect_rbracket<'a>(
             format!("this was interpreted as {:?} when it should be a ']'", tok),
             lbracket_loc,
             "expected ']' because of matching '[' here"
-        ));
+        )
+        .with_code("TCI0001"));
     }
     return Ok(tok);
 }
@@ -1951,7 +2078,8 @@ pub fn expect_lbrace<'a>(tokens: &[Token<'a>], current: &mut usize) -> Result<()
             "expected '{' token, got something else instead",
             tok.loc,
             format!("this was interpreted as {:?} when it should be a '{{'", tok)
-        ));
+        )
+        .with_code("TCI0001"));
     }
     return Ok(());
 }
@@ -1969,7 +2097,8 @@ pub fn expect_rbrace<'a>(
             format!("this was interpreted as {:?} when it should be a '}}'", tok),
             matching_tok,
             "matching left brace here".to_string()
-        ));
+        )
+        .with_code("TCI0001"));
     }
     return Ok(tok.loc);
 }
@@ -1987,7 +2116,8 @@ pub fn expect_rparen<'a>(
             format!("this was interpreted as {:?} when it should be a ')'", tok),
             matching_tok,
             "matching left paren here".to_string()
-        ));
+        )
+        .with_code("TCI0001"));
     }
     return Ok(tok.loc);
 }
@@ -1999,7 +2129,8 @@ pub fn expect_lparen<'a>(tokens: &'a [Token<'a>], current: &mut usize) -> Result
             "expected '(' token, got something else instead",
             tok.loc,
             format!("this was interpreted as {:?} when it should be a '('", tok)
-        ));
+        )
+        .with_code("TCI0001"));
     }
     return Ok(tok);
 }
@@ -2011,7 +2142,8 @@ pub fn expect_comma<'a>(tokens: &'a [Token<'a>], current: &mut usize) -> Result<
             "expected ',' token, got something else instead",
             tok.loc,
             format!("this was interpreted as {:?} when it should be a ','", tok)
-        ));
+        )
+        .with_code("TCI0001"));
     }
     return Ok(tok);
 }
@@ -2028,7 +2160,8 @@ pub fn expect_semicolon(tok: &Token) -> Result<(), Error> {
             "expected ';' token, got something else instead",
             tok.loc,
             format!("this was interpreted as {:?} when it should be a ';'", tok)
-        ));
+        )
+        .with_code("TCI0001"));
     }
     return Ok(());
 }
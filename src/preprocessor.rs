@@ -2,9 +2,17 @@ use crate::lexer::*;
 use crate::util::*;
 use std::collections::{HashMap, HashSet};
 
-pub fn preprocess_file<'a>(token_db: &TokenDb<'a>, file: u32) -> Result<Vec<Token<'a>>, Error> {
+pub fn preprocess_file<'a>(
+    token_db: &TokenDb<'a>,
+    defines: Option<u32>,
+    file: u32,
+) -> Result<Vec<Token<'a>>, Error> {
     let mut macros = HashMap::new();
     let mut included = HashSet::new();
+    if let Some(defines) = defines {
+        preprocess_file_rec(token_db, &mut included, &mut macros, token_db[&defines])?;
+    }
+
     included.insert(file);
     let tokens = token_db[&file];
 
@@ -20,7 +28,7 @@ pub fn preprocess_file_rec<'a>(
     let mut toks = tokens.iter();
     let mut output = Vec::new();
 
-    let expect = || error!("expected token");
+    let expect = || error!("expected token").with_code("TCI0015");
 
     let mut current = 0;
     while let Some(mut tok) = toks.next() {
@@ -31,7 +39,7 @@ pub fn preprocess_file_rec<'a>(
                 let include_text = token_db[&id];
 
                 if !included.insert(id) {
-                    return Err(error!("detected insert cycle"));
+                    return Err(error!("detected insert cycle").with_code("TCI0018"));
                 }
 
                 let mut include_processed =
@@ -95,7 +103,8 @@ pub fn preprocess_file_rec<'a>(
                 return Err(error!(
                     "used marker macro in code",
                     macro_def.loc, "macro defined here", tok.loc, "used here"
-                ))
+                )
+                .with_code("TCI0021"))
             }
             MacroKind::Value(toks) => {
                 let mut expanded = HashSet::new();
@@ -116,7 +125,8 @@ pub fn preprocess_file_rec<'a>(
                 return Err(error!(
                     "expected a left paren '(' because of function macro invokation",
                     tok.loc, "macro used here", macro_def.loc, "macro defined here"
-                ));
+                )
+                .with_code("TCI0021"));
             }
         }
 
@@ -166,7 +176,8 @@ pub fn preprocess_file_rec<'a>(
                     "macro used here (passed in {} arguments)",
                     actual_params.len()
                 )
-            ));
+            )
+            .with_code("TCI0021"));
         }
 
         let mut params_hash = HashMap::new();
@@ -192,7 +203,7 @@ pub fn preprocess_slice<'a>(
     let mut toks = tokens.iter();
     let mut output = Vec::new();
 
-    let expect = || error!("expected token");
+    let expect = || error!("expected token").with_code("TCI0015");
 
     while let Some(tok) = toks.next() {
         let id = match tok.kind {
@@ -225,7 +236,8 @@ pub fn preprocess_slice<'a>(
                 return Err(error!(
                     "used marker macro in code",
                     macro_def.loc, "macro defined here", tok.loc, "used here"
-                ))
+                )
+                .with_code("TCI0021"))
             }
             MacroKind::Value(toks) => {
                 expanded.insert(id);
@@ -245,7 +257,8 @@ pub fn preprocess_slice<'a>(
                 return Err(error!(
                     "expected a left paren '(' because of function macro invokation",
                     tok.loc, "macro used here", macro_def.loc, "macro defined here"
-                ));
+                )
+                .with_code("TCI0021"));
             }
         }
 
@@ -295,7 +308,8 @@ pub fn preprocess_slice<'a>(
                     "macro used here (passed in {} arguments)",
                     actual_params.len()
                 )
-            ));
+            )
+            .with_code("TCI0021"));
         }
 
         let mut params_hash = HashMap::new();
@@ -333,7 +347,7 @@ pub fn parse_func_macro<'a>(loc: CodeLoc, macro_def: &[Token<'a>]) -> Result<Mac
     let mut current = 0;
     let mut params = Vec::new();
 
-    let expect = || error!("expected token");
+    let expect = || error!("expected token").with_code("TCI0015");
 
     let mut tok = macro_def.get(current).ok_or_else(expect)?;
     debug_assert!(tok.kind == TokenKind::LParen);
@@ -348,7 +362,8 @@ pub fn parse_func_macro<'a>(loc: CodeLoc, macro_def: &[Token<'a>]) -> Result<Mac
                 return Err(error!(
                     "expected a function macro parameter",
                     tok.loc, "this should be an identifier"
-                ))
+                )
+                .with_code("TCI0016"))
             }
         };
 
@@ -363,7 +378,8 @@ pub fn parse_func_macro<'a>(loc: CodeLoc, macro_def: &[Token<'a>]) -> Result<Mac
                 return Err(error!(
                     "expected a ')' to end macro parameters or a comma",
                     tok.loc, "this should be ')' or ','"
-                ))
+                )
+                .with_code("TCI0016"))
             }
         }
 
@@ -392,11 +408,9 @@ pub fn expand_macro<'a>(
         match &tok.kind {
             TokenKind::TypeIdent(id) | TokenKind::Ident(id) => {
                 if let Some(expand) = params.get(id) {
-                    for tok in expand {
-                        let mut token = *tok;
-                        token.loc = loc;
-                        output.push(token);
-                    }
+                    // Arguments keep their own locations, so errors in them point
+                    // at the code that was passed in rather than the whole macro
+                    output.extend_from_slice(expand);
                 } else {
                     let mut token = *tok;
                     token.loc = loc;
@@ -1,25 +1,35 @@
 use crate::buckets::*;
 use crate::filedb::INIT_SYMS;
 use crate::util::*;
+use core::hash::{Hash, Hasher};
 use core::{fmt, mem, str};
 use serde::ser::{Serialize, SerializeSeq, SerializeStruct, Serializer};
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::io;
-use std::io::{stderr, stdout, Stderr, Stdout, Write};
+use std::io::{stderr, stdin, stdout, BufRead, Stderr, StdinLock, Stdout, Write};
 
 #[derive(Debug, serde::Serialize)]
 pub struct IError {
     pub short_name: String,
+    pub code: &'static str,
     pub message: String,
+    pub labels: Vec<(CodeLoc, String)>,
 }
 
 impl IError {
     pub fn new(short_name: &str, message: String) -> Self {
         Self {
             short_name: short_name.to_string(),
+            code: crate::explain::runtime_code(short_name),
             message,
+            labels: Vec::new(),
         }
     }
+
+    pub fn with_label(mut self, loc: CodeLoc, message: &str) -> Self {
+        self.labels.push((loc, message.to_string()));
+        return self;
+    }
 }
 
 macro_rules! error {
@@ -73,6 +83,38 @@ impl Var {
     pub fn invalidate(&mut self) {
         self.meta = self.meta | (1u32 << 31);
     }
+
+    pub fn revalidate(&mut self) {
+        self.meta = self.meta & !(1u32 << 31);
+    }
+}
+
+/// Where a heap variable was allocated, and where it was freed if it has been
+#[derive(Debug, Clone, Copy)]
+pub struct HeapSite {
+    pub alloc: CodeLoc,
+    pub free: Option<CodeLoc>,
+}
+
+/// A file in the virtual filesystem that programs can open with fopen
+#[derive(Debug, Clone)]
+pub struct VfsFile {
+    pub path: String,
+    pub contents: Vec<u8>,
+}
+
+pub const FILE_READ: u8 = 1;
+pub const FILE_WRITE: u8 = 2;
+pub const FILE_APPEND: u8 = 4;
+
+/// A file opened by fopen; its file descriptor is its index plus 3
+#[derive(Debug, Clone, Copy)]
+pub struct OpenFile {
+    pub file: u32,
+    pub pos: u32,
+    pub mode: u8,
+    pub open: CodeLoc,
+    pub close: Option<CodeLoc>,
 }
 
 #[derive(Clone, Copy)]
@@ -116,6 +158,21 @@ impl fmt::Debug for VarPointer {
         );
     }
 }
+
+impl PartialEq for VarPointer {
+    fn eq(&self, other: &Self) -> bool {
+        return unsafe { self.value == other.value };
+    }
+}
+
+impl Eq for VarPointer {}
+
+impl Hash for VarPointer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        unsafe { self.value }.hash(state);
+    }
+}
+
 impl VarPointer {
     pub const BINARY_BIT: u16 = 1u16 << 15;
     pub const STACK_BIT: u16 = 1u16 << 14;
@@ -131,6 +188,10 @@ impl VarPointer {
         unsafe { &mut self.fields }
     }
 
+    pub fn null() -> VarPointer {
+        Self { value: 0 }
+    }
+
     pub fn new_stack(idx: u16, offset: u32) -> VarPointer {
         Self {
             fields: VarPointerFields {
@@ -231,7 +292,9 @@ impl VarPointer {
 }
 
 pub fn invalid_ptr(ptr: VarPointer) -> IError {
-    if ptr.is_stack() {
+    if ptr.var_idx() == 0 {
+        return error!("NullPointer", "the pointer {} is NULL", ptr);
+    } else if ptr.is_stack() {
         return error!("InvalidPointer", "the stack pointer {} is invalid", ptr);
     } else if ptr.is_heap() {
         return error!("InvalidPointer", "the heap pointer {} is invalid", ptr);
@@ -268,8 +331,9 @@ pub fn invalid_offset(var: Var, ptr: VarPointer) -> IError {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct VarBuffer {
-    pub data: Vec<u8>,  // Allocator for variables
-    pub vars: Vec<Var>, // Tracker for variables
+    pub data: Vec<u8>,    // Allocator for variables
+    pub vars: Vec<Var>,   // Tracker for variables
+    pub shadow: Vec<u32>, // Per-byte origin of uninitialized data; 0 if initialized, see SUB_OBJECT_BIT
 }
 
 impl VarBuffer {
@@ -277,6 +341,7 @@ impl VarBuffer {
         Self {
             data: Vec::new(),
             vars: Vec::new(),
+            shadow: Vec::new(),
         }
     }
 
@@ -284,6 +349,7 @@ impl VarBuffer {
         let mut buffer = Self::new();
         buffer.data.extend_from_slice(buffer_ref.data);
         buffer.vars.extend_from_slice(buffer_ref.vars);
+        buffer.shadow.resize(buffer.data.len(), 0);
         return buffer;
     }
 
@@ -301,7 +367,20 @@ impl VarBuffer {
     }
 
     pub fn new_from(data: Vec<u8>, vars: Vec<Var>) -> Self {
-        Self { data, vars }
+        let shadow = vec![0; data.len()];
+        Self { data, vars, shadow }
+    }
+
+    /// Returns the origin of the first uninitialized byte in the range, or 0 if
+    /// every byte in it has been initialized
+    pub fn uninit_origin(&self, ptr: VarPointer, len: u32) -> u32 {
+        let (start, end) = match self.get_var_range(ptr, len) {
+            Ok(range) => range,
+            Err(_) => return 0,
+        };
+
+        let shadow = self.shadow[start..end].iter();
+        return shadow.map(|x| *x).find(|x| is_uninit(*x)).unwrap_or(0);
     }
 
     pub fn get_var_range(&self, ptr: VarPointer, len: u32) -> Result<(usize, usize), IError> {
@@ -357,6 +436,7 @@ impl VarBuffer {
         let idx = self.data.len();
         self.vars.push(Var { idx, len, meta });
         self.data.resize(idx + len as usize, 0);
+        self.shadow.resize(idx + len as usize, 0);
         let var_idx = self.vars.len() as u32; // TODO Check for overflow
         return var_idx;
     }
@@ -496,6 +576,12 @@ pub enum MAKind {
     },
     AllocHeapVar {
         len: u32,
+        loc: CodeLoc,
+        bk: usize,
+    },
+    FreeHeapVar {
+        var: u32,
+        loc: CodeLoc,
         bk: usize,
     },
     CallstackPush {
@@ -521,6 +607,51 @@ pub enum MAKind {
         val: u32,
         bk: usize,
     },
+    ReadStdin {
+        prev: u32,
+        val: u32,
+        bk: usize,
+    },
+    CreateFile {
+        path_start: usize,
+        path_end: usize,
+    },
+    SpliceFile {
+        file: u32,
+        offset: u32,
+        value_start: usize,
+        value_end_overwrite_start: usize,
+        overwrite_end: usize,
+    },
+    OpenFile {
+        fd: u32,
+        file: u32,
+        pos: u32,
+        mode: u8,
+        loc: CodeLoc,
+        prev: Option<OpenFile>, // the closed file whose descriptor was reused
+        bk: usize,
+    },
+    CloseFile {
+        fd: u32,
+        loc: CodeLoc,
+        bk: usize,
+    },
+    SeekFile {
+        fd: u32,
+        prev: u32,
+        val: u32,
+        bk: usize,
+    },
+    SetRand {
+        prev: u32,
+        val: u32,
+        bk: usize,
+    },
+    AtExit {
+        func: VarPointer,
+        bk: usize,
+    },
     WriteStdout {
         start: usize,
         end: usize,
@@ -533,6 +664,57 @@ pub enum MAKind {
         start: usize,
         block_size: u32,
     },
+    SetShadow {
+        buffer: BufferKind,
+        idx: usize,
+        value_start: usize,
+        value_end_overwrite_start: usize,
+        overwrite_end: usize,
+    },
+    SetHeapTypes {
+        idx: usize,
+        value_start: usize,
+        value_end_overwrite_start: usize,
+        overwrite_end: usize,
+    },
+}
+
+/// Shadow values with this bit set don't mark uninitialized bytes; they mark the
+/// bytes of a pointer made from an array inside a struct, and the rest of the
+/// value is an index into `Memory::sub_objects`
+pub const SUB_OBJECT_BIT: u32 = 1 << 31;
+
+pub fn is_uninit(origin: u32) -> bool {
+    return origin != 0 && origin & SUB_OBJECT_BIT == 0;
+}
+
+/// An array inside a struct; pointers made from it have to stay inside it, even
+/// though the rest of the struct is part of the same object
+#[derive(Debug, Clone, Copy)]
+pub struct SubObject {
+    pub start: VarPointer,
+    pub len: u32,
+    pub struct_id: u32,
+    pub member: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BufferKind {
+    Stack,
+    Heap,
+    Binary,
+}
+
+impl BufferKind {
+    pub fn of(ptr: VarPointer) -> Self {
+        if ptr.is_stack() {
+            return BufferKind::Stack;
+        } else if ptr.is_heap() {
+            return BufferKind::Heap;
+        } else {
+            return BufferKind::Binary;
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -573,15 +755,26 @@ impl MemoryAction {
                 return var_start;
             }
             MAKind::AllocStackVar { meta, len, bk } => return bk,
-            MAKind::AllocHeapVar { len, bk } => return bk,
+            MAKind::AllocHeapVar { len, loc, bk } => return bk,
+            MAKind::FreeHeapVar { var, loc, bk } => return bk,
             MAKind::CallstackPush { loc, bk } => return bk,
             MAKind::CallstackPop { frame, bk } => return bk,
             MAKind::SetFp { prev, val, bk } => return bk,
             MAKind::SetFunc { prev, val, bk } => return bk,
             MAKind::Jump { prev, val, bk } => return bk,
+            MAKind::ReadStdin { prev, val, bk } => return bk,
+            MAKind::CreateFile { path_start, .. } => return path_start,
+            MAKind::SpliceFile { value_start, .. } => return value_start,
+            MAKind::OpenFile { bk, .. } => return bk,
+            MAKind::CloseFile { fd, loc, bk } => return bk,
+            MAKind::SeekFile { bk, .. } => return bk,
+            MAKind::SetRand { prev, val, bk } => return bk,
+            MAKind::AtExit { func, bk } => return bk,
             MAKind::WriteStderr { start, end } => return start,
             MAKind::WriteStdout { start, end } => return start,
             MAKind::Unwrite { start, block_size } => return start,
+            MAKind::SetShadow { value_start, .. } => return value_start,
+            MAKind::SetHeapTypes { value_start, .. } => return value_start,
         }
     }
 }
@@ -589,11 +782,22 @@ impl MemoryAction {
 pub struct Memory {
     pub stack: VarBuffer,
     pub heap: VarBuffer,
+    pub heap_sites: Vec<HeapSite>,
     pub binary: VarBuffer,
 
     pub io_events: VecDeque<u32>,
     pub io_buf: VecDeque<u8>,
 
+    pub stdin: Vec<u8>, // every byte of input received so far
+    pub stdin_pos: u32,
+    pub stdin_closed: bool,
+
+    pub files: Vec<VfsFile>,
+    pub open_files: Vec<OpenFile>,
+
+    pub rand_next: u32,          // state of rand(); srand sets it to the seed
+    pub atexit: Vec<VarPointer>, // functions registered with atexit, in order
+
     pub callstack: Vec<CallFrame>,
     pub current_func: u32,
     pub fp: u16,
@@ -603,6 +807,15 @@ pub struct Memory {
     pub history: Vec<MemoryAction>,
     pub history_binary_end: usize,
     pub history_index: usize,
+
+    pub taint: u32,      // origin of uninitialized bytes popped during the current op
+    pub provenance: u32, // sub-object tag of the last value popped during the current op
+    pub sub_objects: Vec<SubObject>,
+    pub sub_object_tags: HashMap<(VarPointer, u32, u32), u32>,
+
+    /// Per heap byte, the pc (plus 1) of the op that last wrote it as a typed
+    /// value, or 0 if it has no effective type; only kept up in strict mode
+    pub heap_types: Vec<u32>,
 }
 
 impl Memory {
@@ -610,11 +823,22 @@ impl Memory {
         Self {
             stack: VarBuffer::new(),
             heap: VarBuffer::new(),
+            heap_sites: Vec::new(),
             binary: VarBuffer::new(),
 
             io_events: VecDeque::new(),
             io_buf: VecDeque::new(),
 
+            stdin: Vec::new(),
+            stdin_pos: 0,
+            stdin_closed: false,
+
+            files: Vec::new(),
+            open_files: Vec::new(),
+
+            rand_next: 1,
+            atexit: Vec::new(),
+
             callstack: Vec::new(),
             current_func: INIT_SYMS.translate["main"],
             fp: 1,
@@ -624,6 +848,12 @@ impl Memory {
             history: Vec::new(),
             history_binary_end: 0,
             history_index: 0,
+
+            taint: 0,
+            provenance: 0,
+            sub_objects: Vec::new(),
+            sub_object_tags: HashMap::new(),
+            heap_types: Vec::new(),
         }
     }
 
@@ -639,11 +869,22 @@ impl Memory {
         Self {
             stack: VarBuffer::new(),
             heap: VarBuffer::new(),
+            heap_sites: Vec::new(),
             binary: VarBuffer::load_from_ref(binary),
 
             io_events: VecDeque::new(),
             io_buf: VecDeque::new(),
 
+            stdin: Vec::new(),
+            stdin_pos: 0,
+            stdin_closed: false,
+
+            files: Vec::new(),
+            open_files: Vec::new(),
+
+            rand_next: 1,
+            atexit: Vec::new(),
+
             callstack: Vec::new(),
             current_func: INIT_SYMS.translate["main"],
             fp: 1,
@@ -653,6 +894,12 @@ impl Memory {
             history: Vec::new(),
             history_binary_end,
             history_index: 0,
+
+            taint: 0,
+            provenance: 0,
+            sub_objects: Vec::new(),
+            sub_object_tags: HashMap::new(),
+            heap_types: Vec::new(),
         }
     }
 
@@ -695,19 +942,19 @@ impl Memory {
             self.pop_stack_var().unwrap();
         }
 
-        self.current_func = frame.name;
         self.push_history(MAKind::SetFunc {
             prev: self.current_func,
             val: frame.name,
             bk,
         });
+        self.current_func = frame.name;
 
-        self.fp = frame.fp;
         self.push_history(MAKind::SetFp {
             prev: self.fp,
             val: frame.fp,
             bk,
         });
+        self.fp = frame.fp;
 
         self.push_history(MAKind::Jump {
             prev: self.pc,
@@ -724,19 +971,19 @@ impl Memory {
             .push(CallFrame::new(self.current_func, loc, self.fp, self.pc));
         self.push_history(MAKind::CallstackPush { loc, bk });
 
-        self.current_func = func_name;
         self.push_history(MAKind::SetFunc {
             prev: self.current_func,
             val: func_name,
             bk,
         });
+        self.current_func = func_name;
 
-        self.fp = self.stack_length() + 1;
         self.push_history(MAKind::SetFp {
             prev: self.fp,
             val: self.stack_length() + 1,
             bk,
         });
+        self.fp = self.stack_length() + 1;
 
         self.push_history(MAKind::Jump {
             prev: self.pc,
@@ -783,6 +1030,40 @@ impl Memory {
         self.pc = pc;
     }
 
+    /// Whether memory is the same as it was at `history_index`, when the stack
+    /// was `stack_len` bytes long. This only checks that every action since then
+    /// pushed or popped temporaries above that point, so changes that were later
+    /// undone still count as changes.
+    pub fn unchanged_since(&self, history_index: usize, stack_len: usize) -> bool {
+        if history_index > self.history_index || self.stack.data.len() != stack_len {
+            return false;
+        }
+
+        let mut len = stack_len;
+        for action in &self.history[history_index..self.history_index] {
+            match action.kind {
+                MAKind::PushStack {
+                    value_start,
+                    value_end,
+                } => len += value_end - value_start,
+                MAKind::PopStack {
+                    value_start,
+                    value_end,
+                } => {
+                    let popped = value_end - value_start;
+                    if len - stack_len < popped {
+                        return false;
+                    }
+                    len -= popped;
+                }
+                MAKind::Jump { .. } => {}
+                _ => return false,
+            }
+        }
+
+        return true;
+    }
+
     pub fn increment_pc(&mut self) {
         let bk = self.historical_data.len();
 
@@ -794,6 +1075,137 @@ impl Memory {
         self.pc += 1;
     }
 
+    pub fn stdin_unread(&self) -> &[u8] {
+        return &self.stdin[(self.stdin_pos as usize)..];
+    }
+
+    pub fn consume_stdin(&mut self, len: u32) {
+        let bk = self.historical_data.len();
+
+        self.push_history(MAKind::ReadStdin {
+            prev: self.stdin_pos,
+            val: self.stdin_pos + len,
+            bk,
+        });
+        self.stdin_pos += len;
+    }
+
+    /// Adds a file to the virtual filesystem before the program starts, replacing
+    /// any file that already has the same path
+    pub fn add_file(&mut self, path: &str, contents: &[u8]) {
+        let contents = contents.to_vec();
+        match self.files.iter_mut().find(|file| file.path == path) {
+            Some(file) => file.contents = contents,
+            None => self.files.push(VfsFile {
+                path: path.to_string(),
+                contents,
+            }),
+        }
+    }
+
+    pub fn find_file(&self, path: &str) -> Option<u32> {
+        let file = self.files.iter().position(|file| file.path == path)?;
+        return Some(file as u32);
+    }
+
+    pub fn create_file(&mut self, path: &str) -> u32 {
+        let path_start = self.historical_data.len();
+        self.historical_data.extend_from_slice(path.as_bytes());
+        let path_end = self.historical_data.len();
+        self.push_history(MAKind::CreateFile {
+            path_start,
+            path_end,
+        });
+
+        self.files.push(VfsFile {
+            path: path.to_string(),
+            contents: Vec::new(),
+        });
+        return self.files.len() as u32 - 1;
+    }
+
+    /// Replaces the `len` bytes of a file starting at `offset` with `bytes`
+    pub fn splice_file(&mut self, file: u32, offset: u32, len: u32, bytes: &[u8]) {
+        let (start, end) = (offset as usize, (offset + len) as usize);
+        let value_start = self.historical_data.len();
+        self.historical_data.extend_from_slice(bytes);
+        let value_end_overwrite_start = self.historical_data.len();
+        let contents = &self.files[file as usize].contents;
+        self.historical_data
+            .extend_from_slice(&contents[start..end]);
+        let overwrite_end = self.historical_data.len();
+
+        self.push_history(MAKind::SpliceFile {
+            file,
+            offset,
+            value_start,
+            value_end_overwrite_start,
+            overwrite_end,
+        });
+
+        let contents = &mut self.files[file as usize].contents;
+        contents.splice(start..end, bytes.iter().cloned());
+    }
+
+    /// Opens `file` as `fd`, which is either the next unused descriptor or the
+    /// descriptor of a file that's been closed
+    pub fn open_file(&mut self, fd: u32, file: u32, pos: u32, mode: u8, loc: CodeLoc) {
+        let bk = self.historical_data.len();
+        let prev = self.open_files.get(fd as usize - 3).copied();
+        self.push_history(MAKind::OpenFile {
+            fd,
+            file,
+            pos,
+            mode,
+            loc,
+            prev,
+            bk,
+        });
+
+        let open = OpenFile {
+            file,
+            pos,
+            mode,
+            open: loc,
+            close: None,
+        };
+        match prev {
+            Some(_) => self.open_files[fd as usize - 3] = open,
+            None => self.open_files.push(open),
+        }
+    }
+
+    pub fn close_file(&mut self, fd: u32, loc: CodeLoc) {
+        let bk = self.historical_data.len();
+        self.push_history(MAKind::CloseFile { fd, loc, bk });
+        self.open_files[fd as usize - 3].close = Some(loc);
+    }
+
+    pub fn seek_file(&mut self, fd: u32, pos: u32) {
+        let bk = self.historical_data.len();
+        let prev = self.open_files[fd as usize - 3].pos;
+        self.push_history(MAKind::SeekFile {
+            fd,
+            prev,
+            val: pos,
+            bk,
+        });
+        self.open_files[fd as usize - 3].pos = pos;
+    }
+
+    pub fn set_rand(&mut self, val: u32) {
+        let bk = self.historical_data.len();
+        let prev = self.rand_next;
+        self.push_history(MAKind::SetRand { prev, val, bk });
+        self.rand_next = val;
+    }
+
+    pub fn add_atexit(&mut self, func: VarPointer) {
+        let bk = self.historical_data.len();
+        self.push_history(MAKind::AtExit { func, bk });
+        self.atexit.push(func);
+    }
+
     pub fn push_history(&mut self, kind: MAKind) {
         if self.history.len() != self.history_index {
             let last_history_entry = self.history[self.history_index];
@@ -806,6 +1218,152 @@ impl Memory {
 
         self.history.push(MemoryAction { kind, tag: self.pc });
         self.history_index += 1;
+        self.sync_shadow();
+    }
+
+    fn buffer_mut(&mut self, buffer: BufferKind) -> &mut VarBuffer {
+        match buffer {
+            BufferKind::Stack => return &mut self.stack,
+            BufferKind::Heap => return &mut self.heap,
+            BufferKind::Binary => return &mut self.binary,
+        }
+    }
+
+    /// Keeps the shadow state and effective types the same length as the data
+    /// they describe
+    fn sync_shadow(&mut self) {
+        self.stack.shadow.resize(self.stack.data.len(), 0);
+        self.heap.shadow.resize(self.heap.data.len(), 0);
+        self.heap_types.resize(self.heap.data.len(), 0);
+    }
+
+    /// Sets the initialization state of the bytes starting at `idx`, recording
+    /// the change if anything was different
+    pub fn set_shadow(&mut self, buffer: BufferKind, idx: usize, origins: &[u32]) {
+        let end = idx + origins.len();
+        let target = match buffer {
+            BufferKind::Stack => &mut self.stack,
+            BufferKind::Heap => &mut self.heap,
+            BufferKind::Binary => &mut self.binary,
+        };
+
+        let shadow = &mut target.shadow[idx..end];
+        if shadow == origins {
+            return;
+        }
+
+        let value_start = self.historical_data.len();
+        for origin in origins {
+            self.historical_data
+                .extend_from_slice(&origin.to_ne_bytes());
+        }
+
+        let value_end_overwrite_start = self.historical_data.len();
+        for origin in shadow.iter() {
+            self.historical_data
+                .extend_from_slice(&origin.to_ne_bytes());
+        }
+
+        shadow.copy_from_slice(origins);
+        let overwrite_end = self.historical_data.len();
+        self.push_history(MAKind::SetShadow {
+            buffer,
+            idx,
+            value_start,
+            value_end_overwrite_start,
+            overwrite_end,
+        });
+    }
+
+    fn restore_shadow(&mut self, buffer: BufferKind, idx: usize, start: usize, end: usize) {
+        let target = match buffer {
+            BufferKind::Stack => &mut self.stack,
+            BufferKind::Heap => &mut self.heap,
+            BufferKind::Binary => &mut self.binary,
+        };
+
+        let origins = self.historical_data[start..end].chunks(4);
+        for (to, from) in target.shadow[idx..].iter_mut().zip(origins) {
+            *to = u32::from_ne_bytes([from[0], from[1], from[2], from[3]]);
+        }
+    }
+
+    /// Sets the effective types of the heap bytes starting at `idx`, recording
+    /// the change if anything was different
+    pub fn set_heap_types(&mut self, idx: usize, types: &[u32]) {
+        let end = idx + types.len();
+        let current = &mut self.heap_types[idx..end];
+        if current == types {
+            return;
+        }
+
+        let value_start = self.historical_data.len();
+        for t in types {
+            self.historical_data.extend_from_slice(&t.to_ne_bytes());
+        }
+
+        let value_end_overwrite_start = self.historical_data.len();
+        for t in current.iter() {
+            self.historical_data.extend_from_slice(&t.to_ne_bytes());
+        }
+
+        current.copy_from_slice(types);
+        let overwrite_end = self.historical_data.len();
+        self.push_history(MAKind::SetHeapTypes {
+            idx,
+            value_start,
+            value_end_overwrite_start,
+            overwrite_end,
+        });
+    }
+
+    fn restore_heap_types(&mut self, idx: usize, start: usize, end: usize) {
+        let types = self.historical_data[start..end].chunks(4);
+        for (to, from) in self.heap_types[idx..].iter_mut().zip(types) {
+            *to = u32::from_ne_bytes([from[0], from[1], from[2], from[3]]);
+        }
+    }
+
+    /// Marks everything on the stack from `idx` up as initialized, returning what
+    /// it was before; called before the stack shrinks so history can restore it
+    fn take_stack_shadow(&mut self, idx: usize) -> Vec<u32> {
+        let shadow = self.stack.shadow[idx..].to_vec();
+        self.set_shadow(BufferKind::Stack, idx, &vec![0; shadow.len()]);
+        return shadow;
+    }
+
+    fn set_ptr_shadow(&mut self, ptr: VarPointer, origins: &[u32]) -> Result<(), IError> {
+        let buffer = BufferKind::of(ptr);
+        let len = origins.len() as u32;
+        let (start, end) = self.buffer_mut(buffer).get_var_range(ptr, len)?;
+        self.set_shadow(buffer, start, origins);
+        return Ok(());
+    }
+
+    /// Copies `len` bytes from `src` to `dest` along with whether they've been
+    /// initialized; the two ranges are allowed to overlap
+    pub fn copy(&mut self, dest: VarPointer, src: VarPointer, len: u32) -> Result<(), IError> {
+        self.check_freed(src)?;
+
+        let buffer = self.buffer_mut(BufferKind::of(src));
+        let (start, end) = buffer.get_var_range(src, len)?;
+        let bytes = buffer.data[start..end].to_vec();
+        let shadow = buffer.shadow[start..end].to_vec();
+
+        self.write_bytes(dest, &bytes)?;
+        return self.set_ptr_shadow(dest, &shadow);
+    }
+
+    /// Returns the origin of the first uninitialized byte in the range, or 0 if
+    /// every byte in it has been initialized
+    pub fn uninit_origin(&self, ptr: VarPointer, len: u32) -> u32 {
+        if ptr.is_stack() {
+            return self.stack.uninit_origin(ptr, len);
+        } else if ptr.is_heap() {
+            return self.heap.uninit_origin(ptr, len);
+        } else {
+            return self.binary.uninit_origin(ptr, len);
+        }
     }
 
     /// Returns a pointer to a variable matching criteria set by F, only looking
@@ -825,6 +1383,8 @@ impl Memory {
 
     #[inline]
     pub fn get_var_slice(&self, ptr: VarPointer) -> Result<&[u8], IError> {
+        self.check_freed(ptr)?;
+
         let buffer = if ptr.is_stack() {
             &self.stack
         } else if ptr.is_heap() {
@@ -851,6 +1411,8 @@ impl Memory {
 
     #[inline]
     pub fn get_var_slice_mut(&mut self, ptr: VarPointer) -> Result<&mut [u8], IError> {
+        self.check_freed(ptr)?;
+
         let buffer = if ptr.is_stack() {
             &mut self.stack
         } else if ptr.is_heap() {
@@ -879,6 +1441,8 @@ impl Memory {
 
     #[inline]
     pub fn get_slice(&self, ptr: VarPointer, len: u32) -> Result<&[u8], IError> {
+        self.check_freed(ptr)?;
+
         let buffer;
         if ptr.is_stack() {
             buffer = &self.stack;
@@ -910,6 +1474,8 @@ impl Memory {
 
     #[inline]
     pub fn get_var<T: Copy>(&self, ptr: VarPointer) -> Result<T, IError> {
+        self.check_freed(ptr)?;
+
         if ptr.is_stack() {
             return self.stack.get_var(ptr);
         } else if ptr.is_heap() {
@@ -921,6 +1487,8 @@ impl Memory {
 
     #[inline]
     pub fn set<T: Copy>(&mut self, ptr: VarPointer, value: T) -> Result<(), IError> {
+        self.check_freed(ptr)?;
+
         let value_start = self.historical_data.len();
         self.historical_data
             .extend_from_slice(any_as_u8_slice(&value));
@@ -945,6 +1513,7 @@ impl Memory {
             overwrite_end,
         });
 
+        self.set_ptr_shadow(ptr, &vec![0; mem::size_of::<T>()])?;
         return Ok(());
     }
 
@@ -954,18 +1523,91 @@ impl Memory {
         let ptr = VarPointer::new_stack(self.stack.add_var(len, meta) as u16, 0);
         let bk = self.historical_data.len();
         self.push_history(MAKind::AllocStackVar { meta, len, bk });
+        self.set_ptr_shadow(ptr, &vec![self.pc + 1; len as usize])
+            .unwrap();
         return ptr;
     }
 
     #[inline]
-    pub fn add_heap_var(&mut self, len: u32) -> VarPointer {
+    pub fn add_heap_var(&mut self, len: u32, loc: CodeLoc) -> VarPointer {
         let ptr = VarPointer::new_heap(self.heap.add_var(len, 0), 0);
+        self.heap_sites.push(HeapSite {
+            alloc: loc,
+            free: None,
+        });
         let bk = self.historical_data.len();
-        self.push_history(MAKind::AllocHeapVar { len, bk });
+        self.push_history(MAKind::AllocHeapVar { len, loc, bk });
+        self.set_ptr_shadow(ptr, &vec![self.pc + 1; len as usize])
+            .unwrap();
         return ptr;
     }
 
+    pub fn free_heap_var(&mut self, ptr: VarPointer, loc: CodeLoc) -> Result<(), IError> {
+        if !ptr.is_heap() {
+            return err!(
+                "InvalidPointer",
+                "called free on a pointer that was not from the heap"
+            );
+        }
+
+        if ptr.var_idx() == 0 || ptr.var_idx() > self.heap.vars.len() {
+            return Err(invalid_ptr(ptr));
+        }
+
+        let var = ptr.var_idx() - 1;
+        let site = self.heap_sites[var];
+        if let Some(free_loc) = site.free {
+            return Err(
+                error!("DoubleFree", "the pointer {} was already freed", ptr)
+                    .with_label(site.alloc, "allocated here")
+                    .with_label(free_loc, "first freed here"),
+            );
+        }
+
+        if ptr.offset() != 0 {
+            return err!(
+                "InvalidPointer",
+                "called free on a pointer that was {} bytes off of its allocation",
+                ptr.offset()
+            );
+        }
+
+        self.heap.vars[var].invalidate();
+        self.heap_sites[var].free = Some(loc);
+        let bk = self.historical_data.len();
+        let var = var as u32;
+        self.push_history(MAKind::FreeHeapVar { var, loc, bk });
+        return Ok(());
+    }
+
+    /// Errors if the pointer points into heap memory that's already been freed
+    pub fn check_freed(&self, ptr: VarPointer) -> Result<(), IError> {
+        if !ptr.is_heap() || ptr.var_idx() == 0 {
+            return Ok(());
+        }
+
+        let var = match self.heap.vars.get(ptr.var_idx() - 1) {
+            Some(var) => var,
+            None => return Ok(()),
+        };
+
+        if var.is_valid() {
+            return Ok(());
+        }
+
+        let site = self.heap_sites[ptr.var_idx() - 1];
+        let mut err = error!("UseAfterFree", "the pointer {} points to freed memory", ptr)
+            .with_label(site.alloc, "allocated here");
+        if let Some(free_loc) = site.free {
+            err = err.with_label(free_loc, "freed here");
+        }
+
+        return Err(err);
+    }
+
     pub fn write_bytes(&mut self, ptr: VarPointer, bytes: &[u8]) -> Result<(), IError> {
+        self.check_freed(ptr)?;
+
         let value_start = self.historical_data.len();
         self.historical_data.extend_from_slice(bytes);
 
@@ -991,6 +1633,7 @@ impl Memory {
             overwrite_end,
         });
 
+        self.set_ptr_shadow(ptr, &vec![0; bytes.len()])?;
         return Ok(());
     }
 
@@ -1010,6 +1653,7 @@ impl Memory {
             .extend_from_slice(&self.stack.data[var.idx..]);
         let stack_end = self.historical_data.len();
 
+        self.take_stack_shadow(var.idx);
         self.stack.data.resize(var.idx, 0);
         self.push_history(MAKind::PopStackVar {
             meta: var.meta,
@@ -1032,6 +1676,7 @@ impl Memory {
             .extend_from_slice(&self.stack.data[var.idx..]);
         let stack_end = self.historical_data.len();
 
+        let shadow = self.take_stack_shadow(var.idx);
         self.stack.data.resize(var.idx + var.len as usize, 0);
         self.push_history(MAKind::PopStackVar {
             meta: var.meta,
@@ -1045,9 +1690,70 @@ impl Memory {
             value_end: var_end_stack_start,
         });
 
+        self.set_shadow(BufferKind::Stack, var.idx, &shadow[..var.len as usize]);
+
         return Ok(());
     }
 
+    /// Marks the top bytes of the stack with the taint of the values they were
+    /// computed from, or failing that with the sub-object they point into
+    fn taint_stack_top(&mut self, len: usize) {
+        let origin = if self.taint != 0 {
+            self.taint
+        } else {
+            self.provenance
+        };
+
+        if origin != 0 {
+            let idx = self.stack.data.len() - len;
+            self.set_shadow(BufferKind::Stack, idx, &vec![origin; len]);
+        }
+    }
+
+    /// The shadow value that marks a pointer as made from the array member
+    /// `member` of a struct, starting at `start` and `len` bytes long
+    pub fn sub_object_tag(
+        &mut self,
+        start: VarPointer,
+        len: u32,
+        struct_id: u32,
+        member: u32,
+    ) -> u32 {
+        let sub_objects = &mut self.sub_objects;
+        let key = (start, struct_id, member);
+        let idx = *self.sub_object_tags.entry(key).or_insert_with(|| {
+            sub_objects.push(SubObject {
+                start,
+                len,
+                struct_id,
+                member,
+            });
+            return sub_objects.len() as u32 - 1;
+        });
+
+        return idx | SUB_OBJECT_BIT;
+    }
+
+    pub fn sub_object(&self, tag: u32) -> Option<SubObject> {
+        if tag & SUB_OBJECT_BIT == 0 {
+            return None;
+        }
+
+        return self
+            .sub_objects
+            .get((tag & !SUB_OBJECT_BIT) as usize)
+            .map(|s| *s);
+    }
+
+    /// Returns the array member of a struct that the stack variable `var`
+    /// holds a pointer into, if it was made from one
+    pub fn var_sub_object(&self, var: u16) -> Option<SubObject> {
+        let var = self.stack.vars.get(var as usize - 1)?;
+        let shadow = &self.stack.shadow[var.idx..var.upper()];
+        let tag = shadow.iter().find(|x| **x & SUB_OBJECT_BIT != 0)?;
+        return self.sub_object(*tag);
+    }
+
     #[inline]
     pub fn push_stack<T: Copy>(&mut self, value: T) {
         let from_bytes = any_as_u8_slice(&value);
@@ -1060,6 +1766,8 @@ impl Memory {
             value_start,
             value_end,
         });
+
+        self.taint_stack_top(from_bytes.len());
     }
 
     pub fn push_stack_bytes(&mut self, from_bytes: &[u8]) {
@@ -1072,9 +1780,13 @@ impl Memory {
             value_start,
             value_end,
         });
+
+        self.taint_stack_top(from_bytes.len());
     }
 
     pub fn pop_stack_bytes_into(&mut self, ptr: VarPointer, len: u32) -> Result<(), IError> {
+        self.check_freed(ptr)?;
+
         if self.stack.data.len() < len as usize {
             return err!(
                 "StackTooShort",
@@ -1129,6 +1841,7 @@ impl Memory {
             overwrite_end,
         });
 
+        let shadow = self.take_stack_shadow(self.stack.data.len() - len as usize);
         self.stack
             .data
             .resize(self.stack.data.len() - len as usize, 0);
@@ -1137,10 +1850,13 @@ impl Memory {
             value_end,
         });
 
+        self.set_ptr_shadow(ptr, &shadow)?;
         return Ok(());
     }
 
     pub fn push_stack_bytes_from(&mut self, ptr: VarPointer, len: u32) -> Result<(), IError> {
+        self.check_freed(ptr)?;
+
         let break_idx = if let Some(var) = self.stack.vars.last() {
             var.upper()
         } else {
@@ -1176,6 +1892,11 @@ impl Memory {
             value_end,
         });
 
+        let buffer = self.buffer_mut(BufferKind::of(ptr));
+        let (start, end) = buffer.get_var_range(ptr, len)?;
+        let shadow = buffer.shadow[start..end].to_vec();
+        let idx = self.stack.data.len() - len as usize;
+        self.set_shadow(BufferKind::Stack, idx, &shadow);
         return Ok(());
     }
 
@@ -1206,6 +1927,7 @@ impl Memory {
         self.historical_data.extend_from_slice(from_bytes);
         let value_end = self.historical_data.len();
 
+        self.take_stack_shadow(lower);
         self.stack.data.resize(lower, 0);
         self.push_history(MAKind::PopStack {
             value_start,
@@ -1245,6 +1967,7 @@ impl Memory {
             .extend_from_slice(&self.stack.data[keep_start..]);
         let push_value_end = self.historical_data.len();
 
+        let shadow = self.take_stack_shadow(pop_start);
         let mutate_slice = &mut self.stack.data[pop_start..];
         for i in 0..(keep as usize) {
             mutate_slice[i] = mutate_slice[i + pop as usize];
@@ -1260,6 +1983,7 @@ impl Memory {
             value_end: push_value_end,
         });
 
+        self.set_shadow(BufferKind::Stack, pop_start, &shadow[pop as usize..]);
         return Ok(());
     }
 
@@ -1301,6 +2025,8 @@ impl Memory {
             value_end,
         });
 
+        let shadow = self.stack.shadow[dup_start..dup_end].to_vec();
+        self.set_shadow(BufferKind::Stack, dup_end, &shadow);
         return Ok(());
     }
 
@@ -1334,6 +2060,20 @@ impl Memory {
 
         let mut out = unsafe { mem::MaybeUninit::uninit().assume_init() };
         unsafe { any_as_u8_slice_mut(&mut out).copy_from_slice(from_bytes) };
+        let shadow = self.take_stack_shadow(lower);
+        if self.taint == 0 {
+            self.taint = shadow
+                .iter()
+                .map(|x| *x)
+                .find(|x| is_uninit(*x))
+                .unwrap_or(0);
+        }
+
+        // Only the last value popped keeps its tag, so that in something like
+        // `ptr + idx` the pointer decides where the result points
+        let tagged = shadow.into_iter().find(|x| *x & SUB_OBJECT_BIT != 0);
+        self.provenance = tagged.unwrap_or(0);
+
         self.stack.data.resize(lower, 0);
         self.push_history(MAKind::PopStack {
             value_start,
@@ -1351,7 +2091,42 @@ impl Memory {
         MemoryStderr { memory: self }
     }
 
+    /// Steps forward through one action in the history, along with the
+    /// changes to initialization state and effective types recorded right
+    /// after it, so that tracking those doesn't make stepping any finer
     pub fn next(&mut self) -> bool {
+        if !self.redo_action() {
+            return false;
+        }
+
+        while self.history_index < self.history.len() {
+            match self.history[self.history_index].kind {
+                MAKind::SetShadow { .. } | MAKind::SetHeapTypes { .. } => self.redo_action(),
+                _ => break,
+            };
+        }
+
+        return true;
+    }
+
+    /// Steps back through one action in the history; the inverse of `next`
+    pub fn prev(&mut self) -> bool {
+        if self.history_index == 0 {
+            return false;
+        }
+
+        while self.history_index > 0 {
+            match self.history[self.history_index - 1].kind {
+                MAKind::SetShadow { .. } | MAKind::SetHeapTypes { .. } => self.undo_action(),
+                _ => break,
+            };
+        }
+
+        self.undo_action();
+        return true;
+    }
+
+    fn redo_action(&mut self) -> bool {
         if self.history_index == self.history.len() {
             return false;
         }
@@ -1401,8 +2176,16 @@ impl Memory {
                 let var = self.stack.vars.pop().unwrap();
                 self.stack.data.resize(var.idx, 0);
             }
-            MAKind::AllocHeapVar { len, bk } => {
+            MAKind::AllocHeapVar { len, loc, bk } => {
                 self.heap.add_var(len, 0);
+                self.heap_sites.push(HeapSite {
+                    alloc: loc,
+                    free: None,
+                });
+            }
+            MAKind::FreeHeapVar { var, loc, bk } => {
+                self.heap.vars[var as usize].invalidate();
+                self.heap_sites[var as usize].free = Some(loc);
             }
             MAKind::AllocStackVar { meta, len, bk } => {
                 self.stack.add_var(len, meta);
@@ -1423,6 +2206,64 @@ impl Memory {
             MAKind::Jump { prev, val, bk } => {
                 self.pc = val;
             }
+            MAKind::ReadStdin { prev, val, bk } => {
+                self.stdin_pos = val;
+            }
+            MAKind::CreateFile {
+                path_start,
+                path_end,
+            } => {
+                let path = &self.historical_data[path_start..path_end];
+                self.files.push(VfsFile {
+                    path: String::from_utf8_lossy(path).to_string(),
+                    contents: Vec::new(),
+                });
+            }
+            MAKind::SpliceFile {
+                file,
+                offset,
+                value_start,
+                value_end_overwrite_start: mid,
+                overwrite_end,
+            } => {
+                let (start, old_len) = (offset as usize, overwrite_end - mid);
+                let contents = &mut self.files[file as usize].contents;
+                let value = &self.historical_data[value_start..mid];
+                contents.splice(start..(start + old_len), value.iter().cloned());
+            }
+            MAKind::OpenFile {
+                fd,
+                file,
+                pos,
+                mode,
+                loc,
+                prev,
+                bk,
+            } => {
+                let open = OpenFile {
+                    file,
+                    pos,
+                    mode,
+                    open: loc,
+                    close: None,
+                };
+                match prev {
+                    Some(_) => self.open_files[fd as usize - 3] = open,
+                    None => self.open_files.push(open),
+                }
+            }
+            MAKind::CloseFile { fd, loc, bk } => {
+                self.open_files[fd as usize - 3].close = Some(loc);
+            }
+            MAKind::SeekFile { fd, prev, val, bk } => {
+                self.open_files[fd as usize - 3].pos = val;
+            }
+            MAKind::SetRand { prev, val, bk } => {
+                self.rand_next = val;
+            }
+            MAKind::AtExit { func, bk } => {
+                self.atexit.push(func);
+            }
             MAKind::WriteStdout { start, end } => {
                 self.io_buf.extend(&self.historical_data[start..end]);
                 let block_size = EVENT_STDOUT_WRITE | ((end - start) as u32);
@@ -1436,13 +2277,31 @@ impl Memory {
             MAKind::Unwrite { start, block_size } => {
                 self.io_events.push_back(block_size);
             }
+            MAKind::SetShadow {
+                buffer,
+                idx,
+                value_start,
+                value_end_overwrite_start: mid,
+                overwrite_end,
+            } => {
+                self.restore_shadow(buffer, idx, value_start, mid);
+            }
+            MAKind::SetHeapTypes {
+                idx,
+                value_start,
+                value_end_overwrite_start: mid,
+                overwrite_end,
+            } => {
+                self.restore_heap_types(idx, value_start, mid);
+            }
         }
 
+        self.sync_shadow();
         self.history_index += 1;
         return true;
     }
 
-    pub fn prev(&mut self) -> bool {
+    fn undo_action(&mut self) -> bool {
         if self.history_index == 0 {
             return false;
         }
@@ -1496,9 +2355,14 @@ impl Memory {
                 let vars = &mut self.stack.vars;
                 vars.push(Var { idx, len, meta });
             }
-            MAKind::AllocHeapVar { len, bk } => {
+            MAKind::AllocHeapVar { len, loc, bk } => {
                 let var = self.heap.vars.pop().unwrap();
                 self.heap.data.resize(var.idx, 0);
+                self.heap_sites.pop().unwrap();
+            }
+            MAKind::FreeHeapVar { var, loc, bk } => {
+                self.heap.vars[var as usize].revalidate();
+                self.heap_sites[var as usize].free = None;
             }
             MAKind::AllocStackVar { meta, len, bk } => {
                 let var = self.stack.vars.pop().unwrap();
@@ -1519,6 +2383,42 @@ impl Memory {
             MAKind::Jump { prev, val, bk } => {
                 self.pc = prev;
             }
+            MAKind::ReadStdin { prev, val, bk } => {
+                self.stdin_pos = prev;
+            }
+            MAKind::CreateFile { .. } => {
+                self.files.pop();
+            }
+            MAKind::SpliceFile {
+                file,
+                offset,
+                value_start,
+                value_end_overwrite_start: mid,
+                overwrite_end,
+            } => {
+                let (start, new_len) = (offset as usize, mid - value_start);
+                let contents = &mut self.files[file as usize].contents;
+                let value = &self.historical_data[mid..overwrite_end];
+                contents.splice(start..(start + new_len), value.iter().cloned());
+            }
+            MAKind::OpenFile { fd, prev, .. } => match prev {
+                Some(prev) => self.open_files[fd as usize - 3] = prev,
+                None => {
+                    self.open_files.pop();
+                }
+            },
+            MAKind::CloseFile { fd, loc, bk } => {
+                self.open_files[fd as usize - 3].close = None;
+            }
+            MAKind::SeekFile { fd, prev, val, bk } => {
+                self.open_files[fd as usize - 3].pos = prev;
+            }
+            MAKind::SetRand { prev, val, bk } => {
+                self.rand_next = prev;
+            }
+            MAKind::AtExit { func, bk } => {
+                self.atexit.pop();
+            }
             MAKind::WriteStdout { start, end } => {
                 let block_size = (end - start) as u32;
                 self.io_events.push_back(block_size);
@@ -1535,8 +2435,26 @@ impl Memory {
                 self.io_buf.extend(&self.historical_data[start..end]);
                 self.io_events.push_back(block_enum | block_size);
             }
+            MAKind::SetShadow {
+                buffer,
+                idx,
+                value_start,
+                value_end_overwrite_start: mid,
+                overwrite_end,
+            } => {
+                self.restore_shadow(buffer, idx, mid, overwrite_end);
+            }
+            MAKind::SetHeapTypes {
+                idx,
+                value_start,
+                value_end_overwrite_start: mid,
+                overwrite_end,
+            } => {
+                self.restore_heap_types(idx, mid, overwrite_end);
+            }
         }
 
+        self.sync_shadow();
         self.history_index -= 1;
         return true;
     }
@@ -1572,9 +2490,9 @@ impl<'a> io::Write for MemoryStderr<'a> {
                 .push_back(EVENT_STDERR_WRITE | (block_size as u32));
 
             {
-                let start = self.memory.historical_data.len();
+                let hist_start = self.memory.historical_data.len();
                 self.memory.historical_data.extend(&buf[start..end]);
-                let end = self.memory.historical_data.len();
+                let (start, end) = (hist_start, self.memory.historical_data.len());
                 self.memory.push_history(MAKind::WriteStderr { start, end });
             }
 
@@ -1668,7 +2586,7 @@ fn test_memory_walker() {
     memory.prev();
     memory.prev();
 
-    let stack_expected_2 = VarBuffer::new_from(
+    let mut stack_expected_2 = VarBuffer::new_from(
         vec![
             0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 12, 0, 0, 0, 4,
         ],
@@ -1679,6 +2597,7 @@ fn test_memory_walker() {
         }],
     );
 
+    stack_expected_2.shadow[..12].copy_from_slice(&[1; 12]);
     assert_eq!(memory.stack, stack_expected_2);
 
     memory.next();
@@ -1687,17 +2606,45 @@ fn test_memory_walker() {
     assert_eq!(memory.stack, stack_expected);
 }
 
+#[test]
+fn test_memory_walker_free() {
+    let mut memory = Memory::new();
+    let ptr = memory.add_heap_var(8, l(0, 1, 0));
+    memory
+        .free_heap_var(ptr, l(2, 3, 0))
+        .expect("should not fail");
+
+    assert_eq!(
+        memory.get_var::<u64>(ptr).unwrap_err().short_name,
+        "UseAfterFree"
+    );
+    let err = memory.free_heap_var(ptr, l(4, 5, 0)).unwrap_err();
+    assert_eq!(err.short_name, "DoubleFree");
+
+    memory.prev();
+    assert!(memory.heap.vars[0].is_valid());
+    assert_eq!(memory.heap_sites[0].free, None);
+    memory.get_var::<u64>(ptr).expect("should not fail");
+
+    memory.next();
+    assert!(!memory.heap.vars[0].is_valid());
+    assert_eq!(memory.heap_sites[0].free, Some(l(2, 3, 0)));
+}
+
 pub trait RuntimeIO {
+    type In: BufRead;
     type Out: Write;
     type Log: Write;
     type Err: Write;
 
+    fn input(&mut self) -> &mut Self::In;
     fn out(&mut self) -> &mut Self::Out;
     fn log(&mut self) -> &mut Self::Log;
     fn err(&mut self) -> &mut Self::Err;
 }
 
 pub struct InMemoryIO {
+    pub input: io::Cursor<Vec<u8>>,
     pub out: StringWriter,
     pub log: StringWriter,
     pub err: StringWriter,
@@ -1706,6 +2653,7 @@ pub struct InMemoryIO {
 impl InMemoryIO {
     pub fn new() -> Self {
         Self {
+            input: io::Cursor::new(Vec::new()),
             out: StringWriter::new(),
             log: StringWriter::new(),
             err: StringWriter::new(),
@@ -1714,10 +2662,14 @@ impl InMemoryIO {
 }
 
 impl RuntimeIO for &mut InMemoryIO {
+    type In = io::Cursor<Vec<u8>>;
     type Out = StringWriter;
     type Log = StringWriter;
     type Err = StringWriter;
 
+    fn input(&mut self) -> &mut io::Cursor<Vec<u8>> {
+        return &mut self.input;
+    }
     fn out(&mut self) -> &mut StringWriter {
         return &mut self.out;
     }
@@ -1730,10 +2682,14 @@ impl RuntimeIO for &mut InMemoryIO {
 }
 
 impl RuntimeIO for InMemoryIO {
+    type In = io::Cursor<Vec<u8>>;
     type Out = StringWriter;
     type Log = StringWriter;
     type Err = StringWriter;
 
+    fn input(&mut self) -> &mut io::Cursor<Vec<u8>> {
+        return &mut self.input;
+    }
     fn out(&mut self) -> &mut StringWriter {
         return &mut self.out;
     }
@@ -1746,18 +2702,21 @@ impl RuntimeIO for InMemoryIO {
 }
 
 pub struct DefaultIO {
+    pub input: StdinLock<'static>,
     pub out: Stdout,
     pub log: StringWriter,
     pub err: Stderr,
 }
 
 pub struct TestIO {
+    pub input: io::Empty,
     pub writer: RecordingWriter<Stderr>,
 }
 
 impl DefaultIO {
     pub fn new() -> Self {
         Self {
+            input: stdin().lock(),
             out: stdout(),
             log: StringWriter::new(),
             err: stderr(),
@@ -1766,10 +2725,14 @@ impl DefaultIO {
 }
 
 impl RuntimeIO for DefaultIO {
+    type In = StdinLock<'static>;
     type Out = Stdout;
     type Log = StringWriter;
     type Err = Stderr;
 
+    fn input(&mut self) -> &mut StdinLock<'static> {
+        return &mut self.input;
+    }
     fn out(&mut self) -> &mut Stdout {
         return &mut self.out;
     }
@@ -1782,10 +2745,14 @@ impl RuntimeIO for DefaultIO {
 }
 
 impl RuntimeIO for &mut DefaultIO {
+    type In = StdinLock<'static>;
     type Out = Stdout;
     type Log = StringWriter;
     type Err = Stderr;
 
+    fn input(&mut self) -> &mut StdinLock<'static> {
+        return &mut self.input;
+    }
     fn out(&mut self) -> &mut Stdout {
         return &mut self.out;
     }
@@ -1800,16 +2767,21 @@ impl RuntimeIO for &mut DefaultIO {
 impl TestIO {
     pub fn new() -> Self {
         Self {
+            input: io::empty(),
             writer: RecordingWriter::new(stderr()),
         }
     }
 }
 
 impl RuntimeIO for &mut TestIO {
+    type In = io::Empty;
     type Out = RecordingWriter<Stderr>;
     type Log = RecordingWriter<Stderr>;
     type Err = RecordingWriter<Stderr>;
 
+    fn input(&mut self) -> &mut Self::In {
+        return &mut self.input;
+    }
     fn out(&mut self) -> &mut Self::Out {
         return &mut self.writer;
     }
@@ -1822,10 +2794,14 @@ impl RuntimeIO for &mut TestIO {
 }
 
 impl RuntimeIO for TestIO {
+    type In = io::Empty;
     type Out = RecordingWriter<Stderr>;
     type Log = RecordingWriter<Stderr>;
     type Err = RecordingWriter<Stderr>;
 
+    fn input(&mut self) -> &mut Self::In {
+        return &mut self.input;
+    }
     fn out(&mut self) -> &mut Self::Out {
         return &mut self.writer;
     }
@@ -1838,10 +2814,14 @@ impl RuntimeIO for TestIO {
 }
 
 impl RuntimeIO for Void {
+    type In = Void;
     type Out = Void;
     type Log = Void;
     type Err = Void;
 
+    fn input(&mut self) -> &mut Void {
+        return self;
+    }
     fn out(&mut self) -> &mut Void {
         return self;
     }
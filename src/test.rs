@@ -1,16 +1,20 @@
 use crate::filedb::*;
 use crate::interpreter::{render_err, Runtime};
+use crate::runtime::*;
 use crate::util::*;
 use crate::{compile, emit_err};
 use core::mem;
 use std::fs::read_to_string;
+use std::io::BufRead;
+use std::time::Duration;
+use std::{io, thread};
 
 fn test_file_should_succeed(files: &mut FileDb, output_file: &str) {
     let config = codespan_reporting::term::Config::default();
     let mut writer = StringWriter::new();
 
     let program = match compile(files) {
-        Ok(program) => program,
+        Ok((program, _warnings)) => program,
         Err(errs) => {
             emit_err(&errs, &files, &mut writer);
             println!("{}", writer.into_string());
@@ -24,10 +28,11 @@ fn test_file_should_succeed(files: &mut FileDb, output_file: &str) {
 
     let mut runtime = Runtime::new(program, StringArray::new());
 
-    let code = match runtime.run(&mut writer) {
+    let mut io = InMemoryIO::new();
+    let code = match runtime.run(&mut io) {
         Ok(c) => c,
         Err(err) => {
-            println!("{}", writer.into_string());
+            println!("{}", io.out.into_string());
             println!("");
 
             println!("pc: {}", runtime.memory.pc);
@@ -46,7 +51,7 @@ fn test_file_should_succeed(files: &mut FileDb, output_file: &str) {
         panic!();
     }
 
-    let output = writer.into_string();
+    let output = io.out.into_string();
     println!("{}", output);
     match read_to_string(output_file) {
         Ok(expected) => {
@@ -63,7 +68,22 @@ fn test_file_should_succeed(files: &mut FileDb, output_file: &str) {
     }
 }
 
-fn test_file_compile_should_fail(filename: &str) {
+/// Compiles a single file that should compile without errors, and makes a
+/// runtime for it
+fn compile_runtime(path: &str) -> Runtime {
+    let mut files = FileDb::new(true);
+    files.add_from_fs(path).unwrap();
+
+    let program = match compile(&mut files) {
+        Ok((program, _warnings)) => program,
+        Err(errs) => panic!("{:?}", errs),
+    };
+    mem::drop(files);
+
+    return Runtime::new(program, StringArray::new());
+}
+
+fn test_file_compile_should_fail(filename: &str, expected_errs: usize) {
     let config = codespan_reporting::term::Config::default();
     let mut files = FileDb::new(true);
     let mut writer = StringWriter::new();
@@ -74,11 +94,41 @@ fn test_file_compile_should_fail(filename: &str) {
         Err(errs) => {
             emit_err(&errs, &files, &mut writer);
             println!("{}", writer.to_string());
+            let errs = errs.iter().filter(|err| !err.is_warning());
+            assert_eq!(errs.count(), expected_errs);
         }
         _ => panic!("should have failed"),
     }
 }
 
+fn test_file_compile_warnings(filename: &str, expected_lints: &[Lint]) {
+    let mut files = FileDb::new(true);
+    let mut writer = StringWriter::new();
+
+    files.add_from_fs(filename).unwrap();
+
+    match compile(&mut files) {
+        Ok((_program, warnings)) => {
+            emit_err(&warnings, &files, &mut writer);
+            println!("{}", writer.to_string());
+
+            let lints: Vec<Lint> = warnings
+                .iter()
+                .map(|warning| match warning.severity {
+                    Severity::Warning(lint) => lint,
+                    Severity::Error => panic!("warnings should not contain errors"),
+                })
+                .collect();
+            assert_eq!(lints, expected_lints);
+        }
+        Err(errs) => {
+            emit_err(&errs, &files, &mut writer);
+            println!("{}", writer.into_string());
+            panic!();
+        }
+    }
+}
+
 fn test_file_runtime_should_fail(filename: &str, expected_err: &str) {
     let config = codespan_reporting::term::Config::default();
     let mut files = FileDb::new(true);
@@ -87,7 +137,7 @@ fn test_file_runtime_should_fail(filename: &str, expected_err: &str) {
     files.add_from_fs(filename).unwrap();
 
     let program = match compile(&mut files) {
-        Ok(program) => program,
+        Ok((program, _warnings)) => program,
         Err(errs) => {
             emit_err(&errs, &files, &mut writer);
             println!("{}", writer.into_string());
@@ -97,7 +147,7 @@ fn test_file_runtime_should_fail(filename: &str, expected_err: &str) {
     mem::drop(files);
 
     let mut runtime = Runtime::new(program, StringArray::new());
-    let code = match runtime.run(&mut writer) {
+    let code = match runtime.run(InMemoryIO::new()) {
         Ok(code) => {
             for (idx, op) in program.ops.iter().enumerate() {
                 println!("op {}: {:?}", idx, op);
@@ -145,6 +195,17 @@ macro_rules! gen_test_should_succeed {
 
 }
 
+macro_rules! gen_test_compile_should_fail {
+    ( $( ($ident:ident, $expr:expr ) ),* ) => {
+        $(
+            #[test]
+            fn $ident() {
+                test_file_compile_should_fail(concat!("test/", stringify!($ident), ".c"), $expr);
+            }
+        )*
+    };
+}
+
 macro_rules! gen_test_runtime_should_fail {
     ( $( ($ident:ident, $expr:expr ) ),* ) => {
         $(
@@ -164,7 +225,453 @@ gen_test_should_succeed!(
     control_flow,
     macros,
     binary_search,
+    strings,
+    stdlib,
+    ctype_limits,
+    math,
+    reopen_files,
+    arithmetic,
+    pointers,
     ("dyn_array_ptr/", dyn_array_ptr, main)
 );
 
-gen_test_runtime_should_fail!((stack_locals, "InvalidPointer"));
+#[test]
+fn include_paths() {
+    let mut files = FileDb::new(true);
+    files.add_include_dir("test/include_paths/lib");
+    files
+        .add_defines("#define FACTOR 3\n#define OFFSET 7\n")
+        .unwrap();
+    files.add_from_fs("test/include_paths/main.c").unwrap();
+    test_file_should_succeed(&mut files, "test/include_paths/main.c.out");
+}
+
+gen_test_runtime_should_fail!(
+    (stack_locals, "InvalidPointer"),
+    (use_after_free, "UseAfterFree"),
+    (double_free, "DoubleFree"),
+    (uninit_heap, "UninitializedMemory"),
+    (buffer_overflow, "BufferOverflow"),
+    (overlapping_copy, "OverlappingCopy"),
+    (scanf_pointer, "InvalidFormatString"),
+    (scanf_long_value, "InvalidFormatString"),
+    (closed_file, "InvalidStream"),
+    (bad_comparator, "InvalidFunctionPointer"),
+    (infinite_loop, "InfiniteLoop"),
+    (stack_overflow, "StackOverflow"),
+    (int_overflow, "IntegerOverflow"),
+    (divide_by_zero, "DivideByZero"),
+    (pointer_out_of_bounds, "PointerOutOfBounds"),
+    (unrelated_pointers, "UnrelatedPointers"),
+    (compare_freed, "UseAfterFree"),
+    (member_out_of_bounds, "MemberOutOfBounds"),
+    (member_pointer_out_of_bounds, "MemberOutOfBounds"),
+    (member_strcpy_overflow, "MemberOutOfBounds"),
+    (null_deref, "NullPointer")
+);
+
+gen_test_compile_should_fail!((syntax_errors, 3), (type_errors, 2), (missing_return, 2));
+
+#[test]
+fn warnings() {
+    use Lint::*;
+    test_file_compile_warnings(
+        "test/warnings.c",
+        &[
+            UnusedParameter,
+            UnusedVariable,
+            UnreachableCode,
+            Narrowing,
+            AssignInCondition,
+            SignCompare,
+            EmptyBody,
+        ],
+    );
+}
+
+#[test]
+fn uninit() {
+    use Lint::*;
+    test_file_compile_warnings(
+        "test/uninit.c",
+        &[Uninitialized, Uninitialized, Uninitialized, Uninitialized],
+    );
+}
+
+#[test]
+fn format() {
+    test_file_compile_warnings("test/format.c", &[Lint::Format; 5]);
+}
+
+#[test]
+fn suggestions() {
+    let mut files = FileDb::new(true);
+    files.add_from_fs("test/suggestions.c").unwrap();
+
+    let errs = match compile(&mut files) {
+        Ok(_) => panic!("should have failed"),
+        Err(errs) => errs,
+    };
+
+    let notes: Vec<&str> = errs
+        .iter()
+        .flat_map(|err| &err.notes)
+        .map(|note| &**note)
+        .collect();
+    assert_eq!(
+        notes,
+        [
+            "did you mean `length`?",
+            "did you mean `count`?",
+            "did you mean `printf`?",
+            "`malloc` is declared in <stdlib.h>; try adding `#include <stdlib.h>`",
+        ]
+    );
+}
+
+#[test]
+fn suggestion_ties() {
+    let names = ["count", "mount", "amount"];
+    assert_eq!(
+        closest_match("fount", names.iter().map(|s| *s)),
+        Some("count")
+    );
+    assert_eq!(
+        closest_match("fount", names.iter().rev().map(|s| *s)),
+        Some("count")
+    );
+}
+
+#[test]
+fn leaks() {
+    let mut runtime = compile_runtime("test/leaks.c");
+    assert_eq!(runtime.run(InMemoryIO::new()).unwrap(), 0);
+
+    let leaks: Vec<(u32, u64)> = runtime
+        .leaks()
+        .iter()
+        .map(|leak| (leak.blocks, leak.bytes))
+        .collect();
+    assert_eq!(leaks, [(1, 32), (3, 12)]);
+}
+
+#[test]
+fn stdin() {
+    let input = read_to_string("test/stdin.c.in").unwrap();
+    let mut runtime = compile_runtime("test/stdin.c");
+    let mut io = InMemoryIO::new();
+    io.input = io::Cursor::new(input.clone().into_bytes());
+    assert_eq!(runtime.run(&mut io).unwrap(), 0);
+    let expected = read_to_string("test/stdin.c.out").unwrap();
+    assert_eq!(io.out.into_string(), expected.replace("\r\n", "\n"));
+
+    // Run op by op, the program waits for input to be pushed, and stepping
+    // backwards un-reads it
+    let mut runtime = Runtime::new(runtime.program, StringArray::new());
+    assert_eq!(runtime.run_op_count(u32::MAX).unwrap(), None);
+    assert!(runtime.waiting_for_input);
+    assert_eq!(runtime.memory.stdin_pos, 0);
+
+    runtime.push_stdin(&input);
+    runtime.push_stdin("");
+    assert_eq!(runtime.run_op_count(u32::MAX).unwrap(), Some(0));
+    assert_eq!(runtime.memory.stdin_pos as usize, input.len());
+
+    while runtime.memory.prev() {}
+    assert_eq!(runtime.memory.stdin_pos, 0);
+}
+
+/// Input that takes a while to arrive, like someone typing it
+struct SlowIO {
+    input: io::Cursor<Vec<u8>>,
+    out: StringWriter,
+}
+
+impl io::Read for SlowIO {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        return io::Read::read(&mut self.input, buf);
+    }
+}
+
+impl io::BufRead for SlowIO {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        thread::sleep(Duration::from_millis(100));
+        return self.input.fill_buf();
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.input.consume(amt);
+    }
+}
+
+impl RuntimeIO for &mut SlowIO {
+    type In = SlowIO;
+    type Out = StringWriter;
+    type Log = StringWriter;
+    type Err = StringWriter;
+
+    fn input(&mut self) -> &mut SlowIO {
+        return self;
+    }
+    fn out(&mut self) -> &mut StringWriter {
+        return &mut self.out;
+    }
+    fn log(&mut self) -> &mut StringWriter {
+        return &mut self.out;
+    }
+    fn err(&mut self) -> &mut StringWriter {
+        return &mut self.out;
+    }
+}
+
+#[test]
+fn slow_input() {
+    // Waiting for input doesn't count against the time limit
+    let mut runtime = compile_runtime("test/slow_input.c");
+    runtime.limits.max_time = Some(Duration::from_millis(150));
+    let mut io = SlowIO {
+        input: io::Cursor::new(b"1\n2\n3\n".to_vec()),
+        out: StringWriter::new(),
+    };
+
+    assert_eq!(runtime.run(&mut io).unwrap(), 0);
+    let expected = "value: value: value: \ntotal: 6000\n";
+    assert_eq!(io.out.into_string(), expected);
+}
+
+#[test]
+fn files() {
+    let scores = read_to_string("test/files/scores.txt").unwrap();
+    let mut runtime = compile_runtime("test/files.c");
+    runtime
+        .memory
+        .add_file("test/files/scores.txt", scores.as_bytes());
+
+    let mut io = InMemoryIO::new();
+    assert_eq!(runtime.run(&mut io).unwrap(), 0);
+    let expected = read_to_string("test/files.c.out").unwrap();
+    assert_eq!(io.out.into_string(), expected.replace("\r\n", "\n"));
+    assert_eq!(io.err.into_string(), "to stderr\n");
+    assert_eq!(runtime.unclosed_files().len(), 0);
+
+    let out = runtime.memory.find_file("out.txt").unwrap();
+    let contents = &runtime.memory.files[out as usize].contents;
+    assert_eq!(contents, b"alpha: 42\n!?\nappended\n");
+
+    // Stepping all the way back undoes every file operation
+    while runtime.memory.prev() {}
+    assert_eq!(runtime.memory.files.len(), 1);
+    assert_eq!(runtime.memory.files[0].contents, scores.as_bytes());
+    assert_eq!(runtime.memory.open_files.len(), 0);
+}
+
+#[test]
+fn unclosed_files() {
+    let mut runtime = compile_runtime("test/unclosed_files.c");
+    assert_eq!(runtime.run(InMemoryIO::new()).unwrap(), 0);
+
+    let unclosed = runtime.unclosed_files();
+    let paths: Vec<&str> = unclosed.iter().map(|file| file.path.as_str()).collect();
+    assert_eq!(paths, ["first.txt", "third.txt"]);
+}
+
+#[test]
+fn failed_assert() {
+    let mut runtime = compile_runtime("test/failed_assert.c");
+    let err = runtime.run(InMemoryIO::new()).unwrap_err();
+    assert_eq!(err.short_name, "AssertionFailed");
+    assert_eq!(err.message, "assertion `total - count > grade` failed");
+
+    // Locals and parameters both get their values shown
+    let labels: Vec<&str> = err.labels.iter().map(|(_, msg)| &**msg).collect();
+    assert_eq!(
+        labels,
+        [
+            "this was false",
+            "`total` is 10",
+            "`count` is 0",
+            "`grade` is 66 ('B')"
+        ]
+    );
+}
+
+#[test]
+fn math_domain() {
+    let mut runtime = compile_runtime("test/math_domain.c");
+    runtime.math_warnings = true;
+    let mut io = InMemoryIO::new();
+    assert_eq!(runtime.run(&mut io).unwrap(), 0);
+    assert_eq!(io.out.to_string(), "nan\nnan\nnan\n-inf\n");
+
+    // Each call is only warned about once, however many times it runs
+    let warnings = &runtime.domain_warnings;
+    let messages: Vec<&str> = warnings.iter().map(|w| &*w.message).collect();
+    assert_eq!(
+        messages,
+        [
+            "sqrt was given `area - 10`, which is -6",
+            "log was given `area - 4`, which is 0"
+        ]
+    );
+
+    let mut runtime = Runtime::new(runtime.program, StringArray::new());
+    runtime.run(InMemoryIO::new()).unwrap();
+    assert_eq!(runtime.domain_warnings.len(), 0);
+}
+
+#[test]
+fn remainder_overflow() {
+    let mut runtime = compile_runtime("test/remainder_overflow.c");
+    let err = runtime.run(InMemoryIO::new()).unwrap_err();
+    assert_eq!(err.short_name, "IntegerOverflow");
+    assert_eq!(
+        err.message,
+        "`lowest % divisor` overflowed; computing it divides INT_MIN by -1, which overflows"
+    );
+}
+
+#[test]
+fn increment_overflow() {
+    let mut runtime = compile_runtime("test/increment_overflow.c");
+    let err = runtime.run(InMemoryIO::new()).unwrap_err();
+    assert_eq!(err.short_name, "IntegerOverflow");
+    assert_eq!(
+        err.message,
+        "`visits++` overflowed; it was 9223372036854775807 + 1, which is 9223372036854775808 \
+         and outside the range of `long`"
+    );
+}
+
+#[test]
+fn strict_types() {
+    let mut runtime = compile_runtime("test/strict_types.c");
+    runtime.strict = true;
+    let mut io = InMemoryIO::new();
+    assert_eq!(runtime.run(&mut io).unwrap(), 0);
+    assert_eq!(io.out.to_string(), "0.000000\n2\n0\n1.500000\n");
+
+    // Reading a `long` as `unsigned long` or `char` is allowed, and memcpy
+    // forgets the type of whatever it overwrites
+    let warnings = &runtime.strict_warnings;
+    let kinds: Vec<_> = warnings.iter().map(|w| w.kind).collect();
+    assert_eq!(kinds, ["IncompatibleRead", "MisalignedAccess"]);
+    assert!(warnings[0].message.contains("reads a `double`"));
+    assert!(warnings[0].message.contains("last written as a `long`"));
+    assert!(warnings[1].message.contains("an `int` at offset 2"));
+
+    // Effective types are part of the history, so stepping back undoes them
+    let types = runtime.memory.heap_types.clone();
+    assert!(types.iter().any(|&t| t != 0));
+    while runtime.memory.prev() {}
+    assert!(runtime.memory.heap_types.iter().all(|&t| t == 0));
+    while runtime.memory.next() {}
+    assert_eq!(runtime.memory.heap_types, types);
+
+    let mut runtime = Runtime::new(runtime.program, StringArray::new());
+    runtime.run(InMemoryIO::new()).unwrap();
+    assert_eq!(runtime.strict_warnings.len(), 0);
+}
+
+#[test]
+fn step_limit() {
+    let mut runtime = compile_runtime("test/runaway_loop.c");
+    runtime.limits.max_steps = Some(10_000);
+    let err = runtime.run(InMemoryIO::new()).unwrap_err();
+    assert_eq!(err.short_name, "LimitExceeded");
+    assert_eq!(runtime.steps, 10_001);
+
+    // The error points at wherever in the loop the program was stopped
+    assert_eq!(runtime.memory.callstack.len(), 2);
+}
+
+#[test]
+fn callback_limits() {
+    // The comparator never returns, so qsort never does either
+    let mut runtime = compile_runtime("test/runaway_comparator.c");
+    runtime.limits.max_steps = Some(1_000);
+    let err = runtime.run(InMemoryIO::new()).unwrap_err();
+    assert_eq!(err.short_name, "LimitExceeded");
+    assert_eq!(runtime.steps, 1_001);
+
+    let mut runtime = Runtime::new(runtime.program, StringArray::new());
+    runtime.limits.max_time = Some(Duration::from_millis(10));
+    let err = runtime.run(InMemoryIO::new()).unwrap_err();
+    assert_eq!(err.short_name, "LimitExceeded");
+}
+
+#[test]
+fn step_back_over_loop() {
+    // Stepping back over the jump at the end of a loop and then running it
+    // again doesn't make the loop look like it never changes
+    let program = compile_runtime("test/control_flow.c").program;
+    for count in 1..400 {
+        let mut runtime = Runtime::new(program, StringArray::new());
+        if runtime.run_op_count(count).unwrap().is_some() {
+            break;
+        }
+        if runtime.memory.history_index == 0 {
+            continue;
+        }
+
+        let memory = &mut runtime.memory;
+        let tag = memory.current_tag();
+        while memory.history_index > 0 && memory.current_tag() == tag {
+            memory.prev();
+        }
+
+        runtime.run_op().unwrap();
+        assert_eq!(runtime.run_op_count(u32::MAX).unwrap(), Some(0));
+    }
+}
+
+#[test]
+fn recursion_trace() {
+    let mut runtime = compile_runtime("test/stack_overflow.c");
+    runtime.limits.max_depth = Some(100);
+    let err = runtime.run(InMemoryIO::new()).unwrap_err();
+    assert_eq!(err.short_name, "StackOverflow");
+
+    let trace = render_err(&err, &runtime.memory.callstack, &runtime.program);
+    println!("{}", trace);
+    assert!(trace.contains("`sum_to` called itself 97 more times"));
+    assert!(trace.contains("first call: sum_to(n = 10)"));
+    assert!(trace.contains("last call: sum_to(n = -89)"));
+}
+
+#[test]
+fn explain_catalog() {
+    use crate::explain::*;
+
+    for (idx, explanation) in CATALOG.iter().enumerate() {
+        let duplicate = CATALOG[..idx]
+            .iter()
+            .find(|prev| prev.code == explanation.code);
+        assert!(duplicate.is_none(), "{} is duplicated", explanation.code);
+    }
+
+    for lint in Lint::ALL.iter() {
+        assert!(
+            lookup(lint.code()).is_some(),
+            "{} has no explanation",
+            lint.name()
+        );
+    }
+
+    for name in &["InvalidPointer", "MissingNullTerminator", "StackIsEmpty"] {
+        assert!(lookup(runtime_code(name)).is_some());
+    }
+
+    let coded = [
+        "WriteFailed",
+        "IncompatibleRead",
+        "MisalignedAccess",
+        "MemoryLeak",
+        "UnclosedFile",
+        "WaitingForInput",
+    ];
+    for name in coded.iter() {
+        assert_ne!(runtime_code(name), INTERNAL_ERROR, "{} has no code", name);
+    }
+
+    assert_eq!(lookup("tci0001").unwrap().code, "TCI0001");
+}
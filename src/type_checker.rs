@@ -1,7 +1,10 @@
 use crate::ast::*;
 use crate::buckets::*;
 use crate::filedb::*;
+use crate::flow;
 use crate::util::*;
+use core::cell::RefCell;
+use core::mem;
 use std::collections::{HashMap, HashSet};
 
 pub fn unify<'a>(
@@ -24,7 +27,8 @@ pub fn unify<'a>(
             format!("this has type {}", l.expr_type.display(env.files)),
             r.loc,
             format!("this has type {}", r.expr_type.display(env.files))
-        ));
+        )
+        .with_code("TCI0012"));
     }
 
     if l_rank > r_rank {
@@ -80,19 +84,33 @@ pub static OVERLOADS: LazyStatic<Overloads> = lazy_static!(overloads, Overloads,
         }};
     }
 
-    add_unified_bin_op!(Add, I32, AddU32, I32);
+    // Signed arithmetic gets its own ops, since overflowing it is an error
+    // rather than wrapping around
+    add_unified_bin_op!(Add, I32, AddI32, I32);
     add_unified_bin_op!(Add, U32, AddU32, U32);
+    add_unified_bin_op!(Add, I64, AddI64, I64);
     add_unified_bin_op!(Add, U64, AddU64, U64);
-    add_unified_bin_op!(Add, I64, AddU64, I64);
 
     add_unified_bin_op!(Sub, I32, SubI32, I32);
+    add_unified_bin_op!(Sub, U32, SubU32, U32);
+    add_unified_bin_op!(Sub, I64, SubI64, I64);
     add_unified_bin_op!(Sub, U64, SubU64, U64);
 
+    add_unified_bin_op!(Mul, I32, MulI32, I32);
+    add_unified_bin_op!(Mul, U32, MulU32, U32);
+    add_unified_bin_op!(Mul, I64, MulI64, I64);
     add_unified_bin_op!(Mul, U64, MulU64, U64);
 
     add_unified_bin_op!(Div, I32, DivI32, I32);
+    add_unified_bin_op!(Div, U32, DivU32, U32);
+    add_unified_bin_op!(Div, I64, DivI64, I64);
     add_unified_bin_op!(Div, U64, DivU64, U64);
 
+    add_unified_bin_op!(Mod, I32, ModI32, I32);
+    add_unified_bin_op!(Mod, U32, ModU32, U32);
+    add_unified_bin_op!(Mod, I64, ModI64, I64);
+    add_unified_bin_op!(Mod, U64, ModU64, U64);
+
     add_unified_bin_op!(Lt, I32, LtI32, I8);
     add_unified_bin_op!(Lt, U64, LtU64, I8);
 
@@ -101,9 +119,27 @@ pub static OVERLOADS: LazyStatic<Overloads> = lazy_static!(overloads, Overloads,
 
     add_unified_bin_op!(Gt, I32, GtI32, I8);
 
+    add_unified_bin_op!(Add, F64, AddF64, F64);
+    add_unified_bin_op!(Sub, F64, SubF64, F64);
+    add_unified_bin_op!(Mul, F64, MulF64, F64);
+    add_unified_bin_op!(Div, F64, DivF64, F64);
+    add_unified_bin_op!(Lt, F64, LtF64, I8);
+    add_unified_bin_op!(Geq, F64, GeqF64, I8);
+    add_unified_bin_op!(Gt, F64, GtF64, I8);
+    add_unified_bin_op!(Eq, F64, EqF64, I8);
+
     add_unified_bin_op!(Eq, I32, Eq32, I8);
-    add_unified_bin_op!(Eq, VoidPointer, Eq64, I8);
-    add_unified_bin_op!(Eq, Pointer, Eq64, I8);
+
+    add_unified_bin_op!(Eq, VoidPointer, EqPtr, I8);
+    add_unified_bin_op!(Eq, Pointer, EqPtr, I8);
+    add_unified_bin_op!(Lt, VoidPointer, LtPtr, I8);
+    add_unified_bin_op!(Lt, Pointer, LtPtr, I8);
+    add_unified_bin_op!(Gt, VoidPointer, GtPtr, I8);
+    add_unified_bin_op!(Gt, Pointer, GtPtr, I8);
+    add_unified_bin_op!(Leq, VoidPointer, LeqPtr, I8);
+    add_unified_bin_op!(Leq, Pointer, LeqPtr, I8);
+    add_unified_bin_op!(Geq, VoidPointer, GeqPtr, I8);
+    add_unified_bin_op!(Geq, Pointer, GeqPtr, I8);
 
     macro_rules! add_un_op_ol {
         ($op:ident, $operand:ident, $func:expr) => {{
@@ -112,15 +148,31 @@ pub static OVERLOADS: LazyStatic<Overloads> = lazy_static!(overloads, Overloads,
     }
 
     add_un_op_ol!(Neg, I32, |buckets, op, loc| {
-        let result_type = TCType::new(TCTypeKind::I32, 0);
+        return TCExpr {
+            loc,
+            kind: TCExprKind::NegI32(buckets.add(op)),
+            expr_type: TCType::new(TCTypeKind::I32, 0),
+        };
+    });
+
+    add_un_op_ol!(Neg, I64, |buckets, op, loc| {
+        return TCExpr {
+            loc,
+            kind: TCExprKind::NegI64(buckets.add(op)),
+            expr_type: TCType::new(TCTypeKind::I64, 0),
+        };
+    });
+
+    add_un_op_ol!(Neg, F64, |buckets, op, loc| {
+        let result_type = TCType::new(TCTypeKind::F64, 0);
         let negative_one = TCExpr {
             loc,
-            kind: TCExprKind::I32Literal(-1),
+            kind: TCExprKind::F64Literal(-1.0),
             expr_type: result_type,
         };
         return TCExpr {
             loc,
-            kind: TCExprKind::MulI32(buckets.add(negative_one), buckets.add(op)),
+            kind: TCExprKind::MulF64(buckets.add(negative_one), buckets.add(op)),
             expr_type: result_type,
         };
     });
@@ -160,7 +212,7 @@ pub static OVERLOADS: LazyStatic<Overloads> = lazy_static!(overloads, Overloads,
         return Ok(TCExpr {
             loc: l_from(l.loc, r.loc),
             expr_type: l.expr_type,
-            kind: TCExprKind::AddU64(env.buckets.add(l), env.buckets.add(r)),
+            kind: TCExprKind::AddPtr(env.buckets.add(l), env.buckets.add(r)),
         });
     });
 
@@ -182,7 +234,7 @@ pub static OVERLOADS: LazyStatic<Overloads> = lazy_static!(overloads, Overloads,
         return Ok(TCExpr {
             loc: l_from(l.loc, r.loc),
             expr_type: l.expr_type,
-            kind: TCExprKind::AddU64(env.buckets.add(l), env.buckets.add(r)),
+            kind: TCExprKind::AddPtr(env.buckets.add(l), env.buckets.add(r)),
         });
     });
 
@@ -197,38 +249,37 @@ pub static OVERLOADS: LazyStatic<Overloads> = lazy_static!(overloads, Overloads,
                 format!("this has type {}", l.expr_type.display(env.files)),
                 r.loc,
                 format!("this has type {}", r.expr_type.display(env.files))
-            ));
+            )
+            .with_code("TCI0012"));
         }
 
-        let expr_type = TCType::new(TCTypeKind::U64, 0);
-        let loc = l_from(l.loc, r.loc);
-        let diff = TCExpr {
-            kind: TCExprKind::SubU64(env.buckets.add(l), env.buckets.add(r)),
-            loc,
-            expr_type,
-        };
-
-        let divisor = TCExpr {
-            kind: TCExprKind::U64Literal(l_elem_type.size() as u64),
-            expr_type,
-            loc,
-        };
-
         return Ok(TCExpr {
-            kind: TCExprKind::SubU64(env.buckets.add(diff), env.buckets.add(divisor)),
-            loc,
-            expr_type,
+            kind: TCExprKind::SubPtr(env.buckets.add(l), env.buckets.add(r), l_elem_type.size()),
+            loc: l_from(l.loc, r.loc),
+            expr_type: TCType::new(TCTypeKind::I64, 0),
         });
     });
 
+    // Subtracting from a pointer adds the negated offset, so that it goes
+    // through the same bounds check
     add_op_ol!(Sub, Pointer, U64, |env, l, r| {
-        let expr_type = l.expr_type;
+        let r = TCExpr {
+            loc: r.loc,
+            kind: TCExprKind::TypePun(env.buckets.add(r)),
+            expr_type: TCType::new(TCTypeKind::I64, 0),
+        };
 
-        return Ok(TCExpr {
-            loc: l_from(l.loc, r.loc),
-            kind: TCExprKind::SubU64(env.buckets.add(l), env.buckets.add(r)),
-            expr_type,
-        });
+        return sub_from_ptr(env, l, r);
+    });
+
+    add_op_ol!(Sub, Pointer, I32, |env, l, r| {
+        let r = TCExpr {
+            loc: r.loc,
+            kind: TCExprKind::SConv32To64(env.buckets.add(r)),
+            expr_type: TCType::new(TCTypeKind::I64, 0),
+        };
+
+        return sub_from_ptr(env, l, r);
     });
 
     macro_rules! add_assign_ol {
@@ -256,6 +307,47 @@ pub static OVERLOADS: LazyStatic<Overloads> = lazy_static!(overloads, Overloads,
     add_assign_ol!(U32, U64, ZConv32To64);
     add_assign_ol!(U64, I32, Conv64To32);
     add_assign_ol!(U64, U32, Conv64To32);
+    add_assign_ol!(I32, U32, TypePun);
+    add_assign_ol!(U32, I32, TypePun);
+    add_assign_ol!(I32, I64, SConv32To64);
+    add_assign_ol!(U32, I64, ZConv32To64);
+    add_assign_ol!(I64, I32, Conv64To32);
+    add_assign_ol!(I64, U32, Conv64To32);
+    add_assign_ol!(I64, U64, TypePun);
+    add_assign_ol!(U64, I64, TypePun);
+
+    // Conversions that take more than one step, like those to and from double,
+    // which go through 64 bit integers
+    macro_rules! add_assign_ol_via {
+        ($left:ident, $right:ident, $( $expr_kind:ident : $via:ident ),*) => {{
+            expr_to_type.insert(
+                (TCShallowType::$left, TCShallowType::$right),
+                |buckets, e, t| {
+                    let mut e = e;
+                    $(
+                        e = TCExpr {
+                            loc: e.loc,
+                            kind: TCExprKind::$expr_kind(buckets.add(e)),
+                            expr_type: TCType::new(TCTypeKind::$via, 0),
+                        };
+                    )*
+                    e.expr_type = t;
+                    return e;
+                },
+            );
+        }};
+    }
+
+    add_assign_ol_via!(I8, U32, SConv8To32: I32, TypePun: U32);
+    add_assign_ol_via!(I8, I64, SConv8To32: I32, SConv32To64: I64);
+    add_assign_ol_via!(I8, U64, SConv8To32: I32, SConv32To64: I64);
+    add_assign_ol_via!(I8, F64, SConv8To32: I32, SConv32To64: I64, ConvI64ToF64: F64);
+    add_assign_ol_via!(I32, F64, SConv32To64: I64, ConvI64ToF64: F64);
+    add_assign_ol_via!(U32, F64, ZConv32To64: I64, ConvI64ToF64: F64);
+    add_assign_ol_via!(I64, F64, ConvI64ToF64: F64);
+    add_assign_ol_via!(U64, F64, ConvU64ToF64: F64);
+    add_assign_ol_via!(F64, I32, ConvF64ToI64: I64, Conv64To32: I32);
+    add_assign_ol_via!(F64, I64, ConvF64ToI64: I64);
 
     Overloads {
         unary_op,
@@ -267,6 +359,45 @@ pub static OVERLOADS: LazyStatic<Overloads> = lazy_static!(overloads, Overloads,
     }
 });
 
+/// `l - r`, where `l` is a pointer and `r` is a 64 bit element count
+fn sub_from_ptr<'b>(
+    env: CheckEnv<'_, 'b>,
+    l: TCExpr<'b>,
+    r: TCExpr<'b>,
+) -> Result<TCExpr<'b>, Error> {
+    let negative_size = TCExpr {
+        loc: l.loc,
+        kind: TCExprKind::I64Literal(-(env.deref(&l.expr_type, l.loc)?.size() as i64)),
+        expr_type: TCType::new(TCTypeKind::I64, 0),
+    };
+
+    let r = TCExpr {
+        loc: r.loc,
+        kind: TCExprKind::MulI64(env.buckets.add(r), env.buckets.add(negative_size)),
+        expr_type: TCType::new(TCTypeKind::I64, 0),
+    };
+
+    return Ok(TCExpr {
+        loc: l_from(l.loc, r.loc),
+        expr_type: l.expr_type,
+        kind: TCExprKind::AddPtr(env.buckets.add(l), env.buckets.add(r)),
+    });
+}
+
+/// Indexing dereferences the pointer it makes, and the dereference explains
+/// going out of bounds better than the pointer arithmetic would, so the
+/// arithmetic isn't bounds checked
+fn unchecked_ptr_add<'b>(sum: TCExpr<'b>) -> TCExpr<'b> {
+    if let TCExprKind::AddPtr(l, r) = sum.kind {
+        return TCExpr {
+            kind: TCExprKind::AddU64(l, r),
+            ..sum
+        };
+    }
+
+    return sum;
+}
+
 fn get_overload(
     env: CheckEnv,
     op: BinOp,
@@ -335,13 +466,24 @@ impl LocalTypeEnv {
         return unsafe { &*self.parent }.var(id);
     }
 
+    /// Every variable name that's in scope, innermost scope first
+    pub fn visible_vars(&self) -> Vec<u32> {
+        let mut vars: Vec<u32> = self.symbols.keys().map(|&id| id).collect();
+        if !self.parent.is_null() {
+            vars.extend(unsafe { &*self.parent }.visible_vars());
+        }
+
+        return vars;
+    }
+
     pub fn add_var(&mut self, ident: u32, tc_value: TCVar) -> Result<(), Error> {
         let tc_loc = tc_value.loc;
         if let Some(var_type) = self.symbols.insert(ident, tc_value) {
             return Err(error!(
                 "name redefined in scope",
                 var_type.loc, "first declaration defined here", tc_loc, "redecaration defined here"
-            ));
+            )
+            .with_code("TCI0011"));
         }
 
         return Ok(());
@@ -467,6 +609,7 @@ impl TypeEnv {
             | ATK::UnsignedLongInt
             | ATK::UnsignedLongLongInt
             | ATK::UnsignedLongLong => TCTypeKind::U64,
+            ATK::Double => TCTypeKind::F64,
             ATK::Void => TCTypeKind::Void,
             ATK::Struct(decl) => match decl {
                 StructDecl::Named(ident) => TCTypeKind::Struct {
@@ -477,7 +620,8 @@ impl TypeEnv {
                     return Err(error!(
                         "struct definition not allowed here",
                         ast_type.loc, "struct definition found here"
-                    ))
+                    )
+                    .with_code("TCI0032"))
                 }
             },
             &ATK::Ident(ident) => {
@@ -579,7 +723,8 @@ impl TypeEnv {
                     "target type defined here to be `{}`",
                     assign_type.display(files)
                 )
-            ));
+            )
+            .with_code("TCI0007"));
         }
 
         return Err(error!(
@@ -588,7 +733,8 @@ impl TypeEnv {
             format!("this has type `{}`", expr.expr_type.display(files)),
             assign_loc,
             format!("this has type `{}`", assign_type.display(files))
-        ));
+        )
+        .with_code("TCI0007"));
     }
 
     pub fn cast_convert<'b>(
@@ -629,7 +775,8 @@ impl TypeEnv {
             format!("this has type `{}`", expr.expr_type.display(files)),
             cast_to_loc,
             format!("this has type `{}`", cast_to.display(files))
-        ));
+        )
+        .with_code("TCI0007"));
     }
 
     pub fn check_struct_type(
@@ -639,14 +786,17 @@ impl TypeEnv {
         pointer_count: u32,
         loc: CodeLoc,
     ) -> Result<SizeAlign, Error> {
-        let no_struct = || error!("referenced struct doesn't exist", loc, "struct used here");
+        let no_struct = || {
+            error!("referenced struct doesn't exist", loc, "struct used here").with_code("TCI0031")
+        };
 
         let struct_type = self.structs.get(&struct_ident).ok_or_else(no_struct)?;
         if struct_type.decl_idx > decl_idx {
             return Err(error!(
                 "used type declared later in file",
                 struct_type.decl_loc, "type is declared here", loc, "type is used here"
-            ));
+            )
+            .with_code("TCI0030"));
         }
 
         if let Some(defn) = &struct_type.defn {
@@ -655,7 +805,8 @@ impl TypeEnv {
                     return Err(error!(
                         "used type defined later in file",
                         defn.meta.loc, "type is defined here", loc, "type is used here"
-                    ));
+                    )
+                    .with_code("TCI0030"));
                 }
 
                 return Ok(TC_UNKNOWN_SA);
@@ -666,7 +817,8 @@ impl TypeEnv {
             return Err(error!(
                 "referenced incomplete type without pointer indirection",
                 struct_type.decl_loc, "incomplete type declared here", loc, "type used here"
-            ));
+            )
+            .with_code("TCI0030"));
         } else {
             // type incomplete but we have a pointer to it
             return Ok(TC_UNKNOWN_SA);
@@ -675,6 +827,7 @@ impl TypeEnv {
 
     pub fn check_struct_member(
         &self,
+        files: &FileDb,
         struct_ident: u32,
         decl_idx: u32,
         loc: CodeLoc,
@@ -687,7 +840,8 @@ impl TypeEnv {
             return Err(error!(
                 "tried to dereference undefined struct type",
                 loc, "member access here"
-            ));
+            )
+            .with_code("TCI0031"));
         };
 
         if defn.meta.defn_idx > decl_idx {
@@ -697,17 +851,25 @@ impl TypeEnv {
                 "struct defined here",
                 loc,
                 "struct pointer dereferenced here"
-            ));
+            )
+            .with_code("TCI0030"));
         }
 
         #[rustfmt::skip]
         let member = if let Some(member) = defn.members.iter().find(|member| member.ident == member_ident) {
             member
         } else {
-            return Err(error!(
+            let err = error!(
                 "couldn't find member in struct definition",
                 defn.meta.loc, "struct defined here", loc, "member accessed here"
-            ));
+            )
+            .with_code("TCI0004");
+            let name = files.symbol_to_str(member_ident);
+            let members = defn.members.iter().map(|m| files.symbol_to_str(m.ident));
+            return Err(match closest_match(name, members) {
+                Some(found) => err.with_note(format!("did you mean `{}`?", found)),
+                None => err,
+            });
         };
 
         let mut member = *member;
@@ -723,12 +885,69 @@ impl TypeEnv {
     }
 }
 
+/// Warnings found while checking a file, along with the bookkeeping for the
+/// function currently being checked.
+pub struct Lints {
+    pub warnings: RefCell<Vec<Error>>,
+    pub vars: RefCell<Vec<(u32, CodeLoc, bool)>>,
+    pub used_vars: RefCell<HashSet<CodeLoc>>,
+}
+
+impl Lints {
+    pub fn new() -> Self {
+        Self {
+            warnings: RefCell::new(Vec::new()),
+            vars: RefCell::new(Vec::new()),
+            used_vars: RefCell::new(HashSet::new()),
+        }
+    }
+
+    pub fn warn(&self, warning: Error) {
+        self.warnings.borrow_mut().push(warning);
+    }
+
+    pub fn begin_func(&self) {
+        self.vars.borrow_mut().clear();
+        self.used_vars.borrow_mut().clear();
+    }
+
+    pub fn declare_var(&self, ident: u32, loc: CodeLoc, is_param: bool) {
+        self.vars.borrow_mut().push((ident, loc, is_param));
+    }
+
+    pub fn use_var(&self, loc: CodeLoc) {
+        self.used_vars.borrow_mut().insert(loc);
+    }
+
+    /// Warns about every variable in the current function that was never used
+    pub fn end_func(&self, files: &FileDb) {
+        let vars = mem::replace(&mut *self.vars.borrow_mut(), Vec::new());
+        let used_vars = self.used_vars.borrow();
+        for (ident, loc, is_param) in vars {
+            if !used_vars.contains(&loc) {
+                self.warn(unused_variable(files, ident, loc, is_param));
+            }
+        }
+    }
+
+    /// Consumes the lints, returning warnings in source order
+    pub fn into_warnings(self) -> Vec<Error> {
+        let mut warnings = self.warnings.into_inner();
+        warnings.sort_by_key(|warning| {
+            let loc = warning.sections[0].location;
+            (loc.file, loc.start)
+        });
+        return warnings;
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct CheckEnv<'a, 'b> {
     pub buckets: BucketListRef<'b>,
     pub types: &'a TypeEnv,
     pub func_types: &'a HashMap<u32, TCFuncType>,
     pub files: &'a FileDb,
+    pub lints: &'a Lints,
     pub decl_idx: u32,
 }
 
@@ -738,6 +957,7 @@ impl<'a, 'b> CheckEnv<'a, 'b> {
         types: &'a TypeEnv,
         func_types: &'a HashMap<u32, TCFuncType>,
         files: &'a FileDb,
+        lints: &'a Lints,
         decl_idx: u32,
     ) -> Self {
         Self {
@@ -745,10 +965,53 @@ impl<'a, 'b> CheckEnv<'a, 'b> {
             types,
             func_types,
             files,
+            lints,
             decl_idx,
         }
     }
 
+    /// Warns when an implicit conversion to `asgn_type` could lose information
+    pub fn check_narrowing(&self, asgn_type: &TCType, asgn_loc: CodeLoc, expr: &TCExpr) {
+        let (to, from) = (asgn_type, &expr.expr_type);
+        if to.is_integer() && from.kind == TCTypeKind::F64 && !from.is_pointer() {
+            self.lints.warn(warning!(
+                Lint::Narrowing,
+                "implicit conversion drops the fractional part",
+                expr.loc,
+                format!("this has type `{}`", from.display(self.files)),
+                asgn_loc,
+                format!("converted to `{}` here", to.display(self.files))
+            ));
+            return;
+        }
+
+        if !to.is_integer() || !from.is_integer() || to.size() >= from.size() {
+            return;
+        }
+
+        if let Some(value) = expr.kind.int_literal() {
+            let bits = to.size() * 8;
+            let (min, max) = if to.is_signed() {
+                (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1)
+            } else {
+                (0, (1i128 << bits) - 1)
+            };
+
+            if min <= value && value <= max {
+                return;
+            }
+        }
+
+        self.lints.warn(warning!(
+            Lint::Narrowing,
+            "implicit conversion may change value",
+            expr.loc,
+            format!("this has type `{}`", from.display(self.files)),
+            asgn_loc,
+            format!("converted to `{}` here", to.display(self.files))
+        ));
+    }
+
     pub fn type_eq(&self, l: TCType, r: TCType) -> bool {
         return self.types.type_eq(l, r);
     }
@@ -766,7 +1029,8 @@ impl<'a, 'b> CheckEnv<'a, 'b> {
                     "value has type {}, which cannot be dereferenced",
                     tc_type.display(self.files)
                 )
-            ));
+            )
+            .with_code("TCI0033"));
         }
 
         let result_type = match tc_type.array_kind {
@@ -789,7 +1053,8 @@ impl<'a, 'b> CheckEnv<'a, 'b> {
                     "value has type {}, which cannot be dereferenced",
                     tc_type.display(self.files)
                 )
-            ));
+            )
+            .with_code("TCI0030"));
         }
 
         return Ok(result_type);
@@ -842,6 +1107,7 @@ impl<'a, 'b> CheckEnv<'a, 'b> {
         asgn_loc: CodeLoc,
         expr: TCExpr<'b>,
     ) -> Result<TCExpr<'b>, Error> {
+        self.check_narrowing(asgn_type, asgn_loc, &expr);
         self.types
             .implicit_convert(self.buckets, self.files, asgn_type, asgn_loc, true, expr)
     }
@@ -853,6 +1119,7 @@ impl<'a, 'b> CheckEnv<'a, 'b> {
         asgn_loc: CodeLoc,
         expr: TCExpr<'b>,
     ) -> Result<TCExpr<'b>, Error> {
+        self.check_narrowing(asgn_type, asgn_loc, &expr);
         self.types
             .implicit_convert(self.buckets, self.files, asgn_type, asgn_loc, true, expr)
     }
@@ -870,7 +1137,8 @@ impl<'a, 'b> CheckEnv<'a, 'b> {
                     return Err(error!(
                         "used an initializer list to initialize something other than an array",
                         expr.loc, "initializer list used here"
-                    ));
+                    )
+                    .with_code("TCI0029"));
                 }
                 TCArrayKind::Fixed(len) => {
                     let element_type = TCType::new(asgn_type.kind, asgn_type.pointer_count);
@@ -885,7 +1153,8 @@ impl<'a, 'b> CheckEnv<'a, 'b> {
                             "array length declared here",
                             expr.loc,
                             format!("array has length {}", list.len())
-                        ));
+                        )
+                        .with_code("TCI0029"));
                     }
 
                     let mut array_elements = Vec::new();
@@ -908,7 +1177,7 @@ impl<'a, 'b> CheckEnv<'a, 'b> {
 
         if let TCTypeKind::Uninit { .. } = expr.expr_type.kind {
             if asgn_type.array_kind == TCArrayKind::Fixed(0) {
-                return Err(error!("arrays need to be initialized with an initializer list or declared with an explicit size", asgn_loc, "variable declared here"));
+                return Err(error!("arrays need to be initialized with an initializer list or declared with an explicit size", asgn_loc, "variable declared here").with_code("TCI0022"));
             }
 
             return Ok(TCExpr {
@@ -918,6 +1187,7 @@ impl<'a, 'b> CheckEnv<'a, 'b> {
             });
         }
 
+        self.check_narrowing(asgn_type, asgn_loc, &expr);
         self.types
             .implicit_convert(self.buckets, self.files, asgn_type, asgn_loc, false, expr)
     }
@@ -929,6 +1199,7 @@ impl<'a, 'b> CheckEnv<'a, 'b> {
         asgn_loc: CodeLoc,
         expr: TCExpr<'b>,
     ) -> Result<TCExpr<'b>, Error> {
+        self.check_narrowing(asgn_type, asgn_loc, &expr);
         self.types
             .implicit_convert(self.buckets, self.files, asgn_type, asgn_loc, false, expr)
     }
@@ -952,13 +1223,14 @@ impl<'a, 'b> CheckEnv<'a, 'b> {
         member_ident: u32,
     ) -> Result<TCStructMember, Error> {
         self.types
-            .check_struct_member(struct_ident, self.decl_idx, loc, member_ident)
+            .check_struct_member(self.files, struct_ident, self.decl_idx, loc, member_ident)
     }
 }
 
 pub struct TypedFuncs<'a> {
     pub types: TypeEnv,
     pub functions: HashMap<u32, TCFunc<'a>>,
+    pub warnings: Vec<Error>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -969,6 +1241,7 @@ pub enum ITypeKind {
     I64, // long
     I8,  // char
     U8,  // unsigned char
+    F64, // double
     Void,
     Struct(u32),
     AnonStruct(CodeLoc),
@@ -999,6 +1272,7 @@ impl IType {
             ITypeKind::U64 => TCTypeKind::U64,
             ITypeKind::I8 => TCTypeKind::I8,
             ITypeKind::U8 => TCTypeKind::U8,
+            ITypeKind::F64 => TCTypeKind::F64,
             ITypeKind::Void => TCTypeKind::Void,
             ITypeKind::Struct(ident) => TCTypeKind::Struct {
                 ident,
@@ -1060,6 +1334,7 @@ impl IType {
             | ATK::UnsignedLongInt
             | ATK::UnsignedLongLongInt
             | ATK::UnsignedLongLong => ITypeKind::U64,
+            ATK::Double => ITypeKind::F64,
             ATK::Void => ITypeKind::Void,
             &ATK::Struct(decl) => {
                 found_rec = Some(decl);
@@ -1129,6 +1404,8 @@ pub struct UncheckedFuncDefn<'a> {
     pub loc: CodeLoc,
     pub params: Vec<IFuncParam>,
     pub body: &'a [Stmt<'a>],
+    pub body_end: CodeLoc,
+    pub return_loc: CodeLoc,
 }
 
 pub struct UncheckedEnv<'b> {
@@ -1208,7 +1485,8 @@ pub fn sequentialize_struct_defn<'a, 'b>(
             return Err(error!(
                 "name redefined in struct",
                 original_loc, "first use of name here", member.loc, "second use here"
-            ));
+            )
+            .with_code("TCI0011"));
         }
     }
 
@@ -1271,7 +1549,8 @@ pub fn sequentialize_struct_decl<'a, 'b>(
             return Err(error!(
                 "redefinition of struct",
                 original.decl_loc, "original definition here", loc, "second definition here"
-            ));
+            )
+            .with_code("TCI0011"));
         }
 
         original_decl_meta = Some((original.decl_idx, original.decl_loc));
@@ -1309,7 +1588,14 @@ pub fn sequentialize_rec<'a, 'b>(
             pointer_count,
             params,
             body,
-        } => (return_type, pointer_count, ident, params, Some(body)),
+            body_end,
+        } => (
+            return_type,
+            pointer_count,
+            ident,
+            params,
+            Some((body, body_end)),
+        ),
         GlobalStmtKind::StructDecl(decl_type) => {
             sequentialize_struct_decl(buckets, files, g_decl_idx, env, decl_type, global_stmt.loc)?;
             return Ok(());
@@ -1363,7 +1649,8 @@ pub fn sequentialize_rec<'a, 'b>(
             return Err(error!(
                 "function parameter after vararg",
                 loc, "vararg indicator here", param.loc, "parameter here"
-            ));
+            )
+            .with_code("TCI0035"));
         }
 
         let (decl_type, pointer_count, array_dims) = match &param.kind {
@@ -1380,7 +1667,8 @@ pub fn sequentialize_rec<'a, 'b>(
                             "original declaration here",
                             param.loc,
                             "second declaration here"
-                        ));
+                        )
+                        .with_code("TCI0011"));
                     }
 
                     params.push(IFuncParam {
@@ -1416,12 +1704,13 @@ pub fn sequentialize_rec<'a, 'b>(
         varargs: varargs.is_some(),
     };
 
-    let defn = if let Some(body) = func_body {
+    let defn = if let Some((body, body_end)) = func_body {
         if let Some(ident_loc) = missing_ident_loc {
             return Err(error!(
                 "need to give names for all parameters when defining a function",
                 ident_loc, "parameter without name found here"
-            ));
+            )
+            .with_code("TCI0035"));
         }
 
         let defn_idx = *g_decl_idx;
@@ -1432,6 +1721,8 @@ pub fn sequentialize_rec<'a, 'b>(
             params,
             loc: global_stmt.loc,
             body,
+            body_end,
+            return_loc: rtype.loc,
         })
     } else {
         None
@@ -1472,7 +1763,8 @@ fn check_typedef(
     current_ident: u32,
     loc: CodeLoc,
 ) -> Result<(TCStructDefnMeta, TCType), Error> {
-    let map_err = || error!("typedef does not exist", loc, "typedef referenced here");
+    let map_err =
+        || error!("typedef does not exist", loc, "typedef referenced here").with_code("TCI0031");
     let typedef = unchecked.typedefs.get(&current_ident).ok_or_else(map_err)?;
 
     if let ITypeKind::Ident(id) = typedef.def.kind {
@@ -1555,7 +1847,8 @@ fn check_unnamed_struct_type(
             "anonymous struct does not exist (this is an error in TCI)",
             defn_loc,
             format!("we thought it would be defined here ({:?})", defn_loc)
-        ));
+        )
+        .with_code("TCI0299"));
     };
 
     let checked_defn =
@@ -1588,7 +1881,8 @@ fn check_named_struct_type(
         return Err(error!( // TODO will this ever trigger? I don't think so
             "struct does not exist",
             loc, "struct referenced here"
-        ));
+        )
+        .with_code("TCI0031"));
     };
 
     if visited.structs.contains(&current_ident) {
@@ -1601,7 +1895,8 @@ fn check_named_struct_type(
             return Err(error!(
                 "struct heirarchy contains cycle",
                 type_decl.decl_loc, "found cycle while solving this type"
-            ));
+            )
+            .with_code("TCI0030"));
         }
     }
 
@@ -1733,9 +2028,9 @@ pub fn check_file<'a>(
     buckets: BucketListRef<'a>,
     program: ASTProgram,
     files: &FileDb,
-) -> Result<TypedFuncs<'a>, Error> {
+) -> Result<TypedFuncs<'a>, Vec<Error>> {
     let mut types = TypeEnv::new();
-    let unchecked_env = sequentialize(buckets, program, files)?;
+    let unchecked_env = sequentialize(buckets, program, files).map_err(|err| vec![err])?;
 
     let mut visited = Visited {
         structs: HashSet::new(),
@@ -1750,7 +2045,8 @@ pub fn check_file<'a>(
             &unchecked_env,
             *ident,
             unchecked.decl_loc,
-        )?;
+        )
+        .map_err(|err| vec![err])?;
     }
 
     for (defn_loc, unchecked) in unchecked_env.anon_struct_types.iter() {
@@ -1760,7 +2056,8 @@ pub fn check_file<'a>(
             &unchecked_env,
             *defn_loc,
             unchecked.decl_loc,
-        )?;
+        )
+        .map_err(|err| vec![err])?;
     }
 
     for (ident, unchecked) in unchecked_env.typedefs.iter() {
@@ -1770,11 +2067,13 @@ pub fn check_file<'a>(
             &unchecked_env,
             *ident,
             unchecked.loc,
-        )?;
+        )
+        .map_err(|err| vec![err])?;
     }
 
     // TODO what if someone redefines a typedef?
 
+    let mut errors = Vec::new();
     let mut func_types = HashMap::new();
     for (func_name, func) in unchecked_env.funcs.iter() {
         let decl_idx = func.decls[0].decl_idx;
@@ -1798,26 +2097,40 @@ pub fn check_file<'a>(
             });
         };
 
-        let func_type = check_func_type(&func.decls[0])?;
-        for ftype in func.decls.iter().skip(1) {
-            let second_func_type = check_func_type(ftype)?;
-            if func_type != second_func_type {
-                return Err(func_decl_mismatch(func_type.loc, second_func_type.loc));
+        let check_decls = || -> Result<TCFuncType, Error> {
+            let func_type = check_func_type(&func.decls[0])?;
+            for ftype in func.decls.iter().skip(1) {
+                let second_func_type = check_func_type(ftype)?;
+                if func_type != second_func_type {
+                    return Err(func_decl_mismatch(func_type.loc, second_func_type.loc));
+                }
+            }
+
+            return Ok(func_type);
+        };
+
+        match check_decls() {
+            Ok(func_type) => {
+                func_types.insert(*func_name, func_type);
             }
+            Err(err) => errors.push((func.decls[0].loc, err)),
         }
+    }
 
-        func_types.insert(*func_name, func_type);
+    // Function bodies can't be checked without every function's type
+    if errors.len() != 0 {
+        return Err(sorted_errors(errors));
     }
 
     if let Some(func_type) = func_types.get(&INIT_SYMS.translate["main"]) {
         if func_type.return_type.pointer_count != 0 {
-            return Err(main_return_type(func_type.loc));
+            return Err(vec![main_return_type(func_type.loc)]);
         }
 
         match func_type.return_type.kind {
             TCTypeKind::Void | TCTypeKind::I32 => {}
             _ => {
-                return Err(main_return_type(func_type.loc));
+                return Err(vec![main_return_type(func_type.loc)]);
             }
         }
 
@@ -1826,15 +2139,16 @@ pub fn check_file<'a>(
 
         if func_type.params.len() == 2 {
             if func_type.params[0].0 != int_type {
-                return Err(main_param_types(func_type.params[0].1));
+                return Err(vec![main_param_types(func_type.params[0].1)]);
             } else if func_type.params[1].0 != char_ss_type {
-                return Err(main_param_types(func_type.loc));
+                return Err(vec![main_param_types(func_type.loc)]);
             }
         } else if func_type.params.len() != 0 {
-            return Err(main_param_types(func_type.loc));
+            return Err(vec![main_param_types(func_type.loc)]);
         }
     }
 
+    let lints = Lints::new();
     let mut func_defs = HashMap::new();
     for (func_name, func) in unchecked_env.funcs.into_iter() {
         let defn = match func.defn {
@@ -1850,6 +2164,7 @@ pub fn check_file<'a>(
             func_type.params.len()
         };
 
+        lints.begin_func();
         let mut params = Vec::new();
         for (idx, &(param_type, param_type_loc)) in func_type.params.iter().enumerate() {
             let param = defn.params[idx];
@@ -1861,6 +2176,7 @@ pub fn check_file<'a>(
             };
 
             local_env.add_var(param.ident, tc_value).unwrap();
+            lints.declare_var(param.ident, param.loc, true);
             params.push(TCFuncParam {
                 param_type,
                 loc: param.loc,
@@ -1868,9 +2184,32 @@ pub fn check_file<'a>(
             });
         }
 
-        let env = CheckEnv::new(buckets, &types, &func_types, files, defn.defn_idx);
+        let env = CheckEnv::new(buckets, &types, &func_types, files, &lints, defn.defn_idx);
+
+        let gstmts = match check_stmts(env, &mut local_env, defn.body, None) {
+            Ok(gstmts) => gstmts,
+            Err(err) => {
+                errors.push((defn.loc, err));
+                continue;
+            }
+        };
+        lints.end_func(files);
+        for warning in flow::check_init(files, &gstmts) {
+            lints.warn(warning);
+        }
+
+        let mut warnings = Vec::new();
+        let falls_through = flow::check_reachable(defn.body, &mut warnings).is_none();
+        for warning in warnings {
+            lints.warn(warning);
+        }
+
+        let returns_void = func_type.return_type == VOID;
+        if falls_through && !returns_void && func_name != INIT_SYMS.translate["main"] {
+            errors.push((defn.loc, missing_return(defn.return_loc, defn.body_end)));
+            continue;
+        }
 
-        let gstmts = check_stmts(env, &mut local_env, defn.body, None)?;
         func_defs.insert(
             func_name,
             TCFuncDefn {
@@ -1882,13 +2221,31 @@ pub fn check_file<'a>(
         );
     }
 
+    if errors.len() != 0 {
+        let mut errors = sorted_errors(errors);
+        errors.extend(lints.into_warnings());
+        return Err(errors);
+    }
+
     let mut functions = HashMap::new();
     for (func_name, func_type) in func_types.into_iter() {
         let defn = func_defs.remove(&func_name);
         functions.insert(func_name, TCFunc { func_type, defn });
     }
 
-    return Ok(TypedFuncs { types, functions });
+    let warnings = lints.into_warnings();
+    return Ok(TypedFuncs {
+        types,
+        functions,
+        warnings,
+    });
+}
+
+/// Orders errors by the location of the function they came from, so that
+/// diagnostics don't depend on hash map iteration order.
+fn sorted_errors(mut errors: Vec<(CodeLoc, Error)>) -> Vec<Error> {
+    errors.sort_by_key(|(loc, _)| (loc.file, loc.start));
+    return errors.into_iter().map(|(_, err)| err).collect();
 }
 
 fn check_stmts<'b>(
@@ -1910,7 +2267,8 @@ fn check_stmts<'b>(
                     return Err(error!(
                         "void function should not return a value",
                         expr.loc, "value is here"
-                    ));
+                    )
+                    .with_code("TCI0009"));
                 }
 
                 let expr = env.return_convert(&local_env.return_type, local_env.rtype_loc, expr)?;
@@ -1929,7 +2287,8 @@ fn check_stmts<'b>(
                         "target type is here".to_string(),
                         stmt.loc,
                         "return statement is here".to_string()
-                    ));
+                    )
+                    .with_code("TCI0008"));
                 }
 
                 tstmts.push(TCStmt {
@@ -1960,6 +2319,7 @@ fn check_stmts<'b>(
                     let expr = check_expr_allow_brace(env, local_env, &expr)?;
                     let expr = env.decl_assign_convert(&mut decl_type, recv.loc, expr)?;
                     local_env.add_local(recv.ident, decl_type, *loc)?;
+                    env.lints.declare_var(recv.ident, *loc, false);
                     tstmts.push(TCStmt {
                         kind: TCStmtKind::Decl {
                             symbol: recv.ident,
@@ -1982,6 +2342,18 @@ fn check_stmts<'b>(
                     return Err(truth_value_of_struct(cond.loc));
                 }
 
+                check_condition_lint(env, if_cond);
+                if let [Stmt {
+                    kind: StmtKind::Nop,
+                    loc,
+                }] = if_body.stmts
+                {
+                    // A `{ ; }` body has a different location than its statement
+                    if *loc == if_body.loc {
+                        env.lints.warn(empty_if_body(stmt.loc, *loc));
+                    }
+                }
+
                 let mut if_env = local_env.child();
                 let tc_if_body = check_stmts(env, &mut if_env, if_body.stmts, cblock)?;
 
@@ -2024,6 +2396,7 @@ fn check_stmts<'b>(
                 if let TCTypeKind::Struct { .. } = cond.expr_type.kind {
                     return Err(truth_value_of_struct(cond.loc));
                 }
+                check_condition_lint(env, condition);
 
                 let post = check_expr(env, local_env, post_expr)?;
                 let post = TCStmt {
@@ -2091,6 +2464,7 @@ fn check_stmts<'b>(
                     let expr = check_expr_allow_brace(env, &mut for_env, &decl.expr)?;
                     let expr = env.decl_assign_convert(&mut decl_type, decl.loc, expr)?;
                     for_env.add_local(decl.recv.ident, decl_type, decl.loc)?;
+                    env.lints.declare_var(decl.recv.ident, decl.loc, false);
                     block_stmts.push(TCStmt {
                         kind: TCStmtKind::Decl {
                             symbol: decl.recv.ident,
@@ -2104,6 +2478,7 @@ fn check_stmts<'b>(
                 if let TCTypeKind::Struct { .. } = cond.expr_type.kind {
                     return Err(truth_value_of_struct(cond.loc));
                 }
+                check_condition_lint(env, condition);
 
                 let post = check_expr(env, &for_env, post_expr)?;
                 let post = TCStmt {
@@ -2157,6 +2532,7 @@ fn check_stmts<'b>(
                 if let TCTypeKind::Struct { .. } = cond.expr_type.kind {
                     return Err(truth_value_of_struct(cond.loc));
                 }
+                check_condition_lint(env, condition);
 
                 let mut while_env = local_env.child();
                 let mut loop_stmts = check_stmts(env, &mut while_env, body.stmts, None)?;
@@ -2257,6 +2633,34 @@ pub fn check_expr_allow_brace<'b>(
                 loc: expr.loc,
             });
         }
+        ExprKind::UIntLiteral(val) => {
+            return Ok(TCExpr {
+                kind: TCExprKind::I32Literal(val as i32), // same bits, so the same opcode
+                expr_type: TCType::new(TCTypeKind::U32, 0),
+                loc: expr.loc,
+            });
+        }
+        ExprKind::LongLiteral(val) => {
+            return Ok(TCExpr {
+                kind: TCExprKind::I64Literal(val),
+                expr_type: TCType::new(TCTypeKind::I64, 0),
+                loc: expr.loc,
+            });
+        }
+        ExprKind::ULongLiteral(val) => {
+            return Ok(TCExpr {
+                kind: TCExprKind::U64Literal(val),
+                expr_type: TCType::new(TCTypeKind::U64, 0),
+                loc: expr.loc,
+            });
+        }
+        ExprKind::DoubleLiteral(val) => {
+            return Ok(TCExpr {
+                kind: TCExprKind::F64Literal(val),
+                expr_type: TCType::new(TCTypeKind::F64, 0),
+                loc: expr.loc,
+            });
+        }
         ExprKind::StringLiteral(val) => {
             return Ok(TCExpr {
                 kind: TCExprKind::StringLiteral(env.buckets.add_str(val)),
@@ -2274,10 +2678,19 @@ pub fn check_expr_allow_brace<'b>(
         ExprKind::Ident(id) => {
             let tc_var = match local_env.var(id) {
                 Some(tc_var) => tc_var,
-                None => {
-                    return Err(error!("couldn't find name", expr.loc, "identifier here"));
-                }
+                None => match env.func_types.get(&id) {
+                    // Functions can only be passed around as untyped pointers for now
+                    Some(func_type) if func_type.decl_idx <= env.decl_idx => {
+                        return Ok(TCExpr {
+                            kind: TCExprKind::FuncPtr(id),
+                            expr_type: TCType::new(TCTypeKind::Void, 1),
+                            loc: expr.loc,
+                        });
+                    }
+                    _ => return Err(ident_not_found(env, local_env, id, expr.loc)),
+                },
             };
+            env.lints.use_var(tc_var.loc);
 
             match tc_var.decl_type.array_kind {
                 TCArrayKind::None => {
@@ -2310,7 +2723,8 @@ pub fn check_expr_allow_brace<'b>(
                 return Err(error!(
                     "sizeof called on void type (this doesn't make sense because void doesn't have a size)",
                     expr.loc, "called here"
-                ));
+                )
+                .with_code("TCI0036"));
             }
 
             return Ok(TCExpr {
@@ -2354,23 +2768,34 @@ pub fn check_expr_allow_brace<'b>(
             });
         }
 
-        ExprKind::PostIncr(target) => {
+        ExprKind::PostIncr(target) | ExprKind::PostDecr(target) => {
+            let incr = matches!(expr.kind, ExprKind::PostIncr(_));
             let mut target = check_assign_target(env, local_env, target)?;
             target.target_type = env.resolve_typedef(target.target_type, target.target_loc)?;
             match target.target_type.to_shallow() {
-                TCShallowType::U64 | TCShallowType::I64 => {
+                TCShallowType::I32
+                | TCShallowType::U32
+                | TCShallowType::I64
+                | TCShallowType::U64 => {
+                    let kind = match incr {
+                        true => TCExprKind::PostIncr(target),
+                        false => TCExprKind::PostDecr(target),
+                    };
+
                     return Ok(TCExpr {
                         expr_type: target.target_type,
                         loc: expr.loc,
-                        kind: TCExprKind::PostIncrU64(target),
+                        kind,
                     });
                 }
                 TCShallowType::Struct | TCShallowType::Void => {
+                    let op = if incr { "increment" } else { "decrement" };
                     return Err(error!(
-                        "expression type is not valid for post increment",
+                        &format!("expression type is not valid for post {}", op),
                         target.target_loc,
                         format!("this is of type {}", target.target_type.display(env.files))
-                    ));
+                    )
+                    .with_code("TCI0037"));
                 }
                 _ => unimplemented!(),
             }
@@ -2396,6 +2821,7 @@ pub fn check_expr_allow_brace<'b>(
             if_true,
             if_false,
         } => {
+            check_condition_lint(env, condition);
             let condition = check_expr(env, local_env, condition)?;
             if let TCTypeKind::Struct { .. } = condition.expr_type.kind {
                 return Err(truth_value_of_struct(condition.loc));
@@ -2428,10 +2854,11 @@ pub fn check_expr_allow_brace<'b>(
 
             let bin_op = get_overload(env, BinOp::Add, &l, &r)?;
             let map_err = || invalid_operands_bin_expr(env, BinOp::Index, &l, &r);
-            let sum = bin_op.ok_or_else(map_err)?(env, l, r)?;
+            let mut sum = unchecked_ptr_add(bin_op.ok_or_else(map_err)?(env, l, r)?);
+            sum.loc = expr.loc;
 
             return Ok(TCExpr {
-                loc: l_from(l.loc, r.loc),
+                loc: expr.loc,
                 kind: TCExprKind::Deref(env.buckets.add(sum)),
                 expr_type: result_type,
             });
@@ -2441,8 +2868,18 @@ pub fn check_expr_allow_brace<'b>(
             let l = check_expr(env, local_env, l)?;
             let r = check_expr(env, local_env, r)?;
 
+            match op {
+                BinOp::Lt | BinOp::Gt | BinOp::Leq | BinOp::Geq | BinOp::Eq | BinOp::Neq => {
+                    check_sign_compare(env, &l, &r)?;
+                }
+                _ => {}
+            }
+
+            // The parser's location includes any parentheses around the operands
             if let Some(transform) = get_overload(env, op, &l, &r)? {
-                return transform(env, l, r);
+                let mut tc_expr = transform(env, l, r)?;
+                tc_expr.loc = expr.loc;
+                return Ok(tc_expr);
             }
 
             let (l, r) = unify(env, l, r)?;
@@ -2463,9 +2900,12 @@ pub fn check_expr_allow_brace<'b>(
                         op
                     )
                 )
+                .with_code("TCI0012")
             };
 
-            return OVERLOADS.unified_bin_op.get(&key).ok_or_else(map_err)?(env, l, r);
+            let mut tc_expr = OVERLOADS.unified_bin_op.get(&key).ok_or_else(map_err)?(env, l, r)?;
+            tc_expr.loc = expr.loc;
+            return Ok(tc_expr);
         }
 
         ExprKind::UnaryOp(op, operand) => {
@@ -2482,7 +2922,8 @@ pub fn check_expr_allow_brace<'b>(
                             "operand found here with type {}",
                             operand.expr_type.display(env.files)
                         )
-                    ))
+                    )
+                    .with_code("TCI0037"))
                 }
             };
 
@@ -2500,6 +2941,20 @@ pub fn check_expr_allow_brace<'b>(
 
             let member_info = env.check_struct_member(struct_id, base.loc, member)?;
 
+            if let TCArrayKind::Fixed(_) = member_info.decl_type.array_kind {
+                if let Ok(target) = check_assign_target(env, local_env, expr) {
+                    return Ok(TCExpr {
+                        expr_type: member_info.decl_type,
+                        loc: expr.loc,
+                        kind: TCExprKind::MemberArray {
+                            target,
+                            struct_id,
+                            member,
+                        },
+                    });
+                }
+            }
+
             return Ok(TCExpr {
                 expr_type: member_info.decl_type,
                 loc: expr.loc,
@@ -2525,6 +2980,26 @@ pub fn check_expr_allow_brace<'b>(
 
             let member_info = env.check_struct_member(struct_id, base.loc, member)?;
 
+            if let TCArrayKind::Fixed(_) = member_info.decl_type.array_kind {
+                let target = TCAssignTarget {
+                    kind: TCAssignTargetKind::Ptr(env.buckets.add(base)),
+                    defn_loc: Some(member_info.loc),
+                    target_loc: expr.loc,
+                    target_type: member_info.decl_type,
+                    offset: member_info.offset,
+                };
+
+                return Ok(TCExpr {
+                    expr_type: member_info.decl_type,
+                    loc: expr.loc,
+                    kind: TCExprKind::MemberArray {
+                        target,
+                        struct_id,
+                        member,
+                    },
+                });
+            }
+
             return Ok(TCExpr {
                 expr_type: member_info.decl_type,
                 loc: expr.loc,
@@ -2563,20 +3038,22 @@ pub fn check_expr_allow_brace<'b>(
                 return Err(error!(
                     "calling an expression that isn't a function",
                     function.loc, "called here"
-                ));
+                )
+                .with_code("TCI0038"));
             };
 
             let func_type = if let Some(func_type) = env.func_types.get(&func_id) {
                 func_type
             } else {
-                return Err(error!("function doesn't exist", expr.loc, "called here"));
+                return Err(func_not_found(env, func_id, expr.loc));
             };
 
             if func_type.decl_idx > env.decl_idx {
                 return Err(error!(
                     "function hasn't been declared yet (declaration order matters in C)",
                     expr.loc, "function called here", func_type.loc, "function declared here"
-                ));
+                )
+                .with_code("TCI0005"));
             }
 
             if params.len() < func_type.params.len()
@@ -2585,20 +3062,55 @@ pub fn check_expr_allow_brace<'b>(
                 return Err(error!(
                     "function call has wrong number of parameters",
                     expr.loc, "function called here", func_type.loc, "function declared here"
-                ));
+                )
+                .with_code("TCI0006"));
             }
 
+            let is_assert = func_id == INIT_SYMS.translate["__tci_assert"];
             let mut tparams = Vec::new();
             for (idx, param) in params.iter().enumerate() {
                 let mut expr = check_expr(env, local_env, param)?;
+                if is_assert {
+                    // assert takes anything an if statement would, so it gets the
+                    // same treatment as a condition
+                    check_condition_lint(env, param);
+                    expr = truth_value(env, expr)?;
+                }
+
                 if idx < func_type.params.len() {
                     let param_type = &func_type.params[idx];
                     expr = env.param_convert(&param_type.0, param_type.1, expr)?;
+                } else if let TCArrayKind::Fixed(_) = expr.expr_type.array_kind {
+                    // arrays passed through `...` decay to pointers
+                    expr = TCExpr {
+                        expr_type: TCType::new(
+                            expr.expr_type.kind,
+                            expr.expr_type.pointer_count + 1,
+                        ),
+                        loc: expr.loc,
+                        kind: TCExprKind::TypePun(env.buckets.add(expr)),
+                    };
                 }
 
                 tparams.push(expr);
             }
 
+            let is_printf = func_id == INIT_SYMS.translate["printf"];
+            if is_printf || func_id == INIT_SYMS.translate["scanf"] {
+                if let ExprKind::StringLiteral(_) = params[0].kind {
+                    let (format_loc, args) = (params[0].loc, &tparams[1..]);
+                    let result = match is_printf {
+                        true => check_printf_format(env, format_loc, args, expr.loc),
+                        false => check_scanf_format(env, format_loc, args, expr.loc),
+                    };
+                    match result {
+                        Ok(()) => {}
+                        Err(err) if err.is_warning() => env.lints.warn(err),
+                        Err(err) => return Err(err),
+                    }
+                }
+            }
+
             return Ok(TCExpr {
                 kind: TCExprKind::Call {
                     func: func_id,
@@ -2635,9 +3147,10 @@ fn check_assign_target<'b>(
             let tc_var = match local_env.var(*id) {
                 Some(tc_var) => tc_var,
                 None => {
-                    return Err(ident_not_found(&env.types, expr.loc));
+                    return Err(ident_not_found(env, local_env, *id, expr.loc));
                 }
             };
+            env.lints.use_var(tc_var.loc);
 
             let kind = TCAssignTargetKind::LocalIdent {
                 var_offset: tc_var.var_offset,
@@ -2669,7 +3182,7 @@ fn check_assign_target<'b>(
                 defn_loc: Some(member_info.loc),
                 target_loc: expr.loc,
                 target_type: member_info.decl_type,
-                offset: member_info.offset,
+                offset: base.offset + member_info.offset,
             });
         }
         ExprKind::PtrMember { base, member } => {
@@ -2716,7 +3229,8 @@ fn check_assign_target<'b>(
 
             let bin_op = get_overload(env, BinOp::Add, &ptr, &offset)?;
             let map_err = || invalid_operands_bin_expr(env, BinOp::Index, &ptr, &offset);
-            let sum = bin_op.ok_or_else(map_err)?(env, ptr, offset)?;
+            let mut sum = unchecked_ptr_add(bin_op.ok_or_else(map_err)?(env, ptr, offset)?);
+            sum.loc = expr.loc;
 
             let target_type = env.deref(&ptr.expr_type, ptr.loc)?;
             return Ok(TCAssignTarget {
@@ -2731,30 +3245,431 @@ fn check_assign_target<'b>(
             return Err(error!(
                 "expression is not assignable",
                 expr.loc, "expression found here"
-            ))
+            )
+            .with_code("TCI0039"))
+        }
+    }
+}
+
+/// Converts a scalar `expr` to the int 1 if it's nonzero and 0 otherwise
+fn truth_value<'b>(env: CheckEnv<'_, 'b>, expr: TCExpr<'b>) -> Result<TCExpr<'b>, Error> {
+    if let TCTypeKind::Struct { .. } = expr.expr_type.kind {
+        return Err(truth_value_of_struct(expr.loc));
+    }
+
+    let int_type = TCType::new(TCTypeKind::I32, 0);
+    let literal = |value| TCExpr {
+        kind: TCExprKind::I32Literal(value),
+        expr_type: int_type,
+        loc: expr.loc,
+    };
+
+    return Ok(TCExpr {
+        kind: TCExprKind::Ternary {
+            condition: env.buckets.add(expr),
+            if_true: env.buckets.add(literal(1)),
+            if_false: env.buckets.add(literal(0)),
+        },
+        expr_type: int_type,
+        loc: expr.loc,
+    });
+}
+
+fn check_condition_lint(env: CheckEnv, cond: &Expr) {
+    if let ExprKind::Assign(_, _) = cond.kind {
+        env.lints.warn(assign_in_condition(cond.loc));
+    }
+}
+
+fn check_sign_compare(env: CheckEnv, l: &TCExpr, r: &TCExpr) -> Result<(), Error> {
+    let l_type = env.resolve_typedef(l.expr_type, l.loc)?;
+    let r_type = env.resolve_typedef(r.expr_type, r.loc)?;
+    if !l_type.is_integer() || !r_type.is_integer() {
+        return Ok(());
+    }
+
+    if l_type.is_signed() == r_type.is_signed() {
+        return Ok(());
+    }
+
+    // Comparing against a non-negative constant is always fine
+    let signed_expr = if l_type.is_signed() { l } else { r };
+    if let Some(value) = signed_expr.kind.int_literal() {
+        if value >= 0 {
+            return Ok(());
+        }
+    }
+
+    env.lints.warn(warning!(
+        Lint::SignCompare,
+        "comparison between signed and unsigned integers",
+        l.loc,
+        format!("this has type `{}`", l_type.display(env.files)),
+        r.loc,
+        format!("this has type `{}`", r_type.display(env.files))
+    ));
+    return Ok(());
+}
+
+/// Checks the arguments of a call to `printf` against its format string, using
+/// the text of the string literal in the source so that labels can point at
+/// individual conversions. Mismatches are warnings, like in other compilers.
+fn check_printf_format(
+    env: CheckEnv,
+    format_loc: CodeLoc,
+    args: &[TCExpr],
+    call_loc: CodeLoc,
+) -> Result<(), Error> {
+    let text = env.files.cloc_to_str(format_loc).as_bytes();
+    if text.first() != Some(&b'"') {
+        // The literal came from somewhere we can't read, e.g. a macro argument
+        return Ok(());
+    }
+
+    let spec_loc = |begin: usize, end: usize| {
+        let start = format_loc.start + begin as u32;
+        l(start, format_loc.start + end as u32, format_loc.file)
+    };
+
+    let mut args = args.iter();
+    let mut next_arg = |loc: CodeLoc| match args.next() {
+        Some(arg) => Ok(arg),
+        None => Err(warning!(
+            Lint::Format,
+            "not enough arguments for format string",
+            loc,
+            "this conversion has no matching argument",
+            call_loc,
+            "function called here"
+        )),
+    };
+
+    let (mut idx, mut in_string) = (0, false);
+    while idx < text.len() {
+        let begin = idx;
+        idx += 1;
+        match text[begin] {
+            b'"' => in_string = !in_string,
+            b'\\' if in_string => idx += 1,
+            b'%' if in_string => {
+                while idx < text.len() && b"-+ 0#".contains(&text[idx]) {
+                    idx += 1;
+                }
+
+                let mut star_count = 0;
+                if idx < text.len() && text[idx] == b'*' {
+                    star_count += 1;
+                    idx += 1;
+                }
+                while idx < text.len() && text[idx].is_ascii_digit() {
+                    idx += 1;
+                }
+
+                if idx < text.len() && text[idx] == b'.' {
+                    idx += 1;
+                    if idx < text.len() && text[idx] == b'*' {
+                        star_count += 1;
+                        idx += 1;
+                    }
+                    while idx < text.len() && text[idx].is_ascii_digit() {
+                        idx += 1;
+                    }
+                }
+
+                let mut is_long = false;
+                while idx < text.len() && text[idx] == b'l' {
+                    is_long = true;
+                    idx += 1;
+                }
+
+                let conversion = if idx < text.len() { text[idx] } else { b'"' };
+                let loc = spec_loc(begin, (idx + 1).min(text.len()));
+                idx += 1;
+
+                for _ in 0..star_count {
+                    let arg = next_arg(loc)?;
+                    check_format_arg(env, loc, arg, "an `int` width or precision", |t| {
+                        t.is_integer() && t.size() == 4
+                    })?;
+                }
+
+                match conversion {
+                    b'%' => {}
+                    b'd' | b'i' | b'u' if is_long => {
+                        let arg = next_arg(loc)?;
+                        check_format_arg(env, loc, arg, "a `long`", |t| {
+                            t.is_integer() && t.size() == 8
+                        })?;
+                    }
+                    b'd' | b'i' | b'u' => {
+                        let arg = next_arg(loc)?;
+                        check_format_arg(env, loc, arg, "an `int`", |t| {
+                            t.is_integer() && t.size() == 4
+                        })?;
+                    }
+                    b'f' | b'e' | b'g' => {
+                        let arg = next_arg(loc)?;
+                        check_format_arg(env, loc, arg, "a `double`", |t| {
+                            t.kind == TCTypeKind::F64 && !t.is_pointer()
+                        })?;
+                    }
+                    b'c' => {
+                        let arg = next_arg(loc)?;
+                        check_format_arg(env, loc, arg, "a `char`", |t| {
+                            t.is_integer() && t.size() == 1
+                        })?;
+                    }
+                    b's' => {
+                        let arg = next_arg(loc)?;
+                        check_format_arg(env, loc, arg, "a string (`char*`)", |t| {
+                            let is_char = t.kind == TCTypeKind::I8 || t.kind == TCTypeKind::U8;
+                            let indirection = match t.array_kind {
+                                TCArrayKind::None => t.pointer_count,
+                                TCArrayKind::Fixed(_) => t.pointer_count + 1,
+                            };
+                            is_char && indirection == 1
+                        })?;
+                    }
+                    _ => {
+                        return Err(warning!(
+                            Lint::Format,
+                            "invalid conversion in format string",
+                            loc,
+                            "this conversion isn't supported"
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(arg) = args.next() {
+        env.lints.warn(warning!(
+            Lint::Format,
+            "too many arguments for format string",
+            arg.loc,
+            "this argument isn't used by the format string",
+            format_loc,
+            "format string is here"
+        ));
+    }
+
+    return Ok(());
+}
+
+fn check_scanf_format(
+    env: CheckEnv,
+    format_loc: CodeLoc,
+    args: &[TCExpr],
+    call_loc: CodeLoc,
+) -> Result<(), Error> {
+    let text = env.files.cloc_to_str(format_loc).as_bytes();
+    if text.first() != Some(&b'"') {
+        return Ok(());
+    }
+
+    let spec_loc = |begin: usize, end: usize| {
+        let start = format_loc.start + begin as u32;
+        l(start, format_loc.start + end as u32, format_loc.file)
+    };
+
+    let mut args = args.iter();
+    let mut next_arg = |loc: CodeLoc| match args.next() {
+        Some(arg) => Ok(arg),
+        None => Err(warning!(
+            Lint::Format,
+            "not enough arguments for format string",
+            loc,
+            "this conversion has no matching argument",
+            call_loc,
+            "function called here"
+        )),
+    };
+
+    let points_to = |t: &TCType, size: u32| {
+        if t.pointer_count != 1 || t.array_kind != TCArrayKind::None {
+            return false;
         }
+
+        let pointee = TCType::new(t.kind, 0);
+        return pointee.is_integer() && pointee.size() == size;
+    };
+
+    let (mut idx, mut in_string) = (0, false);
+    while idx < text.len() {
+        let begin = idx;
+        idx += 1;
+        match text[begin] {
+            b'"' => in_string = !in_string,
+            b'\\' if in_string => idx += 1,
+            b'%' if in_string => {
+                if idx < text.len() && text[idx] == b'%' {
+                    idx += 1;
+                    continue;
+                }
+
+                let suppress = idx < text.len() && text[idx] == b'*';
+                if suppress {
+                    idx += 1;
+                }
+                while idx < text.len() && text[idx].is_ascii_digit() {
+                    idx += 1;
+                }
+
+                let mut size = 4;
+                while idx < text.len() && (text[idx] == b'h' || text[idx] == b'l') {
+                    size = match (text[idx], size) {
+                        (b'l', _) => 8,
+                        (_, 4) => 2,
+                        _ => 1,
+                    };
+                    idx += 1;
+                }
+
+                let conversion = if idx < text.len() { text[idx] } else { b'"' };
+                let loc = spec_loc(begin, (idx + 1).min(text.len()));
+                idx += 1;
+
+                let expected = match (conversion, size) {
+                    (b'd', _) | (b'i', _) | (b'u', _) | (b'x', _) => match size {
+                        1 => "a `char*`",
+                        2 => "a `short*`",
+                        4 => "an `int*`",
+                        _ => "a `long*`",
+                    },
+                    (b'c', 4) | (b's', 4) => {
+                        size = 1;
+                        "a `char*`"
+                    }
+                    _ => {
+                        return Err(warning!(
+                            Lint::Format,
+                            "invalid conversion in format string",
+                            loc,
+                            "this conversion isn't supported"
+                        ));
+                    }
+                };
+
+                if !suppress {
+                    let arg = next_arg(loc)?;
+                    check_format_arg(env, loc, arg, expected, |t| points_to(t, size))?;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(arg) = args.next() {
+        env.lints.warn(warning!(
+            Lint::Format,
+            "too many arguments for format string",
+            arg.loc,
+            "this argument isn't used by the format string",
+            format_loc,
+            "format string is here"
+        ));
+    }
+
+    return Ok(());
+}
+
+fn check_format_arg(
+    env: CheckEnv,
+    spec_loc: CodeLoc,
+    arg: &TCExpr,
+    expected: &str,
+    matches: impl Fn(&TCType) -> bool,
+) -> Result<(), Error> {
+    let arg_type = env.resolve_typedef(arg.expr_type, arg.loc)?;
+    if matches(&arg_type) {
+        return Ok(());
+    }
+
+    env.lints.warn(warning!(
+        Lint::Format,
+        "format string doesn't match argument type",
+        spec_loc,
+        format!("this expects {}", expected),
+        arg.loc,
+        format!("this has type `{}`", arg_type.display(env.files))
+    ));
+    return Ok(());
+}
+
+pub fn unused_variable(files: &FileDb, ident: u32, loc: CodeLoc, is_param: bool) -> Error {
+    let name = files.symbol_to_str(ident);
+    if is_param {
+        return warning!(
+            Lint::UnusedParameter,
+            &format!("unused parameter `{}`", name),
+            loc,
+            "parameter declared here"
+        );
     }
+
+    return warning!(
+        Lint::UnusedVariable,
+        &format!("unused variable `{}`", name),
+        loc,
+        "variable declared here"
+    );
+}
+
+pub fn assign_in_condition(loc: CodeLoc) -> Error {
+    return warning!(
+        Lint::AssignInCondition,
+        "assignment used as a condition",
+        loc,
+        "did you mean to compare with `==`?"
+    );
+}
+
+pub fn empty_if_body(if_loc: CodeLoc, semi_loc: CodeLoc) -> Error {
+    return warning!(
+        Lint::EmptyBody,
+        "if statement has an empty body",
+        if_loc,
+        "this if statement does nothing",
+        semi_loc,
+        "because of this semicolon"
+    );
 }
 
 pub fn main_param_types(loc: CodeLoc) -> Error {
     return error!(
         "can only have param types of (int, char**) or no params for the main function",
         loc, "invalid param types found here"
-    );
+    )
+    .with_code("TCI0013");
+}
+
+pub fn missing_return(return_loc: CodeLoc, body_end: CodeLoc) -> Error {
+    return error!(
+        "function can end without returning a value (return type is not void)",
+        return_loc,
+        "return type is here",
+        body_end,
+        "control can reach the end of the function here"
+    )
+    .with_code("TCI0010");
 }
 
 pub fn main_return_type(loc: CodeLoc) -> Error {
     return error!(
         "can only have return type of void or int for the main function",
         loc, "invalid return type found here"
-    );
+    )
+    .with_code("TCI0013");
 }
 
 pub fn void_variable(loc: CodeLoc) -> Error {
     return error!(
         "cannot define a variable of type void",
         loc, "incorrect variable definition here"
-    );
+    )
+    .with_code("TCI0036");
 }
 
 pub fn truth_value_of_struct(loc: CodeLoc) -> Error {
@@ -2762,6 +3677,7 @@ pub fn truth_value_of_struct(loc: CodeLoc) -> Error {
         "tried to check truth value of struct",
         loc, "this is a struct, when it should be a number or pointer"
     )
+    .with_code("TCI0040")
 }
 
 pub fn ptr_member_of_poly_pointer(ptr_loc: CodeLoc, ptr_type: &TCType) -> Error {
@@ -2773,6 +3689,7 @@ pub fn ptr_member_of_poly_pointer(ptr_loc: CodeLoc, ptr_type: &TCType) -> Error
             ptr_type
         )
     )
+    .with_code("TCI0034")
 }
 
 pub fn member_of_non_struct(loc: CodeLoc) -> Error {
@@ -2780,24 +3697,67 @@ pub fn member_of_non_struct(loc: CodeLoc) -> Error {
         "cannot access member of non-struct",
         loc, "access happened here"
     )
+    .with_code("TCI0034")
+}
+
+pub fn ident_not_found(env: CheckEnv, local_env: &LocalTypeEnv, ident: u32, loc: CodeLoc) -> Error {
+    let vars = local_env.visible_vars().into_iter();
+    let candidates = vars.chain(env.func_types.keys().map(|&id| id));
+    let candidates: Vec<&str> = candidates.map(|id| env.files.symbol_to_str(id)).collect();
+
+    let err = error!("couldn't find name", loc, "identifier here").with_code("TCI0002");
+    return suggest_name(err, env.files.symbol_to_str(ident), candidates);
 }
 
-pub fn ident_not_found(env: &TypeEnv, loc: CodeLoc) -> Error {
-    return error!("couldn't find name", loc, "identifier here");
+pub fn func_not_found(env: CheckEnv, ident: u32, loc: CodeLoc) -> Error {
+    let candidates = env.func_types.keys().map(|&id| env.files.symbol_to_str(id));
+
+    let err = error!("function doesn't exist", loc, "called here").with_code("TCI0003");
+    return suggest_name(err, env.files.symbol_to_str(ident), candidates.collect());
+}
+
+/// Adds a note to `err` guessing what name the user meant to write, including
+/// library functions whose header wasn't included
+fn suggest_name(err: Error, name: &str, candidates: Vec<&str>) -> Error {
+    let library = &INIT_SYMS.library_funcs;
+    if let Some((_, header)) = library.iter().find(|(func, _)| *func == name) {
+        return err.with_note(format!(
+            "`{}` is declared in <{}>; try adding `#include <{}>`",
+            name, header, header
+        ));
+    }
+
+    // The builtins the system headers are written with aren't meant to be called
+    let candidates = candidates.into_iter().filter(|c| !c.starts_with("__tci_"));
+    if let Some(found) = closest_match(name, candidates) {
+        return err.with_note(format!("did you mean `{}`?", found));
+    }
+
+    if let Some(found) = closest_match(name, library.iter().map(|(func, _)| *func)) {
+        let (_, header) = library.iter().find(|(func, _)| *func == found).unwrap();
+        return err.with_note(format!(
+            "did you mean `{}`? it's declared in <{}>",
+            found, header
+        ));
+    }
+
+    return err;
 }
 
 pub fn func_decl_mismatch(original: CodeLoc, new: CodeLoc) -> Error {
     return error!(
         "function declaration type doesn't match previous declaration",
         original, "original declaration here", new, "second declaration here"
-    );
+    )
+    .with_code("TCI0041");
 }
 
 pub fn func_redef(original: CodeLoc, redef: CodeLoc) -> Error {
     return error!(
         "redefinition of function",
         original, "original definition here", redef, "second definition here"
-    );
+    )
+    .with_code("TCI0011");
 }
 
 pub fn brace_list(loc: CodeLoc) -> Error {
@@ -2805,6 +3765,7 @@ pub fn brace_list(loc: CodeLoc) -> Error {
         "brace lists are only allowed when declaring a variable",
         loc, "brace list found here"
     )
+    .with_code("TCI0029")
 }
 
 pub fn array_dimensions_too_high(loc: CodeLoc) -> Error {
@@ -2812,35 +3773,39 @@ pub fn array_dimensions_too_high(loc: CodeLoc) -> Error {
         "TCI only supports arrays with up to 1 dimensions",
         loc, "array with too many dimensions found here"
     )
+    .with_code("TCI0022")
 }
 
 pub fn type_decl_where_shouldnt_be(loc: CodeLoc) -> Error {
     return error!(
         "type declaration in function return type",
         loc, "found here"
-    );
+    )
+    .with_code("TCI0032");
 }
 
 pub fn member_incomplete_type(loc: CodeLoc) -> Error {
-    error!("member has incomplete type", loc, "member here")
+    error!("member has incomplete type", loc, "member here").with_code("TCI0030")
 }
 
 pub fn typedef_defined_later(defn: CodeLoc, var: CodeLoc) -> Error {
     return error!(
         "typedef is defined later in the file for non-pointer type (order matters in C)",
         defn, "typedef defined here", var, "typedef referenced here"
-    );
+    )
+    .with_code("TCI0030");
 }
 
 pub fn typedef_not_defined(loc: CodeLoc) -> Error {
-    return error!("typedef is not defined", loc, "referenced here");
+    return error!("typedef is not defined", loc, "referenced here").with_code("TCI0031");
 }
 
 pub fn struct_defined_later(defn: CodeLoc, var: CodeLoc) -> Error {
     return error!(
         "struct is defined later in the file for non-pointer type (order matters in C)",
         defn, "struct defined here", var, "struct referenced here"
-    );
+    )
+    .with_code("TCI0030");
 }
 
 pub fn invalid_operands_bin_expr(env: CheckEnv, op: BinOp, l: &TCExpr, r: &TCExpr) -> Error {
@@ -2861,7 +3826,8 @@ pub fn invalid_operands_bin_expr(env: CheckEnv, op: BinOp, l: &TCExpr, r: &TCExp
             ),
             r.loc,
             format!("this has type {}", r.expr_type.display(env.files))
-        );
+        )
+        .with_code("TCI0012");
     }
 
     if OVERLOADS.right_op.get(&rkey).is_none() {
@@ -2875,7 +3841,8 @@ pub fn invalid_operands_bin_expr(env: CheckEnv, op: BinOp, l: &TCExpr, r: &TCExp
                 r.expr_type.display(env.files),
                 op
             )
-        );
+        )
+        .with_code("TCI0012");
     }
 
     return error!(
@@ -2892,5 +3859,6 @@ pub fn invalid_operands_bin_expr(env: CheckEnv, op: BinOp, l: &TCExpr, r: &TCExp
             r.expr_type.display(env.files),
             op
         )
-    );
+    )
+    .with_code("TCI0012");
 }
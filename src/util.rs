@@ -57,6 +57,36 @@ macro_rules! error {
     };
 }
 
+macro_rules! warning {
+    ($lint:expr, $msg:expr, $loc1:expr, $msg1:expr) => {
+        $crate::util::Error::warning(
+            $lint,
+            $msg,
+            vec![$crate::util::ErrorSection {
+                location: $loc1,
+                message: $msg1.to_string(),
+            }],
+        )
+    };
+
+    ($lint:expr, $msg:expr, $loc1:expr, $msg1:expr, $loc2:expr, $msg2:expr) => {
+        $crate::util::Error::warning(
+            $lint,
+            $msg,
+            vec![
+                $crate::util::ErrorSection {
+                    location: $loc1,
+                    message: $msg1.to_string(),
+                },
+                $crate::util::ErrorSection {
+                    location: $loc2,
+                    message: $msg2.to_string(),
+                },
+            ],
+        )
+    };
+}
+
 pub struct LazyStatic<Obj> {
     pub init: AtomicU8,
     pub constructor: fn() -> Obj,
@@ -147,10 +177,84 @@ pub struct ErrorSection {
     pub message: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+pub enum Lint {
+    UnusedVariable,
+    UnusedParameter,
+    AssignInCondition,
+    SignCompare,
+    Narrowing,
+    EmptyBody,
+    Uninitialized,
+    UnreachableCode,
+    Format,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 9] = [
+        Lint::UnusedVariable,
+        Lint::UnusedParameter,
+        Lint::AssignInCondition,
+        Lint::SignCompare,
+        Lint::Narrowing,
+        Lint::EmptyBody,
+        Lint::Uninitialized,
+        Lint::UnreachableCode,
+        Lint::Format,
+    ];
+
+    /// The name used to refer to this lint on the command line, e.g.
+    /// `-Wno-unused-variable`
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UnusedVariable => return "unused-variable",
+            Lint::UnusedParameter => return "unused-parameter",
+            Lint::AssignInCondition => return "assign-in-condition",
+            Lint::SignCompare => return "sign-compare",
+            Lint::Narrowing => return "narrowing",
+            Lint::EmptyBody => return "empty-body",
+            Lint::Uninitialized => return "uninitialized",
+            Lint::UnreachableCode => return "unreachable-code",
+            Lint::Format => return "format",
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Lint::UnusedVariable => return "TCI0101",
+            Lint::UnusedParameter => return "TCI0102",
+            Lint::AssignInCondition => return "TCI0103",
+            Lint::SignCompare => return "TCI0104",
+            Lint::Narrowing => return "TCI0105",
+            Lint::EmptyBody => return "TCI0106",
+            Lint::Uninitialized => return "TCI0107",
+            Lint::UnreachableCode => return "TCI0108",
+            Lint::Format => return "TCI0109",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Lint> {
+        return Self::ALL
+            .iter()
+            .find(|lint| lint.name() == name)
+            .map(|lint| *lint);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum Severity {
+    Error,
+    Warning(Lint),
+}
+
 #[derive(Debug, serde::Serialize)]
 pub struct Error {
+    pub severity: Severity,
     pub message: String,
     pub sections: Vec<ErrorSection>,
+    pub code: Option<&'static str>,
+    pub secondary: Vec<ErrorSection>,
+    pub notes: Vec<String>,
 }
 
 impl Into<Label<u32>> for &ErrorSection {
@@ -162,15 +266,83 @@ impl Into<Label<u32>> for &ErrorSection {
 impl Error {
     pub fn new(message: &str, sections: Vec<ErrorSection>) -> Error {
         Self {
+            severity: Severity::Error,
+            message: message.to_string(),
+            sections,
+            code: None,
+            secondary: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn warning(lint: Lint, message: &str, sections: Vec<ErrorSection>) -> Error {
+        Self {
+            severity: Severity::Warning(lint),
             message: message.to_string(),
             sections,
+            code: Some(lint.code()),
+            secondary: Vec::new(),
+            notes: Vec::new(),
         }
     }
 
+    /// Adds a label that gives context for the error without being its cause
+    pub fn with_secondary(mut self, location: CodeLoc, message: &str) -> Error {
+        self.secondary.push(ErrorSection {
+            location,
+            message: message.to_string(),
+        });
+        return self;
+    }
+
+    /// Attaches a stable error code, which can be looked up with `tci --explain`
+    pub fn with_code(mut self, code: &'static str) -> Error {
+        self.code = Some(code);
+        return self;
+    }
+
+    pub fn with_note(mut self, note: String) -> Error {
+        self.notes.push(note);
+        return self;
+    }
+
+    pub fn is_warning(&self) -> bool {
+        return self.severity != Severity::Error;
+    }
+
     pub fn diagnostic(&self) -> Diagnostic<u32> {
-        Diagnostic::error()
-            .with_message(&self.message)
-            .with_labels(self.sections.iter().map(|x| x.into()).collect())
+        let mut notes = self.notes.clone();
+        let diagnostic = match self.severity {
+            Severity::Error => Diagnostic::error(),
+            Severity::Warning(lint) => {
+                let name = lint.name();
+                notes.push(format!("this warning can be turned off with -Wno-{}", name));
+                Diagnostic::warning()
+            }
+        };
+
+        let secondary = self
+            .secondary
+            .iter()
+            .map(|x| Label::secondary(x.location.file, x.location).with_message(&x.message));
+
+        let mut diagnostic = diagnostic.with_message(&self.message);
+        if let Some(code) = self.code {
+            diagnostic = diagnostic.with_code(code);
+            notes.push(format!(
+                "for more information, run `tci --explain {}`",
+                code
+            ));
+        }
+
+        let diagnostic = diagnostic.with_notes(notes);
+        diagnostic.with_labels(
+            self.sections
+                .iter()
+                .map(|x| x.into())
+                .chain(secondary)
+                .collect(),
+        )
     }
 }
 
@@ -180,6 +352,54 @@ impl Into<Vec<Error>> for Error {
     }
 }
 
+/// Edit distance between two names, where swapping adjacent characters counts
+/// as a single edit
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut prev2 = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            current[j] = (prev[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(prev2[j - 2] + 1);
+            }
+        }
+
+        core::mem::swap(&mut prev2, &mut prev);
+        core::mem::swap(&mut prev, &mut current);
+    }
+
+    return prev[b.len()];
+}
+
+/// Finds the candidate that's most likely to be what the user meant when they
+/// wrote `name`
+pub fn closest_match<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let max_distance = (name.len() / 3).max(1);
+    let mut best: Option<(usize, &'a str)> = None;
+    for candidate in candidates {
+        let distance = edit_distance(name, candidate);
+        if distance == 0 || distance > max_distance {
+            continue;
+        }
+
+        // Candidates often come from a HashMap, so ties go to the name that
+        // sorts first to keep the suggestion the same from run to run
+        match best {
+            Some(best) if best <= (distance, candidate) => {}
+            _ => best = Some((distance, candidate)),
+        }
+    }
+
+    return best.map(|(_, candidate)| candidate);
+}
+
 pub const NO_FILE: CodeLoc = CodeLoc {
     start: 0,
     end: 0,
@@ -206,10 +426,16 @@ impl Into<ops::Range<usize>> for CodeLoc {
     }
 }
 
+/// The smallest location covering both `loc1` and `loc2`. Macro arguments keep
+/// their own locations, so the two can come in either order.
 #[inline]
 pub fn l_from(loc1: CodeLoc, loc2: CodeLoc) -> CodeLoc {
     debug_assert_eq!(loc1.file, loc2.file);
-    l(loc1.start, loc2.end, loc1.file)
+    l(
+        loc1.start.min(loc2.start),
+        loc1.end.max(loc2.end),
+        loc1.file,
+    )
 }
 
 pub fn align_usize(size: usize, align: usize) -> usize {
@@ -436,6 +662,20 @@ impl Void {
     }
 }
 
+impl io::Read for Void {
+    fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+        Ok(0)
+    }
+}
+
+impl io::BufRead for Void {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        Ok(&[])
+    }
+
+    fn consume(&mut self, _amt: usize) {}
+}
+
 impl io::Write for Void {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         Ok(buf.len())
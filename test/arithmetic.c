@@ -0,0 +1,33 @@
+#include <limits.h>
+#include <stdio.h>
+
+int main() {
+  int a = 46340;
+  printf("%d\n", a * a);
+  printf("%d %d\n", 7 / 2, -7 / 2);
+  printf("%d\n", -(INT_MIN + 1));
+  printf("%d\n", INT_MAX - 1 + 1);
+  printf("%d %d %d\n", 17 % 5, -17 % 5, 17 % -5);
+
+  long big = LONG_MAX;
+  printf("%ld\n", big - 1);
+  printf("%ld\n", big / (0 - 1));
+  printf("%ld\n", a * (big / LONG_MAX));
+  printf("%ld\n", big % 1000);
+
+  unsigned int top = UINT_MAX;
+  unsigned int one = 1;
+  printf("%u\n", top + one);
+  printf("%u\n", one - top);
+  printf("%u\n", top * top);
+  printf("%u\n", top / 2);
+  printf("%u\n", top % 10);
+
+  // unsigned values wrap when stepped past either end
+  unsigned int was_one = one--;
+  unsigned int was_zero = one--;
+  printf("%u %u %u\n", was_one, was_zero, one);
+  long was_max = big--;
+  printf("%ld %ld\n", was_max, big++);
+  return 0;
+}
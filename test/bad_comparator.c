@@ -0,0 +1,12 @@
+#include <stdlib.h>
+
+int main() {
+  int values[3];
+  values[0] = 3;
+  values[1] = 1;
+  values[2] = 2;
+
+  // the comparator has to be a function, not the array itself
+  qsort(values, 3, sizeof(int), values);
+  return 0;
+}
@@ -0,0 +1,9 @@
+#include <stdlib.h>
+#include <string.h>
+
+int main() {
+  char *name = malloc(8);
+  strcpy(name, "a name that is too long");
+  free(name);
+  return 0;
+}
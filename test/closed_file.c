@@ -0,0 +1,9 @@
+#include <stdio.h>
+
+int main() {
+  FILE *log = fopen("log.txt", "w");
+  fprintf(log, "starting\n");
+  fclose(log);
+  fprintf(log, "done\n");
+  return 0;
+}
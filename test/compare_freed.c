@@ -0,0 +1,12 @@
+#include <stdlib.h>
+
+int main() {
+  int *data = malloc(16);
+  int *cursor = data + 2;
+  free(data);
+  if (cursor > data) {
+    return 1;
+  }
+
+  return 0;
+}
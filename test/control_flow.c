@@ -8,6 +8,19 @@ void print_a(int a) {
   }
 }
 
+// Loops that can only be left by returning don't need a return after them
+int first_multiple(int of, int above) {
+  for (int n = above + 1; 1; n = n + 1) {
+    for (int i = 0; 1; i = i + 1) {
+      break;
+    }
+
+    if (n % of == 0) {
+      return n;
+    }
+  }
+}
+
 int main() {
   for (int a = 0; a < 10; a = a + 1) {
     int b = a;
@@ -40,4 +53,6 @@ int main() {
     int c = 10;
     print_a(c);
   }
+
+  print_a(first_multiple(7, 30));
 }
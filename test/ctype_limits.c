@@ -0,0 +1,44 @@
+#include <assert.h>
+#include <ctype.h>
+#include <limits.h>
+#include <stdio.h>
+
+int main() {
+  char *text = "Hello, World 42!";
+  int letters = 0;
+  int digits = 0;
+  int spaces = 0;
+  int punct = 0;
+  for (int i = 0; text[i]; i = i + 1) {
+    if (isalpha(text[i])) {
+      letters = letters + 1;
+    }
+    if (isdigit(text[i])) {
+      digits = digits + 1;
+    }
+    if (isspace(text[i])) {
+      spaces = spaces + 1;
+    }
+    if (ispunct(text[i])) {
+      punct = punct + 1;
+    }
+  }
+
+  printf("letters %d, digits %d, spaces %d, punctuation %d\n", letters, digits,
+         spaces, punct);
+  fputc(toupper('a'), stdout);
+  fputc(tolower('B'), stdout);
+  fputc(toupper('1'), stdout);
+  printf(" %d\n", tolower(EOF));
+  printf("isxdigit %d %d, isupper %d, isprint %d\n", isxdigit('f'), isxdigit('g'),
+         isupper('Q'), isprint(7));
+
+  printf("int: %d to %d, unsigned up to %u\n", INT_MIN, INT_MAX, UINT_MAX);
+  printf("long: %ld to %ld\n", LONG_MIN, LONG_MAX);
+  printf("unsigned long up to %lu\n", ULONG_MAX);
+  printf("char: %d bits, %d to %d\n", CHAR_BIT, CHAR_MIN, CHAR_MAX);
+
+  assert(letters);
+  assert(text);
+  return 0;
+}
@@ -0,0 +1,12 @@
+#include <stdio.h>
+
+int average(int total, int count) {
+  return total / count;
+}
+
+int main() {
+  int scores = 0;
+  printf("%d\n", average(270, 3));
+  printf("%d\n", average(0, scores));
+  return 0;
+}
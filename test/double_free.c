@@ -0,0 +1,10 @@
+#include <stdlib.h>
+
+void cleanup(int *value) { free(value); }
+
+int main() {
+  int *value = malloc(sizeof(int));
+  *value = 12;
+  cleanup(value);
+  free(value);
+}
@@ -14,7 +14,7 @@
    *__dyn_array_len_ptr(*arr) - 1)
 
 #define dyn_array_add_from(arr, elems, len)                                    \
-  (/*ELEMENTS_ARE_WRONG_TYPE*/ elems - *arr,                                   \
+  (sizeof(/*ELEMENTS_ARE_WRONG_TYPE*/ elems - *arr),                           \
    __dyn_array_add_from(arr, sizeof(**arr), elems, len))
 
 uint64_t *__dyn_array_capacity_ptr(void *);
@@ -0,0 +1,13 @@
+#include <assert.h>
+
+int average(int total, int count) {
+  char grade = 'B';
+  assert(total - count > grade);
+  return total / count;
+}
+
+int main() {
+  int total = 10;
+  assert(total);
+  return average(total, 0);
+}
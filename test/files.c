@@ -0,0 +1,53 @@
+#include <stdio.h>
+#include <string.h>
+
+int main() {
+  FILE *scores = fopen("test/files/scores.txt", "r");
+  int count;
+  fscanf(scores, "%d", &count);
+
+  int total = 0;
+  for (int i = 0; i < count; i = i + 1) {
+    int score;
+    fscanf(scores, "%d", &score);
+    total = total + score;
+  }
+
+  char name[8];
+  fscanf(scores, "%7s", name);
+  if (fgetc(scores) == '\n') {
+    printf("read a whole line\n");
+  }
+  if (fgetc(scores) == EOF) {
+    printf("reached the end\n");
+  }
+  fclose(scores);
+
+  FILE *out = fopen("out.txt", "w");
+  fprintf(out, "%s: %d\n", name, total);
+  fputc('!', out);
+  fwrite("?\n", 1, 2, out);
+  fclose(out);
+
+  FILE *log = fopen("out.txt", "a");
+  fprintf(log, "appended\n");
+  fclose(log);
+
+  char buffer[32];
+  memset(buffer, 0, 32);
+  FILE *in = fopen("out.txt", "r");
+  size_t read = fread(buffer, 1, 31, in);
+  fclose(in);
+  printf("%lu bytes:\n%s", read, buffer);
+
+  FILE *missing = fopen("missing.txt", "r");
+  if (missing) {
+    printf("missing.txt exists\n");
+  } else {
+    printf("missing.txt doesn't exist\n");
+  }
+
+  fprintf(stdout, "to stdout\n");
+  fprintf(stderr, "to stderr\n");
+  return 0;
+}
@@ -0,0 +1,18 @@
+#include <stdio.h>
+
+int main() {
+  int x = 1;
+  unsigned long y = sizeof(x);
+  char c = 'a';
+  char *s = "hello";
+
+  printf("%d %lu %c %s %%\n", x, y, c, s);
+  printf("%*d|%.*d|\n", 4, x, 2, x);
+  printf("%d\n", y);
+  printf("%s\n", x);
+  printf("%d %d\n", x);
+  printf("%d\n", x, x);
+  printf("%q\n", x);
+
+  return 0;
+}
@@ -0,0 +1 @@
+#define scale(a) (a + FACTOR)
@@ -0,0 +1,12 @@
+#include <stdio.h>
+#include <point.h>
+#include "scale.h"
+
+int main() {
+  struct Point p;
+  p.x = 3;
+  p.y = 4;
+  printf("%d %d\n", scale(p.x), scale(p.y));
+  printf("%d\n", OFFSET);
+  return 0;
+}
@@ -0,0 +1,9 @@
+#include <limits.h>
+#include <stdio.h>
+
+int main() {
+  long visits = LONG_MAX;
+  long before = visits++;
+  printf("%ld\n", before);
+  return 0;
+}
@@ -0,0 +1,14 @@
+#include <stdio.h>
+
+int main() {
+  int values[5] = {3, 8, 1, 9, 4};
+  int sum = 0;
+  for (int i = 0; i < 5; i + 1) {
+    if (values[i] > 5) {
+      sum = sum + values[i];
+    }
+  }
+
+  printf("%d\n", sum);
+  return 0;
+}
@@ -0,0 +1,12 @@
+#include <limits.h>
+#include <stdio.h>
+
+int main() {
+  int total = INT_MAX - 2;
+  for (int i = 0; i < 5; i = i + 1) {
+    total = total + 1;
+    printf("%d\n", total);
+  }
+
+  return 0;
+}
@@ -0,0 +1,19 @@
+#include <stdlib.h>
+
+int *make_value(int value) {
+  int *ptr = malloc(sizeof(int));
+  *ptr = value;
+  return ptr;
+}
+
+int main() {
+  for (int i = 0; i < 3; i = i + 1) {
+    make_value(i);
+  }
+
+  int *buffer = malloc(32);
+  buffer[0] = 1;
+  char *freed = malloc(8);
+  free(freed);
+  return 0;
+}
@@ -0,0 +1,36 @@
+#include <math.h>
+#include <stdio.h>
+
+double hypotenuse(double a, double b) { return sqrt(a * a + b * b); }
+
+int main() {
+  double x = 2.5;
+  double y = -x * 2;
+  printf("%f %f\n", x, y);
+  printf("%.2f %e %g %g\n", x / 3, 123456.789, 0.0001, 1e20);
+  printf("%g %g %g\n", 100.0, 2.5e-7, 1.0 / 3);
+
+  int n = 7;
+  double half = n / 2.0;
+  int truncated = (int)half;
+  printf("%f %d\n", half, truncated);
+
+  if (x < half) {
+    printf("%f is less than %f\n", x, half);
+  }
+
+  printf("%f\n", hypotenuse(3, 4));
+  printf("%f %f\n", pow(2, 10), pow(2.0, 0.5));
+  printf("%f %f\n", fabs(-3.25), fabs(3.25));
+  printf("%f %f %f %f\n", floor(2.7), ceil(2.2), floor(-2.5), ceil(-2.5));
+  printf("%.4f %.4f\n", sin(M_PI / 2), cos(M_PI));
+  printf("%.4f %.4f\n", log(M_E), exp(1));
+  printf("%f %f\n", sqrt(-1), log(0));
+
+  double sum = 0;
+  for (int i = 1; i < 5; i = i + 1) {
+    sum = sum + 1.0 / i;
+  }
+  printf("%8.3f|%+.1f|%08.3f\n", sum, sum, -sum);
+  return 0;
+}
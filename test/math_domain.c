@@ -0,0 +1,14 @@
+#include <math.h>
+#include <stdio.h>
+
+int main() {
+  double area = 4;
+  for (int i = 0; i < 3; i = i + 1) {
+    double side = sqrt(area - 10);
+    printf("%f\n", side);
+  }
+
+  double scale = log(area - 4);
+  printf("%f\n", scale);
+  return 0;
+}
@@ -0,0 +1,22 @@
+#include <stdio.h>
+#include <string.h>
+
+struct person {
+  char name[8];
+  int age;
+};
+
+int main() {
+  struct person p;
+  p.age = 30;
+  strcpy(p.name, "Bob");
+  printf("%s is %d\n", p.name, p.age);
+
+  struct person *q = &p;
+  for (int i = 0; i < 10; i = i + 1) {
+    q->name[i] = 'x';
+  }
+
+  printf("%d\n", p.age);
+  return 0;
+}
@@ -0,0 +1,18 @@
+#include <stdio.h>
+
+struct person {
+  char name[8];
+  int age;
+};
+
+int main() {
+  struct person s;
+  s.age = 30;
+
+  char *p = s.name;
+  p[0] = 'B';
+  p[9] = 'x';
+
+  printf("%d\n", s.age);
+  return 0;
+}
@@ -0,0 +1,17 @@
+#include <stdio.h>
+#include <string.h>
+
+struct person {
+  char name[8];
+  int age;
+};
+
+int main() {
+  struct person s;
+  s.age = 30;
+
+  strcpy(s.name, "abcdefghij");
+
+  printf("%s is %d\n", s.name, s.age);
+  return 0;
+}
@@ -0,0 +1,25 @@
+int sign(int x) {
+  if (x < 0) {
+    return 0 - 1;
+  } else if (x > 0) {
+    return 1;
+  }
+}
+
+int both(int x) {
+  if (x) {
+    return 1;
+  } else {
+    return 0;
+  }
+}
+
+int looped(int x) {
+  for (int i = 0; i < x; i = i + 1) {
+    return i;
+  }
+}
+
+int main() {
+  return sign(1) + both(1) + looped(1);
+}
@@ -0,0 +1,24 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+struct node {
+  int value;
+  struct node *next;
+};
+
+struct node *push(struct node *list, int value) {
+  struct node *node = malloc(sizeof(struct node));
+  node->value = value;
+  node->next = list;
+  return node;
+}
+
+int main() {
+  struct node *list = push(0, 1);
+  list = push(list, 2);
+  printf("%d %d\n", list->value, list->next->value);
+
+  struct node *third = list->next->next;
+  printf("%d\n", third->value);
+  return 0;
+}
@@ -0,0 +1,10 @@
+#include <stdlib.h>
+#include <string.h>
+
+int main() {
+  char *buffer = malloc(16);
+  strcpy(buffer, "overlapping");
+  memcpy(buffer + 2, buffer, 8);
+  free(buffer);
+  return 0;
+}
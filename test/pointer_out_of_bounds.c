@@ -0,0 +1,18 @@
+int sum(int *values, int count) {
+  int total = 0;
+  int *end = values + count;
+  for (int *p = values; p < end; p = p + 1) {
+    total = total + *p;
+  }
+
+  return total;
+}
+
+int main() {
+  int scores[4];
+  for (int i = 0; i < 4; i = i + 1) {
+    scores[i] = i;
+  }
+
+  return sum(scores, 5);
+}
@@ -0,0 +1,27 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+int main() {
+  int values[5];
+  for (int i = 0; i < 5; i = i + 1) {
+    values[i] = i * 10;
+  }
+
+  int *end = values + 5;
+  for (int *p = values; p < end; p = p + 1) {
+    printf("%d ", *p);
+  }
+  printf("\n");
+
+  int *last = end - 1;
+  printf("%d %ld\n", *last, end - values);
+  int in_order = last >= values;
+  int same = values + 2 == last - 2;
+  printf("%d %d\n", in_order, same);
+
+  char *name = malloc(6);
+  char *cursor = name + 6;
+  printf("%ld\n", cursor - name);
+  free(name);
+  return 0;
+}
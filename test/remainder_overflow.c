@@ -0,0 +1,9 @@
+#include <limits.h>
+#include <stdio.h>
+
+int main() {
+  int lowest = INT_MIN;
+  int divisor = -1;
+  printf("%d\n", lowest % divisor);
+  return 0;
+}
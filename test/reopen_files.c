@@ -0,0 +1,39 @@
+#include <stdio.h>
+
+int main() {
+  // closing a file frees its descriptor for later calls to fopen
+  int failed = 0;
+  for (int i = 0; i < 300; i = i + 1) {
+    FILE *log = fopen("log.txt", "a");
+    if (log) {
+      fputc('.', log);
+      fclose(log);
+    } else {
+      failed = failed + 1;
+    }
+  }
+  printf("%d opens failed\n", failed);
+
+  FILE *log = fopen("log.txt", "r");
+  char dots[301];
+  size_t read = fread(dots, 1, 301, log);
+  printf("read %lu bytes\n", read);
+
+  // nothing is written when the items are empty
+  FILE *out = fopen("out.txt", "w");
+  size_t written = fwrite("abc", 0, 3, out);
+  printf("wrote %lu items\n", written);
+
+  fclose(out);
+  fclose(log);
+
+  // but only so many files can be open at once
+  int opened = 0;
+  for (int i = 0; i < 300; i = i + 1) {
+    if (fopen("log.txt", "r")) {
+      opened = opened + 1;
+    }
+  }
+  printf("opened %d files at once\n", opened);
+  return 0;
+}
@@ -0,0 +1,20 @@
+#include <stdlib.h>
+
+int compare_ints(void *a, void *b) {
+  int *left = a;
+  int *right = b;
+
+  // meant to wait for something, but nothing ever stops the loop
+  int tries = 0;
+  for (int i = 0; i < 10; i = (i + 1) % 10) {
+    tries = i;
+  }
+
+  return *left - *right;
+}
+
+int main() {
+  int values[3] = {3, 1, 2};
+  qsort(values, 3, sizeof(int), compare_ints);
+  return 0;
+}
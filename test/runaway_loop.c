@@ -0,0 +1,11 @@
+#include <stdio.h>
+
+int main() {
+  int total = 0;
+  for (int i = 10; i > 0; i = i + 1) {
+    total = total + i;
+  }
+
+  printf("%d\n", total);
+  return 0;
+}
@@ -0,0 +1,8 @@
+#include <stdio.h>
+
+int main() {
+  long total = 0;
+  scanf("%ld", total);
+  printf("%ld\n", total);
+  return 0;
+}
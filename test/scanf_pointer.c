@@ -0,0 +1,8 @@
+#include <stdio.h>
+
+int main() {
+  int count = 0;
+  scanf("%d", count);
+  printf("%d\n", count);
+  return 0;
+}
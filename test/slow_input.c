@@ -0,0 +1,18 @@
+#include <stdio.h>
+
+int main() {
+  int total = 0;
+  for (int round = 0; round < 3; round = round + 1) {
+    int value;
+    printf("value: ");
+    scanf("%d", &value);
+
+    // enough work that the time limit is checked between reads
+    for (int i = 0; i < 1000; i = i + 1) {
+      total = total + value;
+    }
+  }
+
+  printf("\ntotal: %d\n", total);
+  return 0;
+}
@@ -0,0 +1,10 @@
+#include <stdio.h>
+
+int sum_to(int n) {
+  return n + sum_to(n - 1);
+}
+
+int main() {
+  printf("%d\n", sum_to(10));
+  return 0;
+}
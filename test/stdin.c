@@ -0,0 +1,40 @@
+#include <stdio.h>
+
+int main() {
+  char name[16];
+  printf("name: ");
+  fgets(name, 16, stdin);
+  printf("hello, %s", name);
+
+  int a;
+  int b;
+  int count = scanf("%d + %d", &a, &b);
+  printf("%d items: %d\n", count, a + b);
+
+  int total = 0;
+  for (int i = 0; i < 3; i = i + 1) {
+    int value;
+    scanf("%d", &value);
+    total = total + value;
+  }
+  printf("total: %d\n", total);
+
+  char word[8];
+  scanf("%7s", word);
+  printf("word: %s\n", word);
+
+  int c = getchar();
+  printf("after word: %d\n", c);
+  count = scanf("%7s", word);
+  printf("%d words: %s\n", count, word);
+
+  c = getchar();
+  if (c == EOF) {
+    printf("end of input\n");
+  }
+
+  count = scanf("%d", &a);
+  printf("after the end: %d\n", count);
+
+  return 0;
+}
@@ -0,0 +1,84 @@
+#include <stdio.h>
+#include <stddef.h>
+#include <stdlib.h>
+
+int compare_ints(void *a, void *b) {
+  int *left = a;
+  int *right = b;
+  return *left - *right;
+}
+
+void goodbye() {
+  printf("goodbye\n");
+}
+
+void first() {
+  printf("registered first, runs last\n");
+}
+
+int main() {
+  atexit(first);
+  atexit(goodbye);
+
+  int *zeroes = calloc(4, sizeof(int));
+  printf("calloc: %d %d %d %d\n", zeroes[0], zeroes[1], zeroes[2], zeroes[3]);
+  free(zeroes);
+
+  size_t big = strtoul("4294967296", NULL, 10);
+  void *huge = calloc(big, big);
+  if (huge) {
+    printf("calloc overflow wasn't caught\n");
+  } else {
+    printf("calloc overflow: NULL\n");
+  }
+
+  printf("atoi: %d %d %d\n", atoi("42"), atoi("  -17xyz"), atoi("abc"));
+  printf("atol: %ld\n", atol("123456789012"));
+
+  char *end;
+  long hex = strtol("0x1F rest", &end, 0);
+  printf("strtol: %ld, rest '%s'\n", hex, end);
+  printf("strtol: %ld %ld\n", strtol("777", NULL, 8), strtol("-101", NULL, 2));
+  printf("strtol: %ld\n", strtol("99999999999999999999", NULL, 10));
+  printf("strtoul: %lu\n", strtoul("ff", NULL, 16));
+
+  printf("abs: %d %d, labs: %ld\n", abs(-5), abs(7), labs(atol("-9000000000")));
+
+  srand(1);
+  int r1 = rand();
+  int r2 = rand();
+  srand(1);
+  if (rand() == r1) {
+    printf("rand: %d %d, repeats after srand\n", r1, r2);
+  }
+
+  int values[8];
+  values[0] = 5;
+  values[1] = 3;
+  values[2] = 8;
+  values[3] = 1;
+  values[4] = 9;
+  values[5] = 2;
+  values[6] = 7;
+  values[7] = 3;
+  qsort(values, 8, sizeof(int), compare_ints);
+
+  printf("qsort:");
+  for (int i = 0; i < 8; i = i + 1) {
+    printf(" %d", values[i]);
+  }
+  printf("\n");
+
+  int key = 7;
+  int *found = bsearch(&key, values, 8, sizeof(int), compare_ints);
+  printf("bsearch: found %d, followed by %d\n", found[0], found[1]);
+
+  key = 4;
+  if (bsearch(&key, values, 8, sizeof(int), compare_ints)) {
+    printf("bsearch found a missing key\n");
+  } else {
+    printf("bsearch: 4 is missing\n");
+  }
+
+  return 0;
+}
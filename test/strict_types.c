@@ -0,0 +1,32 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+int main() {
+  long *values = malloc(4 * sizeof(long));
+  for (int i = 0; i < 4; i = i + 1) {
+    values[i] = i;
+  }
+
+  void *raw = values;
+  double *as_double = raw;
+  printf("%f\n", as_double[1]);
+
+  unsigned long *as_unsigned = raw;
+  printf("%lu\n", as_unsigned[2]);
+
+  char *bytes = raw;
+  int byte = bytes[8];
+  printf("%d\n", byte);
+
+  void *odd = bytes + 2;
+  int *misaligned = odd;
+  *misaligned = 7;
+
+  double d = 1.5;
+  memcpy(values + 3, &d, sizeof(double));
+  printf("%f\n", as_double[3]);
+
+  free(values);
+  return 0;
+}
@@ -0,0 +1,43 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+int main() {
+  char *greeting = malloc(32);
+  strcpy(greeting, "hello");
+  strcat(greeting, ", world");
+  printf("%s (%ld)\n", greeting, strlen(greeting));
+
+  char *copy = strdup(greeting);
+  printf("%d %d\n", strcmp(copy, greeting), strncmp(copy, "help", 3));
+  printf("%d\n", strcmp("abc", "abd"));
+
+  printf("%s\n", strchr(greeting, 'o'));
+  printf("%s\n", strrchr(greeting, 'o'));
+  printf("%s\n", strstr(greeting, "wor"));
+  char *missing = strstr(greeting, "xyz");
+  if (missing) {
+    printf("found %s\n", missing);
+  } else {
+    printf("not found\n");
+  }
+
+  char *part = malloc(8);
+  memset(part, 0, 8);
+  strncpy(part, greeting, 4);
+  strncat(part, "!!!", 2);
+  printf("%s\n", part);
+
+  memset(part, 'z', 3);
+  printf("%s\n", part);
+
+  memmove(greeting + 2, greeting, 5);
+  printf("%s\n", greeting);
+  memcpy(copy, "HELLO", 5);
+  printf("%s %d\n", copy, memcmp(copy, "HELLO", 5));
+
+  free(greeting);
+  free(copy);
+  free(part);
+  return 0;
+}
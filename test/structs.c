@@ -16,6 +16,10 @@ int main() {
   struct FileId file;
   struct FileId file2;
   struct FileId *file3;
+  struct Location empty;
+  empty.length = 0;
+  empty.cursor = 0;
+  file2.location = empty;
   file3 = &file2;
   file.ident = 12;
   file2.ident = ident_of(file);
@@ -0,0 +1,35 @@
+#include <stdio.h>
+
+struct Buffer {
+  int length;
+  int capacity;
+};
+
+int member() {
+  struct Buffer buf;
+  buf.length = 0;
+  return buf.lenght;
+}
+
+int local() {
+  int count = 1;
+  return cout + 1;
+}
+
+int function() {
+  return prinft("%d\n", 1);
+}
+
+int missing_include() {
+  void *data = malloc(10);
+  return 0;
+}
+
+int builtin() {
+  // tci's own builtins are never suggested
+  return tci_stream(1);
+}
+
+int main() {
+  return 0;
+}
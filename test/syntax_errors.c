@@ -0,0 +1,16 @@
+#include <stdio.h>
+
+int add(int a, int b) {
+  int c = a + ;
+  return c;
+}
+
+int main() {
+  int x = 1
+  printf("%d\n", add(x, 2));
+  if (x) {
+    x = (x + 2;
+  }
+
+  return 0;
+}
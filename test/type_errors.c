@@ -0,0 +1,10 @@
+#include <stdio.h>
+
+int add(int a, int b) { return a + missing; }
+
+int sub(int a, int b) { return a - b; }
+
+int main() {
+  printf("%d\n", sub(3, 1));
+  return add(1, nope);
+}
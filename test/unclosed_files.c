@@ -0,0 +1,12 @@
+#include <stdio.h>
+
+int main() {
+  FILE *first = fopen("first.txt", "w");
+  FILE *second = fopen("second.txt", "w");
+  FILE *third = fopen("third.txt", "w");
+  fputc('x', first);
+  fputc('y', second);
+  fputc('z', third);
+  fclose(second);
+  return 0;
+}
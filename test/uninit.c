@@ -0,0 +1,39 @@
+#include <stdio.h>
+
+int main() {
+  int a;
+  int b;
+  int c;
+  int d = 0;
+
+  printf("%d\n", a);
+
+  if (d == 0) {
+    b = 1;
+  }
+  printf("%d\n", b);
+
+  for (int i = 0; i < 3; i = i + 1) {
+    if (i == 2) {
+      c = i;
+    }
+  }
+  printf("%d\n", c);
+
+  int e;
+  if (d == 1) {
+    e = 1;
+  } else {
+    e = 2;
+  }
+  printf("%d\n", e);
+
+  int f;
+  for (int j = 0; j < 10; j = j + 1) {
+    f = 3;
+    break;
+  }
+  printf("%d\n", f);
+
+  return 0;
+}
@@ -0,0 +1,16 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+int main() {
+  int *counts = malloc(sizeof(int) * 4);
+  counts[0] = 1;
+  counts[2] = counts[0] + 1;
+
+  int total = counts[0] + counts[1];
+  if (total) {
+    printf("%d\n", total);
+  }
+
+  free(counts);
+  return 0;
+}
@@ -0,0 +1,10 @@
+#include <stdio.h>
+
+int main() {
+  char first[8];
+  char second[8];
+  char *a = first + 2;
+  char *b = second;
+  printf("%ld\n", a - b);
+  return 0;
+}
@@ -0,0 +1,9 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+int main() {
+  int *value = malloc(sizeof(int));
+  *value = 12;
+  free(value);
+  printf("%d\n", *value);
+}
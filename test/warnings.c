@@ -0,0 +1,33 @@
+#include <stdio.h>
+
+int helper(int used, int ignored) {
+  int unused;
+  return used;
+  used = 2;
+}
+
+int main() {
+  int x = 3;
+  unsigned long len = 4;
+  int n = len;
+  int ok = 100;
+
+  if (x = 4) {
+    printf("%d\n", x);
+  }
+
+  if (x < len) {
+    printf("%d\n", ok);
+  }
+
+  if (len >= 0) {
+    printf("%d\n", n);
+  }
+
+  if (x == 2);
+  {
+    printf("%d\n", x);
+  }
+
+  return helper(x, 1) - 4;
+}